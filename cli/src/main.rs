@@ -1,22 +1,34 @@
 use crate::analyzers::ReportConfig;
-use ariadne::sources;
-use clap::{ArgAction, Parser, ValueHint};
-use pyrometer::context::analyzers::FunctionVarsBoundAnalyzer;
+use ariadne::{sources, Cache};
+use clap::{error::ErrorKind, ArgAction, CommandFactory, Parser, ValueEnum, ValueHint};
+use ethers_core::types::U256;
+use pyrometer::context::analyzers::{
+    FunctionVarsBoundAnalyzer, InvariantAnalyzer, JsonError, JsonReport, PanicAnalyzer,
+    ReentrancyAnalyzer, SarifDisplay, SarifLog, UncheckedMathAnalyzer,
+};
+use pyrometer::context::exprs::ExprErr;
 use pyrometer::{
-    context::{analyzers::ReportDisplay, *},
-    Analyzer,
+    context::{
+        analyzers::ReportDisplay,
+        queries::{storage_write::AccessStorageWriteQuery, taint::TaintQuery},
+        *,
+    },
+    foundry::FoundryConfig,
+    parse_remappings, Analyzer,
 };
 
-use shared::nodes::FunctionNode;
+use shared::nodes::{Concrete, FunctionNode};
+use shared::range::SolcRange;
+use shared::smt::{SmtSolver, DEFAULT_SMT_TIMEOUT_MS};
 
 use shared::Edge;
 use shared::{
-    analyzer::{GraphLike, Search},
+    analyzer::{AnalyzerLike, GraphLike, Search},
     nodes::ContractNode,
 };
 use tracing_subscriber::prelude::*;
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::PathBuf;
 
 use std::env::{self};
@@ -25,12 +37,30 @@ use std::fs;
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// The path to the solidity file to process
-    #[clap(value_hint = ValueHint::FilePath, value_name = "PATH")]
+    /// The path to the solidity file to process, the root of a foundry project (i.e. the directory containing `foundry.toml`),
+    /// or a solc standard-JSON input file
+    ///
+    /// When passed a project root, every file in the project's `src` directory is analyzed in one run and remappings are
+    /// derived from `foundry.toml`, `remappings.txt` and the project's library directories.
+    ///
+    /// When passed a `.json` file, it is read as a solc standard-JSON input and every source in it is analyzed.
+    #[clap(value_hint = ValueHint::AnyPath, value_name = "PATH")]
     pub path: String,
     /// The path to the `remappings.txt` as per the output of `forge remappings`
     #[clap(long, short)]
     pub remappings: Option<String>,
+    /// When analyzing a foundry project, also analyze the files in the `test` and `script` directories
+    #[clap(long)]
+    pub include_tests: bool,
+    /// A directory to resolve direct (non-relative) imports against, as per solc's `--base-path`. Direct imports are
+    /// always resolved against the current directory (or the project root when analyzing a project) first
+    #[clap(long, value_hint = ValueHint::DirPath)]
+    pub base_path: Option<String>,
+    /// Additional directories to resolve direct imports against, as per solc's `--include-path`
+    ///
+    /// Can be passed multiple times, i.e. `--include-path node_modules --include-path lib`
+    #[clap(long, value_hint = ValueHint::DirPath)]
+    pub include_path: Vec<String>,
     /// Limit the output to just contracts that start with the name passed. i.e. `--contracts "MyCon"` woudl match "MyContract", "MyCon2", .. etc.
     ///
     /// Can be passed multiple times, i.e. `--contract "MyCon" --contract "MyOtherContract"`
@@ -80,18 +110,82 @@ struct Args {
     /// Show non-revert paths
     #[clap(long)]
     pub show_nonreverts: Option<bool>,
-    // #[clap(long, short)]
-    // pub access_query: Vec<String>,
-    // #[clap(long, short)]
-    // pub query: Vec<String>,
-    // #[clap(long, short)]
-    // pub write_query: Vec<String>,
+    /// The maximum number of iterations of a loop to unroll. Loops that may run for more iterations have the variables
+    /// modified in their body widened to their type's full range instead
+    #[clap(long, default_value = "10")]
+    pub max_loop_unroll: usize,
+    /// Apply the joined return values, storage writes and required conditions of already analyzed internal functions
+    /// at their call sites instead of evaluating them again. Only functions declared before the caller are summarized
+    #[clap(long)]
+    pub summarize_calls: bool,
+    /// Analyze the constructors first and start every other function from the storage they and the variables'
+    /// initializers leave behind, instead of from the full range of each storage variable's type. Writes by other
+    /// functions are not accounted for, so this models the first call to a freshly deployed contract
+    #[clap(long)]
+    pub storage_from_constructor: bool,
+    /// The path to a z3 or cvc5 binary. After each `require`, the conditions of the path are checked with the
+    /// solver and paths it proves unsatisfiable are marked unreachable, beyond what the ranges alone can show
+    #[clap(long, value_hint = ValueHint::FilePath)]
+    pub smt_solver: Option<PathBuf>,
+    /// The time the SMT solver gets to check each path, in milliseconds. Paths it can't decide in time are assumed
+    /// reachable
+    #[clap(long, default_value_t = DEFAULT_SMT_TIMEOUT_MS)]
+    pub smt_timeout: u64,
+    /// Assume every contract is part of the analyzed sources. External calls through a contract or interface type
+    /// are resolved to each deployable contract inheriting it, and each implementation is called on its own path
+    /// instead of returning unconstrained values
+    #[clap(long)]
+    pub closed_world: bool,
+    /// Infer and print the ranges each contract's storage variables stay within across any sequence of calls. Only
+    /// sound without `--storage-from-constructor`
+    #[clap(long)]
+    pub invariants: bool,
+    /// Report storage writes that follow an external call on the same path, and the functions that could be
+    /// reentered to observe the state before the write
+    #[clap(long)]
+    pub reentrancy: bool,
+    /// Report checked arithmetic that can never overflow and could be wrapped in `unchecked { }` to save gas, and
+    /// `unchecked` arithmetic that can overflow
+    #[clap(long)]
+    pub unchecked_math: bool,
+    /// Report arithmetic and array accesses that may or always will revert with a panic: overflows of checked
+    /// arithmetic (`0x11`), divisions or modulos by zero (`0x12`), `pop()`s of empty arrays (`0x31`) and out of
+    /// bounds indices (`0x32`)
+    #[clap(long)]
+    pub panics: bool,
+    /// The output format of the analysis. `json` and `sarif` print a single document to stdout with the bounds
+    /// analysis only, so they can't be combined with `--invariants`, `--reentrancy`, `--unchecked-math`, `--panics`,
+    /// `--query`, `--access-query` or `--write-query`
+    #[clap(long, value_enum, default_value_t = OutputFormat::Cli)]
+    pub format: OutputFormat,
+    /// Storage variables, as `Contract.variable`, to list the writes of. Reports each function that can write the
+    /// variable, a field or an element of it, and the conditions each write requires
+    #[clap(long, short)]
+    pub access_query: Vec<String>,
+    /// Storage variables to find the paths through a function that leave them with a target value, as
+    /// `Contract.function.variable=value` where the value is a `true`, `false` or an unsigned integer
+    #[clap(long, short)]
+    pub write_query: Vec<String>,
+    /// Contracts to run a taint analysis on. Reports where values a caller controls reach sensitive operations,
+    /// such as `delegatecall` targets, `selfdestruct` recipients, `sstore` slots, array indices and `transfer` amounts
+    #[clap(long, short)]
+    pub query: Vec<String>,
     /// A debugging command to prevent bound analysis printing. Useful for debugging parse errors during development. Only prints out parse errors
     /// then ends the program
     #[clap(long)]
     pub debug: bool,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable terminal reports
+    Cli,
+    /// Machine readable JSON
+    Json,
+    /// SARIF 2.1.0, for consumption by static analysis result viewers
+    Sarif,
+}
+
 pub fn subscriber() {
     tracing_subscriber::Registry::default()
         .with(tracing_subscriber::filter::EnvFilter::from_default_env())
@@ -99,9 +193,47 @@ pub fn subscriber() {
         .init()
 }
 
+/// Runs an analysis pass over each of the contracts and prints the reports it finds
+fn print_contract_reports<R: ReportDisplay>(
+    analyzer: &mut Analyzer,
+    contracts: &[ContractNode],
+    file_mapping: &BTreeMap<usize, String>,
+    source_map: &mut impl Cache<String>,
+    pass: impl Fn(&mut Analyzer, &BTreeMap<usize, String>, ContractNode) -> Result<Vec<R>, ExprErr>,
+) {
+    contracts.iter().for_each(|contract| {
+        let res = pass(analyzer, file_mapping, *contract);
+        if let Some(reports) = analyzer.add_if_err(res) {
+            reports
+                .iter()
+                .for_each(|report| report.print_reports(source_map, &*analyzer));
+        }
+    });
+}
+
 fn main() {
     subscriber();
     let args = Args::parse();
+    if args.format != OutputFormat::Cli {
+        let cli_only = [
+            ("--invariants", args.invariants),
+            ("--reentrancy", args.reentrancy),
+            ("--unchecked-math", args.unchecked_math),
+            ("--panics", args.panics),
+            ("--query", !args.query.is_empty()),
+            ("--access-query", !args.access_query.is_empty()),
+            ("--write-query", !args.write_query.is_empty()),
+            ("--dot", args.dot),
+        ];
+        if let Some((flag, _)) = cli_only.iter().find(|(_, set)| *set) {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!("{flag} is only reported with `--format cli`"),
+                )
+                .exit();
+        }
+    }
     let path_str = args.path.to_string();
     let verbosity = args.verbosity;
     let config = match verbosity {
@@ -203,25 +335,71 @@ fn main() {
         },
     };
 
-    let sol = fs::read_to_string(args.path.clone()).expect("Could not find file");
-
     let mut analyzer = Analyzer {
         root: env::current_dir().unwrap(),
+        max_loop_unroll: args.max_loop_unroll,
+        summarize_calls: args.summarize_calls,
+        storage_from_constructor: args.storage_from_constructor,
+        smt_solver: args
+            .smt_solver
+            .as_ref()
+            .map(|path| SmtSolver::from_path(path, args.smt_timeout)),
+        closed_world: args.closed_world,
         ..Default::default()
     };
-    if args.remappings.is_some() {
-        let remappings = args.remappings.unwrap();
-        analyzer.set_remappings_and_root(remappings);
-    }
+
+    analyzer.include_paths = args
+        .base_path
+        .iter()
+        .chain(args.include_path.iter())
+        .map(PathBuf::from)
+        .collect();
+
+    let path = PathBuf::from(args.path.clone());
     let t0 = std::time::Instant::now();
-    let (maybe_entry, mut all_sources) =
-        analyzer.parse(&sol, &PathBuf::from(args.path.clone()), true);
+    let (entries, all_sources) = if path.is_dir() {
+        let foundry_config = FoundryConfig::from_root(&path).unwrap_or_else(|e| {
+            eprintln!("Invalid foundry.toml in {path:?}: {e}");
+            std::process::exit(1)
+        });
+        if let Err(e) = analyzer.set_foundry_project(&foundry_config) {
+            eprintln!("Invalid remappings.txt in {path:?}: {e}");
+            std::process::exit(1)
+        }
+        if let Some(remappings) = args.remappings {
+            let mut remappings = parse_remappings(
+                &fs::read_to_string(remappings).expect("Remappings file not found"),
+            );
+            remappings.append(&mut analyzer.remappings);
+            analyzer.remappings = remappings;
+        }
+        analyzer
+            .parse_project(&foundry_config.source_files(args.include_tests))
+            .unwrap_or_else(|e| {
+                eprintln!("Could not parse the project in {path:?}: {e}");
+                std::process::exit(1)
+            })
+    } else if path.extension().map(|ext| ext == "json").unwrap_or(false) {
+        let json = fs::read_to_string(args.path.clone()).expect("Could not find file");
+        analyzer.parse_standard_json(&json).unwrap_or_else(|e| {
+            eprintln!("Invalid standard-JSON input {path:?}: {e}");
+            std::process::exit(1)
+        })
+    } else {
+        let sol = fs::read_to_string(args.path.clone()).expect("Could not find file");
+        if args.remappings.is_some() {
+            let remappings = args.remappings.unwrap();
+            analyzer.set_remappings_and_root(remappings);
+        }
+        let (maybe_entry, mut all_sources) = analyzer.parse(&sol, &path, true);
+        all_sources.push((maybe_entry, args.path, sol, 0));
+        (vec![maybe_entry.unwrap()], all_sources)
+    };
     let parse_time = t0.elapsed().as_millis();
 
-    println!("DONE ANALYZING IN: {parse_time}ms. Writing to cli...");
-
-    all_sources.push((maybe_entry, args.path, sol, 0));
-    let entry = maybe_entry.unwrap();
+    if args.format == OutputFormat::Cli {
+        println!("DONE ANALYZING IN: {parse_time}ms. Writing to cli...");
+    }
 
     let mut file_mapping: BTreeMap<_, _> = vec![(0usize, path_str)].into_iter().collect();
     file_mapping.extend(
@@ -239,8 +417,6 @@ fn main() {
     );
 
     // let t = petgraph::algo::toposort(&analyzer.graph, None);
-    analyzer.print_errors(&file_mapping, &mut source_map);
-
     if args.open_dot {
         analyzer.open_dot()
     }
@@ -250,17 +426,24 @@ fn main() {
     }
 
     if args.debug {
+        if args.format == OutputFormat::Cli {
+            analyzer.print_errors(&file_mapping, &mut source_map);
+        }
         return;
     }
 
-    let all_contracts = analyzer
-        .search_children(entry, &Edge::Contract)
-        .into_iter()
+    let all_contracts = entries
+        .iter()
+        .flat_map(|entry| analyzer.search_children(*entry, &Edge::Contract))
         .map(ContractNode::from)
         .collect::<Vec<_>>();
     let _t1 = std::time::Instant::now();
+    let mut analyses = vec![];
     if args.contracts.is_empty() {
-        let funcs = analyzer.search_children(entry, &Edge::Func);
+        let funcs = entries
+            .iter()
+            .flat_map(|entry| analyzer.search_children(*entry, &Edge::Func))
+            .collect::<BTreeSet<_>>();
         for func in funcs.into_iter() {
             if !args.funcs.is_empty() {
                 if args.funcs.iter().any(|analyze_for| {
@@ -270,17 +453,11 @@ fn main() {
                         .starts_with(analyze_for)
                 }) {
                     if let Some(ctx) = FunctionNode::from(func).maybe_body_ctx(&mut analyzer) {
-                        let analysis = analyzer
-                            .bounds_for_all(&file_mapping, ctx, config)
-                            .as_cli_compat(&file_mapping);
-                        analysis.print_reports(&mut source_map, &analyzer);
+                        analyses.push(analyzer.bounds_for_all(&file_mapping, ctx, config));
                     }
                 }
             } else if let Some(ctx) = FunctionNode::from(func).maybe_body_ctx(&mut analyzer) {
-                let analysis = analyzer
-                    .bounds_for_all(&file_mapping, ctx, config)
-                    .as_cli_compat(&file_mapping);
-                analysis.print_reports(&mut source_map, &analyzer);
+                analyses.push(analyzer.bounds_for_all(&file_mapping, ctx, config));
             }
         }
     } else {
@@ -298,58 +475,192 @@ fn main() {
                     if !args.funcs.is_empty() {
                         if args.funcs.contains(&func.name(&analyzer).unwrap()) {
                             let ctx = func.body_ctx(&mut analyzer);
-                            let analysis = analyzer
-                                .bounds_for_all(&file_mapping, ctx, config)
-                                .as_cli_compat(&file_mapping);
-                            analysis.print_reports(&mut source_map, &analyzer);
+                            analyses.push(analyzer.bounds_for_all(&file_mapping, ctx, config));
                         }
                     } else {
                         let ctx = func.body_ctx(&mut analyzer);
-                        let analysis = analyzer
-                            .bounds_for_all(&file_mapping, ctx, config)
-                            .as_cli_compat(&file_mapping);
-                        analysis.print_reports(&mut source_map, &analyzer);
+                        analyses.push(analyzer.bounds_for_all(&file_mapping, ctx, config));
                     }
                 }
             });
     }
 
-    // args.query.iter().for_each(|query| {
-    //     analyzer.taint_query(entry, query.to_string());
-    //     println!();
-    // });
+    if args.format == OutputFormat::Cli {
+        analyses.drain(..).for_each(|analysis| {
+            analysis
+                .as_cli_compat(&file_mapping)
+                .print_reports(&mut source_map, &analyzer);
+        });
+    }
+
+    let analyzed_contracts = all_contracts
+        .iter()
+        .copied()
+        .filter(|contract| {
+            args.contracts.is_empty() || args.contracts.contains(&contract.name(&analyzer).unwrap())
+        })
+        .collect::<Vec<_>>();
+
+    if args.invariants {
+        print_contract_reports(
+            &mut analyzer,
+            &analyzed_contracts,
+            &file_mapping,
+            &mut source_map,
+            |analyzer, file_mapping, contract| {
+                let analysis = analyzer.invariants_for(file_mapping, contract)?;
+                if analysis.invariants.is_empty() {
+                    Ok(vec![])
+                } else {
+                    Ok(vec![analysis])
+                }
+            },
+        );
+    }
+
+    if args.reentrancy {
+        print_contract_reports(
+            &mut analyzer,
+            &analyzed_contracts,
+            &file_mapping,
+            &mut source_map,
+            |analyzer, file_mapping, contract| analyzer.reentrancy_for(file_mapping, contract),
+        );
+    }
+
+    if args.unchecked_math {
+        print_contract_reports(
+            &mut analyzer,
+            &analyzed_contracts,
+            &file_mapping,
+            &mut source_map,
+            |analyzer, file_mapping, contract| analyzer.unchecked_math_for(file_mapping, contract),
+        );
+    }
+
+    if args.panics {
+        print_contract_reports(
+            &mut analyzer,
+            &analyzed_contracts,
+            &file_mapping,
+            &mut source_map,
+            |analyzer, file_mapping, contract| analyzer.panics_for(file_mapping, contract),
+        );
+    }
 
-    // args.access_query.iter().for_each(|query| {
-    //     let split: Vec<&str> = query.split('.').collect();
-    //     analyzer
-    //         .access_query(
-    //             entry,
-    //             &file_mapping,
-    //             config,
-    //             split[0].to_string(),
-    //             split[1].to_string(),
-    //         )
-    //         .print_reports(&mut source_map, &analyzer);
-    //     println!();
-    // });
+    args.query.iter().for_each(|query| {
+        let res = analyzer.taint_query(&entries, &file_mapping, query.to_string());
+        if let Some(report) = analyzer.add_if_err(res) {
+            if report.sinks.is_empty() {
+                println!("{}", report.msgs.join("\n"));
+            } else {
+                report.print_reports(&mut source_map, &analyzer);
+            }
+        }
+        println!();
+    });
 
-    // args.write_query.iter().for_each(|query| {
-    //     let split: Vec<&str> = query.split('.').collect();
-    //     if let Some(report) = analyzer.func_query(
-    //         entry,
-    //         &file_mapping,
-    //         config,
-    //         split[0].to_string(),
-    //         split[1].to_string(),
-    //         split[2].to_string(),
-    //         SolcRange::new(
-    //             Concrete::Bool(true).into(),
-    //             Concrete::Bool(true).into(),
-    //             vec![],
-    //         ),
-    //     ) {
-    //         report.print_reports(&mut source_map, &analyzer);
-    //     }
-    //     println!();
-    // });
+    args.access_query.iter().for_each(|query| {
+        let Some((contract_name, storage_var_name)) = query.split_once('.') else {
+            eprintln!("Access queries take the form `Contract.variable`, got `{query}`");
+            return;
+        };
+        let res = analyzer.access_query(
+            &entries,
+            &file_mapping,
+            contract_name.to_string(),
+            storage_var_name.to_string(),
+        );
+        if let Some(report) = analyzer.add_if_err(res) {
+            if report.writes.is_empty() {
+                println!("{}", report.msgs.join("\n"));
+            } else {
+                report.print_reports(&mut source_map, &analyzer);
+            }
+        }
+        println!();
+    });
+
+    args.write_query.iter().for_each(|query| {
+        let parsed = query.split_once('=').and_then(|(var, value)| {
+            let mut split = var.splitn(3, '.');
+            let target = match value {
+                "true" => Concrete::Bool(true),
+                "false" => Concrete::Bool(false),
+                _ => Concrete::Uint(256, U256::from_dec_str(value).ok()?),
+            };
+            Some((split.next()?, split.next()?, split.next()?, target))
+        });
+        let Some((contract_name, func_name, storage_var_name, target)) = parsed else {
+            eprintln!(
+                "Write queries take the form `Contract.function.variable=value`, got `{query}`"
+            );
+            return;
+        };
+        let res = analyzer.func_query(
+            &entries,
+            &file_mapping,
+            config,
+            contract_name.to_string(),
+            func_name.to_string(),
+            storage_var_name.to_string(),
+            SolcRange::new(target.clone().into(), target.into(), vec![]),
+        );
+        if let Some(reports) = analyzer.add_if_err(res) {
+            if reports.is_empty() {
+                println!(
+                    "No path through {contract_name}.{func_name} leaves {storage_var_name} with the target value"
+                );
+            }
+            reports
+                .iter()
+                .for_each(|report| report.print_reports(&mut source_map, &analyzer));
+        }
+        println!();
+    });
+
+    // the analyses above can add errors of their own
+    match args.format {
+        OutputFormat::Cli => analyzer.print_errors(&file_mapping, &mut source_map),
+        OutputFormat::Json => {
+            let functions = analyses
+                .iter()
+                .filter_map(|analysis| {
+                    let res = analysis.as_json(&file_mapping, &analyzer);
+                    analyzer.add_if_err(res)
+                })
+                .collect();
+            let report = JsonReport::new(
+                functions,
+                analyzer
+                    .expr_errs
+                    .iter()
+                    .map(|err| JsonError::new(&file_mapping, err))
+                    .collect(),
+            );
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+        OutputFormat::Sarif => {
+            let mut results = analyses
+                .into_iter()
+                .flat_map(|analysis| {
+                    analysis
+                        .as_cli_compat(&file_mapping)
+                        .sarif_results(&analyzer)
+                })
+                .collect::<Vec<_>>();
+            results.extend(
+                analyzer
+                    .expr_errs
+                    .iter()
+                    .map(|err| err.sarif_result(&file_mapping)),
+            );
+            let srcs = all_sources
+                .iter()
+                .map(|(_entry, name, src, _num)| (name.clone(), src.clone()))
+                .collect();
+            let log = SarifLog::new(results, &srcs);
+            println!("{}", serde_json::to_string_pretty(&log).unwrap());
+        }
+    }
 }
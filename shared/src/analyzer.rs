@@ -3,9 +3,12 @@ use crate::as_dot_str;
 use crate::FunctionParamNode;
 
 use crate::range::Range;
+use crate::range::SolcRange;
+use crate::smt::SmtSolver;
 use crate::BlockNode;
 
 use crate::MsgNode;
+use crate::VarNode;
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -46,6 +49,20 @@ pub trait AnalyzerLike: GraphLike {
     fn max_depth(&self) -> usize;
     /// Returns the configured max fork width
     fn max_width(&self) -> usize;
+    /// Returns the configured max number of iterations of a loop to unroll before widening instead
+    fn max_loop_unroll(&self) -> usize;
+    /// Returns whether calls of already analyzed internal functions are summarized instead of evaluated
+    fn summarize_calls(&self) -> bool;
+    /// Returns whether functions start from the storage the constructors and initializers leave behind, rather than
+    /// the full range of each variable's type
+    fn storage_from_constructor(&self) -> bool;
+    /// Gets the range each storage variable has when a function is called on a deployed contract
+    fn storage_entry(&self) -> &BTreeMap<VarNode, SolcRange>;
+    /// Gets the SMT solver used to check whether a context's dependencies are satisfiable, if one is configured
+    fn smt_solver(&self) -> Option<&SmtSolver>;
+    /// Returns whether external calls through a contract or interface type are resolved to the implementations of
+    /// the deployable contracts inheriting it
+    fn closed_world(&self) -> bool;
     fn builtin_fn_inputs(&self) -> &HashMap<String, (Vec<FunctionParam>, Vec<FunctionReturn>)>;
     fn builtins(&self) -> &HashMap<Builtin, NodeIdx>;
     fn builtins_mut(&mut self) -> &mut HashMap<Builtin, NodeIdx>;
@@ -3,11 +3,19 @@ use crate::analyzer::{AnalyzerLike, GraphLike, Search};
 use crate::as_dot_str;
 use crate::nodes::FunctionNode;
 
+use crate::range::elem::{RangeElem, RangeOp};
+use crate::range::elem_ty::Elem;
+use crate::range::{Range, SolcRange};
 use crate::AsDotStr;
 use crate::ContractNode;
+use crate::ErrorNode;
+use crate::EventNode;
 use crate::FunctionParamNode;
+use crate::MsgNode;
 use crate::StructNode;
+use ethers_core::types::U256;
 use petgraph::dot::Dot;
+use petgraph::{visit::EdgeRef, Direction};
 use std::collections::BTreeSet;
 
 use crate::{Edge, Node, NodeIdx};
@@ -107,6 +115,118 @@ impl ModifierState {
     }
 }
 
+/// An `emit` of an event. The arguments are the versions of the variables at the time of the emit
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ContextEmit {
+    pub loc: Loc,
+    pub event: EventNode,
+    pub args: Vec<ContextVarNode>,
+}
+
+/// A side effect of a context that matters to other contracts: a low level call out of the contract
+/// or a write of its storage
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ContextEffect {
+    /// A low level `call` or `delegatecall` of an address
+    LowLevelCall { loc: Loc, kind: String },
+    /// An assignment to a storage variable. The variable is the new version
+    StorageWrite { loc: Loc, var: ContextVarNode },
+}
+
+/// An operation whose behavior a caller shouldn't control
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum SinkKind {
+    /// The address a `delegatecall` runs the code of
+    DelegatecallTarget,
+    /// The address `selfdestruct` sends the contract's balance to
+    SelfdestructRecipient,
+    /// The slot of a yul `sstore`
+    SstoreSlot,
+    /// The index into an array
+    ArrayIndex,
+    /// The amount of a `transfer` or `send`
+    TransferAmount,
+}
+
+impl SinkKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SinkKind::DelegatecallTarget => "delegatecall target",
+            SinkKind::SelfdestructRecipient => "selfdestruct recipient",
+            SinkKind::SstoreSlot => "sstore slot",
+            SinkKind::ArrayIndex => "array index",
+            SinkKind::TransferAmount => "transfer amount",
+        }
+    }
+}
+
+/// A value flowing into a sensitive operation. The variable is the version at the time of the operation
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ContextSink {
+    pub loc: Loc,
+    pub kind: SinkKind,
+    pub var: ContextVarNode,
+}
+
+/// An arithmetic operation on integers that can panic: an addition, subtraction, multiplication, exponentiation or
+/// negation, which reverts on overflow unless it is in an `unchecked` block, or a division or modulo, which reverts on
+/// a zero denominator. A negation `-x` is recorded as a subtraction from a zero of `x`'s type. The operands are the
+/// versions at the time of the operation
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ContextArith {
+    pub loc: Loc,
+    pub op: RangeOp,
+    /// Whether the operation is in an `unchecked` block. Divisions and modulos are checked regardless
+    pub unchecked: bool,
+    pub lhs: ContextVarNode,
+    /// The right hand side, or `None` for a `++` or `--`
+    pub rhs: Option<ContextVarNode>,
+    /// The range of the right hand side at the time of the operation. A division constrains its denominator to be
+    /// nonzero, which can narrow the right hand side in place, so its range is copied rather than read later
+    pub rhs_range: Option<SolcRange>,
+}
+
+/// An access of an array that panics when out of bounds: an index access, which reverts with `Panic(0x32)` for an
+/// index past the end, or a `pop()`, which reverts with `Panic(0x31)` on an empty array. The variables are the
+/// versions at the time of the access
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ContextArrayAccess {
+    pub loc: Loc,
+    /// The length of the array
+    pub len: ContextVarNode,
+    /// The index, or `None` for a `pop()`
+    pub index: Option<ContextVarNode>,
+}
+
+/// The effects of calling an already analyzed function, instantiated for a call site by
+/// [`ContextNode::join`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FunctionSummary {
+    /// The return values of the function
+    pub rets: Vec<ContextVarNode>,
+    /// The new values of the storage variables the function writes, by name
+    pub storage: Vec<(String, ContextVarNode)>,
+    /// The conditions every successful path of the function puts on the arguments and storage, as a left hand
+    /// side, a comparison and a right hand side, which the call has to meet in turn
+    pub requires: Vec<(ContextVarNode, RangeOp, ContextVarNode)>,
+}
+
+/// A `break` or `continue` that ended the current iteration of a loop
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LoopControl {
+    Break,
+    Continue,
+}
+
+/// A `revert` with a reason. The error is `None` for `revert("reason")`, i.e. a revert with an
+/// `Error(string)`. The arguments are the versions of the variables at the time of the revert
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ContextRevert {
+    pub loc: Loc,
+    pub error: Option<ErrorNode>,
+    pub args: Vec<ContextVarNode>,
+}
+
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
 pub struct ContextCache {
     pub vars: BTreeMap<String, ContextVarNode>,
@@ -147,6 +267,40 @@ pub struct Context {
     pub loc: Loc,
     /// The return node and the return location
     pub ret: Vec<(Loc, ContextVarNode)>,
+    /// The events emitted in this context
+    pub emits: Vec<ContextEmit>,
+    /// The low level calls and storage writes of this context, in order
+    pub effects: Vec<ContextEffect>,
+    /// The values flowing into sensitive operations in this context
+    pub sinks: Vec<ContextSink>,
+    /// The integer arithmetic of this context that can panic
+    pub ariths: Vec<ContextArith>,
+    /// The index accesses and `pop()`s of arrays in this context
+    pub array_accesses: Vec<ContextArrayAccess>,
+    /// The values passed to `abi.encode`, keyed by the resulting bytes, so that decoding them gives
+    /// back the values
+    pub abi_encodings: BTreeMap<ContextVarNode, Vec<ContextVarNode>>,
+    /// The words inline assembly stored at constant memory offsets, so that a yul `keccak256` of them can be traced
+    /// back to them
+    pub memory_words: BTreeMap<U256, ContextVarNode>,
+    /// The words of memory hashed by yul `keccak256`, keyed by the resulting hash, so that the storage slot of a
+    /// mapping or dynamic array element can be traced back to its key or array
+    pub hashes: BTreeMap<ContextVarNode, Vec<ContextVarNode>>,
+    /// The environment of the external call this context runs in, or `None` if it runs in the transaction's
+    pub msg: Option<MsgNode>,
+    /// The `msg.sender` and `msg.value` variables of the external call this context runs in, keyed by name, so that
+    /// the modifiers and internal calls of the call see them
+    pub frame_vars: BTreeMap<String, ContextVarNode>,
+    /// The `{value: ..}` options of the external calls being set up in this context, keyed by the location of
+    /// the called function's name
+    pub call_values: BTreeMap<Loc, ContextVarNode>,
+    /// The error this context reverted with, if it reverted with a reason
+    pub reverts_with: Option<ContextRevert>,
+    /// The calls of this context that were summarized instead of evaluated, and the functions they call
+    pub summarized_calls: Vec<(Loc, FunctionNode)>,
+    /// Denotes whether this context hit a `break` or `continue`. No further statements are evaluated in
+    /// the context until the enclosing loop takes it
+    pub loop_control: Option<LoopControl>,
     /// Depth tracker
     pub depth: usize,
     /// Width tracker
@@ -176,6 +330,20 @@ impl Context {
             ext_fn_call: None,
             child: None,
             ret: vec![],
+            emits: vec![],
+            effects: vec![],
+            sinks: vec![],
+            ariths: vec![],
+            array_accesses: vec![],
+            abi_encodings: Default::default(),
+            memory_words: Default::default(),
+            hashes: Default::default(),
+            msg: None,
+            frame_vars: Default::default(),
+            call_values: Default::default(),
+            reverts_with: None,
+            summarized_calls: vec![],
+            loop_control: None,
             loc,
             modifier_state: None,
             depth: 0,
@@ -267,6 +435,34 @@ impl Context {
             child: None,
             tmp_var_ctr: parent_ctx.underlying(analyzer)?.tmp_var_ctr,
             ret: vec![],
+            emits: vec![],
+            effects: vec![],
+            sinks: vec![],
+            ariths: vec![],
+            array_accesses: vec![],
+            abi_encodings: parent_ctx.underlying(analyzer)?.abi_encodings.clone(),
+            memory_words: parent_ctx.underlying(analyzer)?.memory_words.clone(),
+            hashes: parent_ctx.underlying(analyzer)?.hashes.clone(),
+            msg: if let Some(ret_ctx) = returning_ctx {
+                ret_ctx.underlying(analyzer)?.msg
+            } else {
+                parent_ctx.underlying(analyzer)?.msg
+            },
+            frame_vars: if let Some(ret_ctx) = returning_ctx {
+                ret_ctx.underlying(analyzer)?.frame_vars.clone()
+            } else {
+                parent_ctx.underlying(analyzer)?.frame_vars.clone()
+            },
+            call_values: if fork_expr.is_some() {
+                parent_ctx.underlying(analyzer)?.call_values.clone()
+            } else if let Some(ret_ctx) = returning_ctx {
+                ret_ctx.underlying(analyzer)?.call_values.clone()
+            } else {
+                Default::default()
+            },
+            reverts_with: None,
+            summarized_calls: vec![],
+            loop_control: None,
             loc,
             modifier_state,
             depth,
@@ -377,62 +573,449 @@ impl ContextNode {
     //     }).collect()
     // }
 
+    /// Summarizes a call of an already analyzed function from this context, so that its body doesn't
+    /// have to be evaluated again. The return values and storage writes of the function's successful
+    /// paths are instantiated with the arguments of the call and this context's storage, and joined
+    /// across paths.
+    ///
+    /// The conditions the function requires of its parameters and the storage it reads, by a `require`
+    /// or by checked arithmetic, are instantiated the same way and returned for the call to require in
+    /// turn, so that a call whose arguments can't meet them kills the calling path. Only the conditions
+    /// every successful path shares are returned, so a condition that only some paths take (e.g. a branch)
+    /// is dropped and the joined values cover both sides of it. The conditions are applied to the arguments
+    /// after the return values are instantiated, so the return values keep the bounds the function narrowed
+    /// its parameters to rather than the arguments' narrowed bounds.
+    ///
+    /// Functions whose array accesses can panic are evaluated instead, as their bounds checks aren't
+    /// conditions a summary can carry. So are functions with operations the analyzers judge on the
+    /// evaluated paths, i.e. sensitive operations, and functions accessing elements or members of storage
+    /// variables, which are named after the function's own index expressions and can't be mapped onto this
+    /// context's variables.
+    ///
+    /// Returns `None` if the function can't be summarized and has to be evaluated instead, i.e. it hasn't
+    /// been analyzed yet, is being called recursively, has modifiers, is or makes an external call,
+    /// emits events, always reverts, requires a condition on something other than its parameters, storage
+    /// and constants (e.g. `msg.sender`), or is excluded above
     pub fn join(
         &self,
-        _func: FunctionNode,
-        _mapping: &BTreeMap<ContextVarNode, FunctionParamNode>,
-        _analyzer: &mut (impl GraphLike + AnalyzerLike),
-    ) {
-        todo!("Joining not supported yet");
-        // println!("joining");
-        // if let Some(body_ctx) = func.maybe_body_ctx(analyzer) {
-        //     let vars: Vec<_> = body_ctx.vars(analyzer).values().map(|var| var.latest_version(analyzer)).collect();
-        //     println!("vars: {vars:#?}");
-        //     let replacements: Vec<(ContextVarNode, ContextVarNode)> = mapping.iter().filter_map(|(input_var, param)| {
-        //         vars.iter().find(|var| var.name(analyzer).unwrap() == param.name(analyzer).unwrap()).map(|var| {
-        //             (*var, *input_var)
-        //         })
-        //     }).collect();
-
-        //     let mut mapping = BTreeMap::default();
-        //     replacements.into_iter().for_each(|(var, replacement)| {
-        //         mapping.insert(var, replacement);
-        //         let mut latest = var;
-        //         while let Some(next) = latest.next_version(analyzer) {
-        //             latest = next;
-        //             mapping.insert(latest, replacement);
-        //         }
-        //     });
-
-        //     println!("mapping: {mapping:#?}");
-
-        //     vars.iter().for_each(|var| {
-        //         let mut latest = *var;
-        //         let mut range = latest.range(analyzer).unwrap().unwrap();
-        //         println!("var: {var:?}, depends on: {:#?}, {range:#?}", var.range_deps(analyzer));
-        //         range.uncache_range_min();
-        //         range.uncache_range_max();
-        //         mapping.iter().for_each(|(to_replace, replacement)| {
-        //             // range.filter_min_recursion((*to_replace).into(), (*replacement).into());
-        //             // range.filter_max_recursion((*to_replace).into(), (*replacement).into());
-        //         });
-        //         latest.set_range(analyzer, range).unwrap();
-        //         while let Some(next) = latest.next_version(analyzer) {
-        //             latest = next;
-        //             let mut range = latest.range(analyzer).unwrap().unwrap();
-        //             range.uncache_range_min();
-        //             range.uncache_range_max();
-        //             mapping.iter().for_each(|(to_replace, replacement)| {
-        //                 // range.filter_min_recursion((*to_replace).into(), (*replacement).into());
-        //                 // range.filter_max_recursion((*to_replace).into(), (*replacement).into());
-        //             });
-        //             latest.set_range(analyzer, range).unwrap();
-        //         }
-        //     });
+        loc: Loc,
+        func: FunctionNode,
+        mapping: &BTreeMap<ContextVarNode, FunctionParamNode>,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<Option<FunctionSummary>, GraphError> {
+        let Some(body_ctx) = func.maybe_body_ctx(analyzer) else {
+            return Ok(None);
+        };
+        if !func.modifiers_set(analyzer)?
+            || !func.modifiers(analyzer).is_empty()
+            || self.underlying(analyzer)?.modifier_state.is_some()
+            || self.is_fn_ext(func, analyzer)?
+        {
+            return Ok(None);
+        }
+        if *self == body_ctx || self.parent_list(analyzer)?.contains(&body_ctx) {
+            // the function is still being analyzed
+            return Ok(None);
+        }
 
-        // } else {
-        //     // need to process the function
-        // }
+        // external calls are left to evaluating the function, so that they keep their place among the
+        // caller's storage writes
+        let mut ctxs = vec![body_ctx];
+        ctxs.extend(body_ctx.descendants(analyzer)?);
+        for ctx in ctxs {
+            let underlying = ctx.underlying(analyzer)?;
+            if underlying.ext_fn_call.is_some()
+                || underlying
+                    .effects
+                    .iter()
+                    .any(|effect| matches!(effect, ContextEffect::LowLevelCall { .. }))
+                || !underlying.array_accesses.is_empty()
+                || !underlying.sinks.is_empty()
+            {
+                return Ok(None);
+            }
+            for var in ctx.vars(analyzer).values() {
+                if let Some(root) = var.element_root(analyzer) {
+                    if root.is_storage(analyzer)? {
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+
+        let num_rets = func.returns(analyzer).count();
+        let mut paths = vec![];
+        let mut shared_deps: Option<BTreeSet<ContextVarNode>> = None;
+        let mut dep_spans = BTreeSet::default();
+        for leaf in body_ctx.successful_leaves(analyzer)? {
+            let underlying = leaf.underlying(analyzer)?;
+            // implicit returns of named return variables and emits are left to evaluating the function
+            if underlying.ret.len() != num_rets || !leaf.path_emits(analyzer)?.is_empty() {
+                return Ok(None);
+            }
+            // a path inherits the conditions of the paths it forked from, so the conditions every path
+            // shares are the same variables
+            let deps = underlying
+                .ctx_deps
+                .values()
+                .copied()
+                .collect::<BTreeSet<_>>();
+            for dep in deps.iter() {
+                if let Some(Loc::File(file, start, end)) = dep.underlying(analyzer)?.loc {
+                    dep_spans.insert((file, start, end));
+                }
+            }
+            shared_deps = Some(match shared_deps {
+                Some(shared) => shared.intersection(&deps).copied().collect(),
+                None => deps,
+            });
+            paths.push(leaf);
+        }
+        if paths.is_empty() {
+            return Ok(None);
+        }
+
+        // map the parameters onto the arguments and the storage the function reads onto this context's
+        let mut var_mapping = BTreeMap::default();
+        let mut unchanged = BTreeSet::default();
+        for (input, param) in mapping.iter() {
+            let Some(name) = param.maybe_name(analyzer)? else {
+                continue;
+            };
+            if let Some(param_var) = body_ctx.var_by_name(analyzer, &name) {
+                let versions = param_var.unchanged_versions(analyzer)?;
+                let input = input.latest_version(analyzer);
+                versions.iter().for_each(|version| {
+                    var_mapping.insert(*version, input);
+                });
+                unchanged.extend(versions);
+            }
+        }
+
+        let mut storage_roots: BTreeMap<String, ContextVarNode> = BTreeMap::default();
+        let mut ctxs = vec![body_ctx];
+        ctxs.extend(body_ctx.descendants(analyzer)?);
+        for ctx in ctxs {
+            for var in ctx.vars(analyzer).clone().into_values() {
+                if !var.is_storage(analyzer)?
+                    || var.previous_or_inherited_version(analyzer).is_some()
+                {
+                    continue;
+                }
+                let name = var.name(analyzer)?;
+                let versions = var.unchanged_versions(analyzer)?;
+                if let Some(caller_var) = self.var_by_name_or_recurse(analyzer, &name)? {
+                    let caller_var = caller_var.latest_version(analyzer);
+                    versions.iter().for_each(|version| {
+                        var_mapping.insert(*version, caller_var);
+                    });
+                }
+                unchanged.extend(versions);
+                storage_roots.entry(name).or_insert(var);
+            }
+        }
+
+        let call_vars = var_mapping.keys().copied().collect::<BTreeSet<_>>();
+        let mut requires = vec![];
+        for dep in shared_deps.unwrap_or_default() {
+            let Some(tmp) = dep.tmp_of(analyzer)? else {
+                return Ok(None);
+            };
+            let (Some(rhs), Some(_)) = (tmp.rhs, tmp.op.require_parts()) else {
+                return Ok(None);
+            };
+            // conditions narrow new versions of their sides, so a condition is put on the versions from
+            // before any condition narrowed them, i.e. the versions made outside of the conditions' expressions
+            let mut sides = vec![];
+            for mut side in [tmp.lhs, rhs] {
+                while !call_vars.contains(&side) {
+                    let Some(prev) = side.previous_version(analyzer) else {
+                        break;
+                    };
+                    let Some(Loc::File(file, start, end)) = side.underlying(analyzer)?.loc else {
+                        break;
+                    };
+                    if !dep_spans.iter().any(|(dep_file, dep_start, dep_end)| {
+                        file == *dep_file && start >= *dep_start && end <= *dep_end
+                    }) {
+                        break;
+                    }
+                    side = prev;
+                }
+                sides.push(side);
+            }
+            let mut roots = BTreeSet::default();
+            for side in sides.iter() {
+                roots.extend(Self::range_roots(*side, &call_vars, analyzer)?);
+            }
+            let mut depends_on_call = false;
+            for root in roots {
+                if call_vars.contains(&root) {
+                    depends_on_call = true;
+                } else if !matches!(analyzer.node(root), Node::ContextVar(..))
+                    || !root.is_const(analyzer)?
+                {
+                    return Ok(None);
+                }
+            }
+            // a condition on constants alone already held when the function was analyzed
+            if depends_on_call {
+                let lhs = self.instantiate_var(loc, sides[0], &mut var_mapping, analyzer)?;
+                let rhs = self.instantiate_var(loc, sides[1], &mut var_mapping, analyzer)?;
+                if !requires.contains(&(lhs, tmp.op, rhs)) {
+                    requires.push((lhs, tmp.op, rhs));
+                }
+            }
+        }
+
+        let mut path_rets = vec![vec![]; num_rets];
+        let mut path_storage: BTreeMap<String, Vec<ContextVarNode>> = BTreeMap::default();
+        let mut written = BTreeSet::default();
+        for path in paths {
+            let rets = path.underlying(analyzer)?.ret.clone();
+            for (i, (_, ret)) in rets.into_iter().enumerate() {
+                let ret = self.instantiate_var(loc, ret, &mut var_mapping, analyzer)?;
+                path_rets[i].push(ret);
+            }
+
+            for (name, root) in storage_roots.iter() {
+                let value = match path.var_by_name_or_recurse(analyzer, name)? {
+                    Some(var) => {
+                        let var = var.latest_version(analyzer);
+                        if !unchanged.contains(&var) {
+                            written.insert(name.clone());
+                        }
+                        self.instantiate_var(loc, var, &mut var_mapping, analyzer)?
+                    }
+                    // this path didn't read the variable
+                    None => *var_mapping.get(root).unwrap_or(root),
+                };
+                path_storage.entry(name.clone()).or_default().push(value);
+            }
+        }
+
+        let rets = path_rets
+            .into_iter()
+            .map(|vars| self.join_vars(loc, vars, analyzer))
+            .collect::<Result<Vec<_>, GraphError>>()?;
+        let storage = path_storage
+            .into_iter()
+            .filter(|(name, _)| written.contains(name))
+            .map(|(name, vars)| Ok((name, self.join_vars(loc, vars, analyzer)?)))
+            .collect::<Result<Vec<_>, GraphError>>()?;
+        Ok(Some(FunctionSummary {
+            rets,
+            storage,
+            requires,
+        }))
+    }
+
+    /// Evaluates the storage a finished function context leaves behind when it is entered with the given storage
+    /// variables of this context, i.e. the effects of a transaction on a contract's state. Each variable's new value
+    /// is joined over the function's successful paths, and is `None` if the function writes it somewhere its
+    /// successful paths can't see (i.e. in a modifier). Returns `Ok(None)` if no path succeeds
+    pub fn storage_effects(
+        &self,
+        loc: Loc,
+        body_ctx: ContextNode,
+        storage: &BTreeMap<String, ContextVarNode>,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<Option<BTreeMap<String, Option<ContextVarNode>>>, GraphError> {
+        let paths = body_ctx.successful_leaves(analyzer)?;
+        if paths.is_empty() {
+            return Ok(None);
+        }
+
+        // map the storage the function reads onto the given storage
+        let mut var_mapping = BTreeMap::default();
+        let mut unchanged = BTreeSet::default();
+        let mut ctxs = vec![body_ctx];
+        ctxs.extend(body_ctx.descendants(analyzer)?);
+        let mut storage_vars = vec![];
+        for ctx in ctxs {
+            for var in ctx.vars(analyzer).clone().into_values() {
+                if !var.is_storage(analyzer)? {
+                    continue;
+                }
+                storage_vars.push(var);
+                if var.previous_or_inherited_version(analyzer).is_some() {
+                    continue;
+                }
+                let versions = var.unchanged_versions(analyzer)?;
+                if let Some(state_var) = storage.get(&var.name(analyzer)?) {
+                    versions.iter().for_each(|version| {
+                        var_mapping.insert(*version, *state_var);
+                    });
+                }
+                unchanged.extend(versions);
+            }
+        }
+        let mut written = BTreeSet::default();
+        for var in storage_vars {
+            if !unchanged.contains(&var.latest_version(analyzer)) {
+                written.insert(var.name(analyzer)?);
+            }
+        }
+
+        let mut values: BTreeMap<String, Vec<ContextVarNode>> = BTreeMap::default();
+        let mut unknown = BTreeSet::default();
+        for path in paths {
+            for (name, state_var) in storage.iter() {
+                match path.storage_var_by_name(analyzer, name)? {
+                    Some(var) => {
+                        let value = self.instantiate_var(loc, var, &mut var_mapping, analyzer)?;
+                        values.entry(name.clone()).or_default().push(value);
+                    }
+                    None if written.contains(name) => {
+                        unknown.insert(name.clone());
+                    }
+                    // this path didn't access the variable
+                    None => values.entry(name.clone()).or_default().push(*state_var),
+                }
+            }
+        }
+
+        let mut effects = BTreeMap::default();
+        for (name, vars) in values {
+            let value = if unknown.contains(&name) {
+                None
+            } else {
+                Some(self.join_vars(loc, vars, analyzer)?)
+            };
+            effects.insert(name, value);
+        }
+        for name in unknown {
+            effects.entry(name).or_insert(None);
+        }
+        Ok(Some(effects))
+    }
+
+    /// Recreates a variable of another context in this context, replacing the variables its range depends on
+    /// (recursively) by the variables they are mapped to
+    fn instantiate_var(
+        &self,
+        loc: Loc,
+        var: ContextVarNode,
+        mapping: &mut BTreeMap<ContextVarNode, ContextVarNode>,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<ContextVarNode, GraphError> {
+        if let Some(mapped) = mapping.get(&var) {
+            return Ok(*mapped);
+        }
+        if !matches!(analyzer.node(var), Node::ContextVar(..)) {
+            return Ok(var);
+        }
+        let Some(range) = var.ref_range(analyzer)?.map(|range| range.into_owned()) else {
+            mapping.insert(var, var);
+            return Ok(var);
+        };
+
+        // a `require` between two symbolic variables makes their ranges depend on each other, so a variable
+        // stays itself while its own dependencies are instantiated
+        mapping.insert(var, var);
+        let mut dep_mapping = BTreeMap::default();
+        for dep in range.dependent_on() {
+            let new_dep = self.instantiate_var(loc, dep, mapping, analyzer)?;
+            if new_dep != dep {
+                dep_mapping.insert(dep, new_dep);
+            }
+        }
+
+        let new_var = if dep_mapping.is_empty() {
+            var
+        } else {
+            let new_var = var.as_tmp(loc, *self, analyzer)?;
+            analyzer.add_edge(new_var, *self, Edge::Context(ContextEdge::Variable));
+            let mut min = range.range_min().into_owned();
+            min.update_deps(&dep_mapping);
+            min.uncache();
+            let mut max = range.range_max().into_owned();
+            max.update_deps(&dep_mapping);
+            max.uncache();
+            new_var.set_range_min(analyzer, min)?;
+            new_var.set_range_max(analyzer, max)?;
+            // a `require` on the new variable narrows what it was built from, so that has to be instantiated
+            // too, or it is forgotten rather than narrowing the other context's variables
+            let tmp_of = match var.tmp_of(analyzer)? {
+                Some(tmp) => {
+                    let lhs = self.instantiate_var(loc, tmp.lhs, mapping, analyzer)?;
+                    let rhs = match tmp.rhs {
+                        Some(rhs) => Some(self.instantiate_var(loc, rhs, mapping, analyzer)?),
+                        None => None,
+                    };
+                    let mut instantiated = true;
+                    for (side, new_side) in [(Some(tmp.lhs), Some(lhs)), (tmp.rhs, rhs)] {
+                        if let (Some(side), Some(new_side)) = (side, new_side) {
+                            instantiated &= side != new_side || side.is_const(analyzer)?;
+                        }
+                    }
+                    instantiated.then(|| TmpConstruction::new(lhs, tmp.op, rhs))
+                }
+                None => None,
+            };
+            new_var.underlying_mut(analyzer)?.tmp_of = tmp_of;
+            new_var
+        };
+        mapping.insert(var, new_var);
+        Ok(new_var)
+    }
+
+    /// Returns the variables the range of a variable of another context is ultimately built from: the given
+    /// variables it reaches, and the variables whose ranges don't depend on any other
+    fn range_roots(
+        var: ContextVarNode,
+        vars: &BTreeSet<ContextVarNode>,
+        analyzer: &impl GraphLike,
+    ) -> Result<BTreeSet<ContextVarNode>, GraphError> {
+        let mut roots = BTreeSet::default();
+        let mut seen = BTreeSet::default();
+        let mut stack = vec![var];
+        while let Some(var) = stack.pop() {
+            if !seen.insert(var) {
+                continue;
+            }
+            let deps = if vars.contains(&var) || !matches!(analyzer.node(var), Node::ContextVar(..))
+            {
+                vec![]
+            } else {
+                var.range_deps(analyzer)?
+            };
+            if deps.is_empty() {
+                roots.insert(var);
+            } else {
+                stack.extend(deps);
+            }
+        }
+        Ok(roots)
+    }
+
+    /// Joins the values a variable has at the end of different paths into a single variable whose
+    /// range covers all of them
+    fn join_vars(
+        &self,
+        loc: Loc,
+        vars: Vec<ContextVarNode>,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<ContextVarNode, GraphError> {
+        let mut unique = vec![];
+        vars.into_iter().for_each(|var| {
+            if !unique.contains(&var) {
+                unique.push(var);
+            }
+        });
+        if unique.len() == 1 {
+            return Ok(unique[0]);
+        }
+
+        let new_var = unique[0].as_tmp(loc, *self, analyzer)?;
+        analyzer.add_edge(new_var, *self, Edge::Context(ContextEdge::Variable));
+        let (min, max) = unique[1..].iter().fold(
+            (Elem::from(unique[0]), Elem::from(unique[0])),
+            |(min, max), var| (min.min(Elem::from(*var)), max.max(Elem::from(*var))),
+        );
+        new_var.set_range_min(analyzer, min)?;
+        new_var.set_range_max(analyzer, max)?;
+        Ok(new_var)
     }
 
     pub fn is_ext_fn(&self, analyzer: &impl GraphLike) -> Result<bool, GraphError> {
@@ -908,6 +1491,32 @@ impl ContextNode {
             .collect::<Vec<_>>()
     }
 
+    /// Gets all visible events
+    pub fn visible_events(&self, analyzer: &mut (impl GraphLike + AnalyzerLike)) -> Vec<EventNode> {
+        let Some(source) = self.maybe_associated_source(analyzer) else {
+            return vec![]
+        };
+
+        analyzer
+            .search_children_exclude_via(source, &Edge::Event, &[Edge::Func])
+            .into_iter()
+            .map(EventNode::from)
+            .collect::<Vec<_>>()
+    }
+
+    /// Gets all visible errors
+    pub fn visible_errors(&self, analyzer: &mut (impl GraphLike + AnalyzerLike)) -> Vec<ErrorNode> {
+        let Some(source) = self.maybe_associated_source(analyzer) else {
+            return vec![]
+        };
+
+        analyzer
+            .search_children_exclude_via(source, &Edge::Error, &[Edge::Func])
+            .into_iter()
+            .map(ErrorNode::from)
+            .collect::<Vec<_>>()
+    }
+
     /// Gets the associated function for the context
     pub fn associated_fn(&self, analyzer: &impl GraphLike) -> Result<FunctionNode, GraphError> {
         let underlying = self.underlying(analyzer)?;
@@ -994,7 +1603,7 @@ impl ContextNode {
         } else if let Some(parent) = self.ancestor_in_fn(analyzer, self.associated_fn(analyzer)?)? {
             parent.var_by_name_or_recurse(analyzer, name)
         } else {
-            Ok(None)
+            Ok(self.underlying(analyzer)?.frame_vars.get(name).copied())
         }
     }
 
@@ -1324,6 +1933,39 @@ impl ContextNode {
         Ok(())
     }
 
+    /// Deletes the child of the context and the graph edge connecting it to the context, so that the
+    /// discarded subcontexts aren't reachable from the context through the graph either
+    pub fn detach_child(
+        &self,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        let Some(child) = self.underlying(analyzer)?.child else {
+            return Ok(());
+        };
+        // a call is connected directly, a fork through its fork node
+        let sources: Vec<NodeIdx> = match child {
+            CallFork::Call(call) => vec![call.into()],
+            CallFork::Fork(w1, _) => analyzer
+                .graph()
+                .edges_directed(w1.into(), Direction::Outgoing)
+                .filter(|edge| *edge.weight() == Edge::Context(ContextEdge::Subcontext))
+                .map(|edge| edge.target())
+                .collect(),
+        };
+        let mut edges: Vec<_> = analyzer
+            .graph()
+            .edges_directed((*self).into(), Direction::Incoming)
+            .filter(|edge| sources.contains(&edge.source()))
+            .map(|edge| edge.id())
+            .collect();
+        // removing an edge moves the last edge into its index, so remove from the back
+        edges.sort();
+        edges.into_iter().rev().for_each(|edge| {
+            analyzer.graph_mut().remove_edge(edge);
+        });
+        self.delete_child(analyzer)
+    }
+
     /// Kills the context by denoting it as killed. Recurses up the contexts and kills
     /// parent contexts if all subcontexts of that context are killed
     pub fn kill(
@@ -1395,6 +2037,61 @@ impl ContextNode {
         Ok(())
     }
 
+    /// Returns all contexts below this one, i.e. its forks and calls and theirs
+    pub fn descendants(&self, analyzer: &impl GraphLike) -> Result<Vec<ContextNode>, GraphError> {
+        let mut descendants = vec![];
+        match self.underlying(analyzer)?.child {
+            Some(CallFork::Fork(w1, w2)) => {
+                descendants.push(w1);
+                descendants.extend(w1.descendants(analyzer)?);
+                descendants.push(w2);
+                descendants.extend(w2.descendants(analyzer)?);
+            }
+            Some(CallFork::Call(call)) => {
+                descendants.push(call);
+                descendants.extend(call.descendants(analyzer)?);
+            }
+            None => {}
+        }
+        Ok(descendants)
+    }
+
+    /// Returns the leaves of the context's tree that finished without reverting. A context without children
+    /// is its own leaf
+    pub fn successful_leaves(
+        &self,
+        analyzer: &impl GraphLike,
+    ) -> Result<Vec<ContextNode>, GraphError> {
+        let mut leaves = self.all_edges(analyzer)?;
+        if leaves.is_empty() {
+            leaves.push(*self);
+        }
+        let mut successful = vec![];
+        for leaf in leaves {
+            if !leaf.is_killed(analyzer)? {
+                successful.push(leaf);
+            }
+        }
+        Ok(successful)
+    }
+
+    /// Gets the latest version of a storage variable visible from this context, if it was accessed
+    pub fn storage_var_by_name(
+        &self,
+        analyzer: &impl GraphLike,
+        name: &str,
+    ) -> Result<Option<ContextVarNode>, GraphError> {
+        let Some(var) = self.var_by_name_or_recurse(analyzer, name)? else {
+            return Ok(None);
+        };
+        let var = var.latest_version(analyzer);
+        if var.is_storage(analyzer)? {
+            Ok(Some(var))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Gets parent list
     pub fn parent_list(&self, analyzer: &impl GraphLike) -> Result<Vec<ContextNode>, GraphError> {
         let context = self.underlying(analyzer)?;
@@ -1406,6 +2103,30 @@ impl ContextNode {
         Ok(parents)
     }
 
+    /// Returns whether this context evaluates the code of an external call made on its path, rather than the
+    /// code of the function the path started in. The contexts resuming the caller after the call are not
+    pub fn in_external_call(&self, analyzer: &impl GraphLike) -> Result<bool, GraphError> {
+        let mut lineage = self.parent_list(analyzer)?;
+        lineage.reverse();
+        lineage.push(*self);
+        // the contexts that made the external calls the path is currently inside of
+        let mut callers: Vec<ContextNode> = vec![];
+        for ctx in lineage {
+            let underlying = ctx.underlying(analyzer)?;
+            if underlying.returning_ctx.is_some()
+                && underlying.returning_ctx == callers.last().copied()
+            {
+                callers.pop();
+            }
+            if underlying.ext_fn_call.is_some() {
+                if let Some(parent) = underlying.parent_ctx {
+                    callers.push(parent);
+                }
+            }
+        }
+        Ok(!callers.is_empty())
+    }
+
     pub fn recursive_calls(
         &self,
         analyzer: &impl GraphLike,
@@ -1458,13 +2179,17 @@ impl ContextNode {
     /// Returns whether the context is killed
     pub fn is_ended(&self, analyzer: &impl GraphLike) -> Result<bool, GraphError> {
         let underlying = self.underlying(analyzer)?;
-        Ok(underlying.child.is_some() || underlying.killed.is_some() || !underlying.ret.is_empty())
+        Ok(underlying.child.is_some()
+            || underlying.killed.is_some()
+            || !underlying.ret.is_empty()
+            || underlying.loop_control.is_some())
     }
 
     pub fn killed_or_ret(&self, analyzer: &impl GraphLike) -> Result<bool, GraphError> {
         let underlying = self.underlying(analyzer)?;
         Ok(underlying.killed.is_some()
-            || (!underlying.ret.is_empty() && underlying.modifier_state.is_none()))
+            || (!underlying.ret.is_empty() && underlying.modifier_state.is_none())
+            || underlying.loop_control.is_some())
     }
 
     /// Returns an option to where the context was killed
@@ -1531,6 +2256,297 @@ impl ContextNode {
         Ok(self.underlying(analyzer)?.ret.clone())
     }
 
+    /// Records an `emit` of an event in this context
+    pub fn add_emit(
+        &self,
+        emit: ContextEmit,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?.emits.push(emit);
+        Ok(())
+    }
+
+    /// Returns the events emitted in this context
+    pub fn emits(&self, analyzer: &impl GraphLike) -> Result<Vec<ContextEmit>, GraphError> {
+        Ok(self.underlying(analyzer)?.emits.clone())
+    }
+
+    /// Returns the events emitted along the path from the root context to this context, in order
+    pub fn path_emits(&self, analyzer: &impl GraphLike) -> Result<Vec<ContextEmit>, GraphError> {
+        let mut lineage = self.parent_list(analyzer)?;
+        lineage.reverse();
+        lineage.push(*self);
+        lineage.iter().try_fold(vec![], |mut emits, ctx| {
+            emits.extend(ctx.emits(analyzer)?);
+            Ok(emits)
+        })
+    }
+
+    /// Records a call from this context that was summarized instead of evaluated
+    pub fn add_summarized_call(
+        &self,
+        loc: Loc,
+        func: FunctionNode,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?
+            .summarized_calls
+            .push((loc, func));
+        Ok(())
+    }
+
+    /// Returns the calls from this context that were summarized instead of evaluated
+    pub fn summarized_calls(
+        &self,
+        analyzer: &impl GraphLike,
+    ) -> Result<Vec<(Loc, FunctionNode)>, GraphError> {
+        Ok(self.underlying(analyzer)?.summarized_calls.clone())
+    }
+
+    /// Records a low level call or storage write in this context
+    pub fn add_effect(
+        &self,
+        effect: ContextEffect,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?.effects.push(effect);
+        Ok(())
+    }
+
+    /// Returns the low level calls and storage writes of this context, in order
+    pub fn effects(&self, analyzer: &impl GraphLike) -> Result<Vec<ContextEffect>, GraphError> {
+        Ok(self.underlying(analyzer)?.effects.clone())
+    }
+
+    /// Records a value flowing into a sensitive operation in this context
+    pub fn add_sink(
+        &self,
+        sink: ContextSink,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?.sinks.push(sink);
+        Ok(())
+    }
+
+    /// Returns the values flowing into sensitive operations in this context
+    pub fn sinks(&self, analyzer: &impl GraphLike) -> Result<Vec<ContextSink>, GraphError> {
+        Ok(self.underlying(analyzer)?.sinks.clone())
+    }
+
+    /// Records integer arithmetic that can panic in this context
+    pub fn add_arith(
+        &self,
+        arith: ContextArith,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?.ariths.push(arith);
+        Ok(())
+    }
+
+    /// Returns the integer arithmetic that can panic in this context
+    pub fn ariths(&self, analyzer: &impl GraphLike) -> Result<Vec<ContextArith>, GraphError> {
+        Ok(self.underlying(analyzer)?.ariths.clone())
+    }
+
+    /// Records an index access or `pop()` of an array in this context
+    pub fn add_array_access(
+        &self,
+        access: ContextArrayAccess,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?.array_accesses.push(access);
+        Ok(())
+    }
+
+    /// Returns the index accesses and `pop()`s of arrays in this context
+    pub fn array_accesses(
+        &self,
+        analyzer: &impl GraphLike,
+    ) -> Result<Vec<ContextArrayAccess>, GraphError> {
+        Ok(self.underlying(analyzer)?.array_accesses.clone())
+    }
+
+    /// Records the values that were `abi.encode`d into `encoded`
+    pub fn add_abi_encoding(
+        &self,
+        encoded: ContextVarNode,
+        values: Vec<ContextVarNode>,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?
+            .abi_encodings
+            .insert(encoded, values);
+        Ok(())
+    }
+
+    /// Returns the values that were `abi.encode`d into `encoded`, if they are known
+    pub fn abi_encoding(
+        &self,
+        encoded: ContextVarNode,
+        analyzer: &impl GraphLike,
+    ) -> Result<Option<Vec<ContextVarNode>>, GraphError> {
+        Ok(self
+            .underlying(analyzer)?
+            .abi_encodings
+            .get(&encoded)
+            .cloned())
+    }
+
+    /// Returns the words inline assembly stored at constant memory offsets
+    pub fn memory_words(
+        &self,
+        analyzer: &impl GraphLike,
+    ) -> Result<BTreeMap<U256, ContextVarNode>, GraphError> {
+        Ok(self.underlying(analyzer)?.memory_words.clone())
+    }
+
+    /// Records a store of `len` bytes of memory at a constant offset, which overwrites the words it overlaps. The
+    /// stored word is only known if it is a whole word
+    pub fn store_memory(
+        &self,
+        offset: U256,
+        len: U256,
+        word: Option<ContextVarNode>,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        let end = offset.saturating_add(len);
+        let words = &mut self.underlying_mut(analyzer)?.memory_words;
+        words.retain(|start, _| start.saturating_add(U256::from(32)) <= offset || *start >= end);
+        if let Some(word) = word {
+            words.insert(offset, word);
+        }
+        Ok(())
+    }
+
+    /// Forgets the words known to be in memory, i.e. after a store to an offset that isn't constant
+    pub fn forget_memory(
+        &self,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?.memory_words.clear();
+        Ok(())
+    }
+
+    /// Records the words of memory that were hashed into `hash`
+    pub fn add_hash(
+        &self,
+        hash: ContextVarNode,
+        words: Vec<ContextVarNode>,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?.hashes.insert(hash, words);
+        Ok(())
+    }
+
+    /// Returns the words of memory that were hashed into `hash`, if they are known
+    pub fn hash_preimage(
+        &self,
+        hash: ContextVarNode,
+        analyzer: &impl GraphLike,
+    ) -> Result<Option<Vec<ContextVarNode>>, GraphError> {
+        Ok(self.underlying(analyzer)?.hashes.get(&hash).cloned())
+    }
+
+    /// Gets the `msg` and `tx` environment of the call frame the context runs in
+    pub fn msg(
+        &self,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<MsgNode, GraphError> {
+        match self.underlying(analyzer)?.msg {
+            Some(msg) => Ok(msg),
+            None => Ok(analyzer.msg()),
+        }
+    }
+
+    /// Binds a `msg` variable of the external call this context runs in, for its modifiers and internal calls
+    pub fn add_frame_var(
+        &self,
+        name: &str,
+        var: ContextVarNode,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?
+            .frame_vars
+            .insert(name.to_string(), var);
+        Ok(())
+    }
+
+    /// Records the `{value: ..}` option of the external call of the function named at `loc`
+    pub fn add_call_value(
+        &self,
+        loc: Loc,
+        value: ContextVarNode,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?
+            .call_values
+            .insert(loc, value);
+        Ok(())
+    }
+
+    /// Takes the `{value: ..}` option of the external call being set up at `loc`, if it has one
+    pub fn take_call_value(
+        &self,
+        loc: Loc,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<Option<ContextVarNode>, GraphError> {
+        let call_values = &mut self.underlying_mut(analyzer)?.call_values;
+        let name_loc = call_values
+            .keys()
+            .find(|name_loc| match (name_loc, loc) {
+                (Loc::File(file, start, end), Loc::File(call_file, call_start, call_end)) => {
+                    *file == call_file && call_start <= *start && *end <= call_end
+                }
+                _ => **name_loc == loc,
+            })
+            .copied();
+        Ok(name_loc.and_then(|name_loc| call_values.remove(&name_loc)))
+    }
+
+    /// Ends the current loop iteration of this context via a `break` or `continue`
+    pub fn set_loop_control(
+        &self,
+        control: LoopControl,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?.loop_control = Some(control);
+        Ok(())
+    }
+
+    /// Gets the `break` or `continue` this context hit, if any
+    pub fn loop_control(
+        &self,
+        analyzer: &impl GraphLike,
+    ) -> Result<Option<LoopControl>, GraphError> {
+        Ok(self.underlying(analyzer)?.loop_control)
+    }
+
+    /// Takes the `break` or `continue` this context hit, if any, allowing statements to be evaluated in it again
+    pub fn take_loop_control(
+        &self,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<Option<LoopControl>, GraphError> {
+        Ok(self.underlying_mut(analyzer)?.loop_control.take())
+    }
+
+    /// Records the error this context reverts with
+    pub fn set_reverts_with(
+        &self,
+        revert: ContextRevert,
+        analyzer: &mut (impl GraphLike + AnalyzerLike),
+    ) -> Result<(), GraphError> {
+        self.underlying_mut(analyzer)?.reverts_with = Some(revert);
+        Ok(())
+    }
+
+    /// Returns the error this context reverted with, if it reverted with a reason
+    pub fn reverts_with(
+        &self,
+        analyzer: &impl GraphLike,
+    ) -> Result<Option<ContextRevert>, GraphError> {
+        Ok(self.underlying(analyzer)?.reverts_with.clone())
+    }
+
     pub fn as_string(&mut self) -> String {
         "Context".to_string()
     }
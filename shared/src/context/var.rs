@@ -384,6 +384,39 @@ impl ContextVarNode {
         Some(ContextVarNode::from(index))
     }
 
+    /// Returns the variable this is an element or member of (i.e. `balances` for `balances[to]` or `s` for
+    /// `s.x`), following nested accesses, or `None` if it isn't an element or member
+    pub fn element_root(&self, analyzer: &impl GraphLike) -> Option<ContextVarNode> {
+        let parent = |var: ContextVarNode| {
+            analyzer
+                .graph()
+                .edges_directed(var.first_version(analyzer).into(), Direction::Outgoing)
+                .find(|edge| {
+                    matches!(
+                        edge.weight(),
+                        Edge::Context(ContextEdge::IndexAccess)
+                            | Edge::Context(ContextEdge::AttrAccess)
+                    )
+                })
+                .map(|edge| ContextVarNode::from(edge.target()).latest_version(analyzer))
+        };
+        let mut root = parent(*self)?;
+        while let Some(next) = parent(root) {
+            root = next;
+        }
+        Some(root)
+    }
+
+    /// Returns the name of the variable this is an element of, following nested index accesses, or its own
+    /// name if it isn't an element
+    pub fn root_name(&self, analyzer: &impl GraphLike) -> Result<String, GraphError> {
+        let mut root = *self;
+        while let Some(arr) = root.index_to_array(analyzer) {
+            root = arr;
+        }
+        root.name(analyzer)
+    }
+
     pub fn as_range_elem(
         &self,
         analyzer: &impl GraphLike,
@@ -690,6 +723,33 @@ impl ContextVarNode {
         }
     }
 
+    /// Returns this variable and all later versions and inherited copies of it that have the same
+    /// range, i.e. that weren't assigned to or narrowed
+    pub fn unchanged_versions(&self, analyzer: &impl GraphLike) -> Result<Vec<Self>, GraphError> {
+        let (min, max) = (self.range_min(analyzer)?, self.range_max(analyzer)?);
+        let mut unchanged = vec![];
+        let mut stack = vec![*self];
+        while let Some(var) = stack.pop() {
+            if var.range_min(analyzer)? == min && var.range_max(analyzer)? == max {
+                unchanged.push(var);
+            }
+            stack.extend(
+                analyzer
+                    .graph()
+                    .edges_directed(var.0.into(), Direction::Incoming)
+                    .filter(|edge| {
+                        matches!(
+                            *edge.weight(),
+                            Edge::Context(ContextEdge::Prev)
+                                | Edge::Context(ContextEdge::InheritedVariable)
+                        )
+                    })
+                    .map(|edge| ContextVarNode::from(edge.source())),
+            );
+        }
+        Ok(unchanged)
+    }
+
     pub fn range_deps(&self, analyzer: &impl GraphLike) -> Result<Vec<Self>, GraphError> {
         if let Some(range) = self.ref_range(analyzer)? {
             Ok(range.dependent_on())
@@ -16,6 +16,7 @@ pub mod analyzer;
 pub mod context;
 pub mod nodes;
 pub mod range;
+pub mod smt;
 
 pub type NodeIdx = NodeIndex<usize>;
 pub type EdgeIdx = EdgeIndex<usize>;
@@ -39,6 +40,8 @@ pub enum Node {
     Enum(Enum),
     Error(Error),
     ErrorParam(ErrorParam),
+    Event(Event),
+    EventParam(EventParam),
     Field(Field),
     Var(Var),
     Ty(Ty),
@@ -63,6 +66,7 @@ pub fn as_dot_str(idx: NodeIdx, analyzer: &impl GraphLike) -> String {
         FunctionReturn(_fr) => FunctionReturnNode::from(idx).as_dot_str(analyzer),
         Struct(_s) => StructNode::from(idx).as_dot_str(analyzer),
         Enum(_e) => EnumNode::from(idx).as_dot_str(analyzer),
+        Event(_e) => EventNode::from(idx).as_dot_str(analyzer),
         Field(_f) => FieldNode::from(idx).as_dot_str(analyzer),
         Var(_v) => VarNode::from(idx).as_dot_str(analyzer),
         Ty(_t) => TyNode::from(idx).as_dot_str(analyzer),
@@ -121,6 +125,7 @@ pub enum Edge {
     Error,
     ErrorParam,
     Event,
+    EventParam,
     Var,
     Ty,
     Func,
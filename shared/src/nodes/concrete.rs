@@ -3,6 +3,7 @@ use crate::analyzer::{AnalyzerLike, GraphLike};
 use crate::Builtin;
 use crate::VarType;
 use crate::{Node, NodeIdx};
+use ethers_core::abi::{encode, Token};
 use ethers_core::types::{Address, H256, I256, U256};
 
 /// An index in the graph that references a [`Concrete`] node
@@ -290,6 +291,117 @@ impl Concrete {
         }
     }
 
+    /// The bytes a hashing function sees when given this concrete
+    pub fn hash_input(&self) -> Option<Vec<u8>> {
+        match self {
+            Concrete::DynBytes(b) => Some(b.clone()),
+            Concrete::String(s) => Some(s.as_bytes().to_vec()),
+            Concrete::Bytes(size, b) => Some(b.0[..*size as usize].to_vec()),
+            _ => None,
+        }
+    }
+
+    /// Folds `keccak256` over a concrete input
+    pub fn keccak256(&self) -> Option<Self> {
+        let hash = ethers_core::utils::keccak256(self.hash_input()?);
+        Some(Concrete::Bytes(32, H256::from(hash)))
+    }
+
+    /// Folds the `sha256` precompile over a concrete input
+    pub fn sha256(&self) -> Option<Self> {
+        use ethers_core::k256::sha2::{Digest, Sha256};
+        let hash = Sha256::digest(self.hash_input()?);
+        Some(Concrete::Bytes(32, H256::from_slice(&hash)))
+    }
+
+    /// Folds the `ripemd160` precompile over a concrete input, returning a `bytes20`
+    pub fn ripemd160(&self) -> Option<Self> {
+        use ripemd::{Digest, Ripemd160};
+        let hash = Ripemd160::digest(self.hash_input()?);
+        let mut h = H256::default();
+        h.0[..20].copy_from_slice(&hash);
+        Some(Concrete::Bytes(20, h))
+    }
+
+    /// Converts the concrete into an ABI token, if it has a representation
+    pub fn as_abi_token(&self) -> Option<Token> {
+        match self {
+            Concrete::Uint(_, val) => Some(Token::Uint(*val)),
+            Concrete::Int(_, val) => Some(Token::Int(val.into_raw())),
+            Concrete::Bytes(size, b) => Some(Token::FixedBytes(b.0[..*size as usize].to_vec())),
+            Concrete::Address(a) => Some(Token::Address(*a)),
+            Concrete::Bool(b) => Some(Token::Bool(*b)),
+            Concrete::DynBytes(b) => Some(Token::Bytes(b.clone())),
+            Concrete::String(s) => Some(Token::String(s.clone())),
+            Concrete::Array(arr) => Some(Token::Array(
+                arr.iter()
+                    .map(|c| c.as_abi_token())
+                    .collect::<Option<Vec<_>>>()?,
+            )),
+        }
+    }
+
+    /// Folds `abi.encode` over concrete arguments
+    pub fn abi_encode(args: &[Self]) -> Option<Self> {
+        let tokens = args
+            .iter()
+            .map(|arg| arg.as_abi_token())
+            .collect::<Option<Vec<_>>>()?;
+        Some(Concrete::DynBytes(encode(&tokens)))
+    }
+
+    /// Folds `abi.encodePacked` over concrete arguments. Value types take their exact width,
+    /// dynamic types are not length prefixed and array elements are padded to 32 bytes.
+    pub fn abi_encode_packed(args: &[Self]) -> Option<Self> {
+        let mut packed = vec![];
+        for arg in args.iter() {
+            match arg {
+                Concrete::Array(arr) => {
+                    for elem in arr.iter() {
+                        if elem.is_dyn() {
+                            return None;
+                        }
+                        packed.extend(encode(&[elem.as_abi_token()?]));
+                    }
+                }
+                Concrete::Uint(size, val) => {
+                    let mut bytes = [0u8; 32];
+                    val.to_big_endian(&mut bytes);
+                    packed.extend(&bytes[32 - (*size as usize / 8)..]);
+                }
+                Concrete::Int(size, val) => {
+                    let mut bytes = [0u8; 32];
+                    val.into_raw().to_big_endian(&mut bytes);
+                    packed.extend(&bytes[32 - (*size as usize / 8)..]);
+                }
+                Concrete::Address(a) => packed.extend(a.as_bytes()),
+                Concrete::Bool(b) => packed.push(*b as u8),
+                _ => packed.extend(arg.hash_input()?),
+            }
+        }
+        Some(Concrete::DynBytes(packed))
+    }
+
+    /// Folds `abi.encodeWithSelector` over a concrete `bytes4` selector and arguments
+    pub fn abi_encode_with_selector(selector: &Self, args: &[Self]) -> Option<Self> {
+        let Concrete::Bytes(4, selector) = selector else {
+            return None;
+        };
+        let mut encoded = selector.0[..4].to_vec();
+        encoded.extend(Self::abi_encode(args)?.hash_input()?);
+        Some(Concrete::DynBytes(encoded))
+    }
+
+    /// Folds `abi.encodeWithSignature` over a concrete signature string and arguments
+    pub fn abi_encode_with_signature(signature: &Self, args: &[Self]) -> Option<Self> {
+        let Concrete::Bytes(_, hash) = signature.keccak256()? else {
+            return None;
+        };
+        let mut selector = H256::default();
+        selector.0[..4].copy_from_slice(&hash.0[..4]);
+        Self::abi_encode_with_selector(&Concrete::Bytes(4, selector), args)
+    }
+
     /// Cast the concrete to another type as denoted by a [`Builtin`].
     pub fn cast(self, builtin: Builtin) -> Option<Self> {
         match self {
@@ -1,14 +1,22 @@
 use crate::analyzer::GraphError;
 use crate::analyzer::Search;
 use crate::analyzer::{AnalyzerLike, GraphLike};
+use crate::range::{elem::RangeElem, Range, SolcRange};
 use crate::AsDotStr;
 use crate::Edge;
+use crate::ErrorNode;
+use crate::EventNode;
 use crate::FunctionNode;
 use crate::Node;
 use crate::NodeIdx;
 use crate::StructNode;
+use crate::VarNode;
+use crate::{Builtin, TypeNode, VarType};
+use ethers_core::types::{H256, U256};
+use ethers_core::utils::keccak256;
 use petgraph::{visit::EdgeRef, Direction};
-use solang_parser::pt::{ContractDefinition, ContractTy, Identifier, Loc};
+use solang_parser::pt::{ContractDefinition, ContractTy, Identifier, Loc, VariableAttribute};
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
 
 /// An index in the graph that references a [`Contract`] node
@@ -161,6 +169,260 @@ impl ContractNode {
         mapping
     }
 
+    /// Gets the functions a transaction can enter the [`Contract`] through: its public and external functions,
+    /// including inherited ones, and its fallback and receive functions
+    pub fn entry_functions(
+        &self,
+        analyzer: &(impl GraphLike + Search + AnalyzerLike),
+    ) -> Vec<FunctionNode> {
+        let mut funcs = self
+            .linearized_functions(analyzer)
+            .into_values()
+            .filter(|func| func.is_public_or_ext(analyzer).unwrap_or(false))
+            .collect::<Vec<_>>();
+        for edge in [Edge::FallbackFunc, Edge::ReceiveFunc] {
+            funcs.extend(
+                analyzer
+                    .search_children_depth(self.0.into(), &edge, 1, 0)
+                    .into_iter()
+                    .map(FunctionNode::from),
+            );
+        }
+        funcs
+    }
+
+    /// Gets the constructor of the [`Contract`], if it defines one. The constructors of the contracts it
+    /// inherits from aren't its own
+    pub fn constructor(&self, analyzer: &(impl GraphLike + Search)) -> Option<FunctionNode> {
+        analyzer
+            .search_children_depth(self.0.into(), &Edge::Constructor, 0, 0)
+            .into_iter()
+            .map(FunctionNode::from)
+            .next()
+    }
+
+    /// Gets the storage variables and constants defined in the [`Contract`]
+    pub fn vars(&self, analyzer: &(impl GraphLike + Search)) -> Vec<VarNode> {
+        analyzer
+            .search_children_depth(self.0.into(), &Edge::Var, 1, 0)
+            .into_iter()
+            .map(VarNode::from)
+            .collect()
+    }
+
+    /// Gets the ranges of the storage variables of the [`Contract`] and the contracts it inherits from once it
+    /// is deployed. A variable has the range the most derived constructor that accesses it leaves it with,
+    /// joined over the constructor's successful paths, or its [`VarNode::deploy_range`] if no constructor
+    /// accesses it. The constructors must already have been analyzed. Variables without a known range (i.e.
+    /// arrays or ones initialized by a call) map to `None`
+    pub fn deployed_storage(
+        &self,
+        analyzer: &mut (impl GraphLike + AnalyzerLike + Search),
+    ) -> Result<BTreeMap<VarNode, Option<SolcRange>>, GraphError> {
+        let mut contracts = vec![*self];
+        contracts.extend(self.all_inherited_contracts(analyzer));
+        let mut vars = vec![];
+        for contract in contracts.iter() {
+            for var in contract.vars(analyzer) {
+                if !var.is_const(analyzer)? && !vars.contains(&var) {
+                    vars.push(var);
+                }
+            }
+        }
+
+        let mut accessed: BTreeMap<VarNode, Option<SolcRange>> = BTreeMap::default();
+        for contract in contracts.iter() {
+            let Some(body_ctx) = contract
+                .constructor(analyzer)
+                .and_then(|constructor| constructor.maybe_body_ctx(analyzer))
+            else {
+                continue;
+            };
+            let leaves = body_ctx.successful_leaves(analyzer)?;
+            let own_vars = contract.vars(analyzer);
+            for var in vars.iter() {
+                if accessed.contains_key(var) {
+                    continue;
+                }
+                let name = var.name(analyzer)?;
+                let mut found = vec![];
+                for leaf in leaves.iter() {
+                    found.push(leaf.storage_var_by_name(analyzer, &name)?);
+                }
+                if found.iter().all(|cvar| cvar.is_none()) {
+                    continue;
+                }
+
+                // a path that doesn't access the variable leaves it with the value it had before the
+                // constructor, which is only known for the constructor's own variables
+                let before = if own_vars.contains(var) {
+                    var.deploy_range(analyzer)?
+                } else {
+                    None
+                };
+                let ranges = found
+                    .into_iter()
+                    .map(|cvar| match cvar {
+                        Some(cvar) => cvar.range(analyzer),
+                        None => Ok(before.clone()),
+                    })
+                    .collect::<Result<Vec<_>, GraphError>>()?;
+                accessed.insert(*var, join_ranges(ranges, analyzer)?);
+            }
+        }
+
+        let mut storage = BTreeMap::default();
+        for var in vars {
+            let range = match accessed.remove(&var) {
+                Some(range) => range,
+                None => var.deploy_range(analyzer)?,
+            };
+            storage.insert(var, range);
+        }
+        Ok(storage)
+    }
+
+    /// Gets the contracts the [`Contract`] inherits from, and itself, in C3 linearization order from the most
+    /// base contract to the most derived one, which is the order their storage variables are laid out in
+    pub fn linearized_bases(&self, analyzer: &impl GraphLike) -> Vec<ContractNode> {
+        let mut linearized = self.c3_linearization(analyzer);
+        linearized.reverse();
+        linearized
+    }
+
+    /// Gets the deployable contracts that inherit from the [`Contract`], i.e. every contract an external call through
+    /// it can end up in, assuming all of them are known
+    pub fn implementations(&self, analyzer: &impl GraphLike) -> Vec<ContractNode> {
+        analyzer
+            .graph()
+            .node_indices()
+            .filter(|idx| {
+                matches!(
+                    analyzer.node(*idx),
+                    Node::Contract(Contract {
+                        ty: ContractTy::Contract(_),
+                        ..
+                    })
+                )
+            })
+            .map(ContractNode::from)
+            .filter(|contract| {
+                contract != self && contract.linearized_bases(analyzer).contains(self)
+            })
+            .collect()
+    }
+
+    /// Gets the function a call of `func` on the [`Contract`] runs: the most derived function with a body that has the
+    /// same name and parameter types
+    pub fn implementation_of(
+        &self,
+        func: FunctionNode,
+        analyzer: &(impl GraphLike + Search),
+    ) -> Result<Option<FunctionNode>, GraphError> {
+        let name = func.name(analyzer)?;
+        let param_tys = func
+            .params(analyzer)
+            .iter()
+            .map(|param| param.ty_str(analyzer))
+            .collect::<Result<Vec<_>, GraphError>>()?;
+        for base in self.linearized_bases(analyzer).into_iter().rev() {
+            for candidate in base.funcs(analyzer) {
+                if candidate.underlying(analyzer)?.body.is_none()
+                    || candidate.name(analyzer)? != name
+                {
+                    continue;
+                }
+                let candidate_tys = candidate
+                    .params(analyzer)
+                    .iter()
+                    .map(|param| param.ty_str(analyzer))
+                    .collect::<Result<Vec<_>, GraphError>>()?;
+                if candidate_tys == param_tys {
+                    return Ok(Some(candidate));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// The C3 linearization of the [`Contract`], from itself to its most base contract. In solidity, the
+    /// rightmost base in an `is` list is the most derived
+    fn c3_linearization(&self, analyzer: &impl GraphLike) -> Vec<ContractNode> {
+        // bases that were resolved after the contract was parsed only have an edge, so they go last
+        let mut bases = self.direct_inherited_contracts(analyzer);
+        for base in self.super_contracts(analyzer) {
+            if !bases.contains(&base) {
+                bases.push(base);
+            }
+        }
+        bases.reverse();
+
+        let mut seqs: Vec<Vec<ContractNode>> = bases
+            .iter()
+            .map(|base| base.c3_linearization(analyzer))
+            .collect();
+        seqs.push(bases);
+        let mut linearized = vec![*self];
+        loop {
+            seqs.retain(|seq| !seq.is_empty());
+            // the first head that isn't in the tail of any sequence, or any head if the hierarchy is inconsistent
+            let Some(next) = seqs
+                .iter()
+                .map(|seq| seq[0])
+                .find(|head| !seqs.iter().any(|seq| seq[1..].contains(head)))
+                .or_else(|| seqs.first().map(|seq| seq[0]))
+            else {
+                break;
+            };
+            seqs.iter_mut().for_each(|seq| seq.retain(|c| *c != next));
+            linearized.push(next);
+        }
+        linearized
+    }
+
+    /// Gets the storage slots of the variables of the [`Contract`] and the contracts it inherits from, as solc
+    /// lays them out. Constants and immutables have no slot. Values of mappings and dynamic arrays live at slots
+    /// derived from their variable's slot with [`mapping_slot`] and [`dynamic_array_slot`]
+    pub fn storage_layout(
+        &self,
+        analyzer: &(impl GraphLike + Search),
+    ) -> Result<Vec<StorageSlot>, GraphError> {
+        let mut vars = vec![];
+        for contract in self.linearized_bases(analyzer) {
+            let mut own_vars = contract.vars(analyzer);
+            // declaration order
+            own_vars.sort();
+            for var in own_vars {
+                let no_slot = var.underlying(analyzer)?.attrs.iter().any(|attr| {
+                    matches!(
+                        attr,
+                        VariableAttribute::Constant(_) | VariableAttribute::Immutable(_)
+                    )
+                });
+                if !no_slot && !vars.contains(&var) {
+                    vars.push(var);
+                }
+            }
+        }
+
+        let mut tys = vec![];
+        for var in vars.iter() {
+            tys.push(var.underlying(analyzer)?.ty);
+        }
+        let (packed, _slots) = pack_storage(&tys, analyzer)?;
+        Ok(vars
+            .into_iter()
+            .zip(packed)
+            .map(|(var, (slot, offset, size, slots))| StorageSlot {
+                var,
+                slot,
+                offset,
+                size,
+                slots,
+            })
+            .collect())
+    }
+
     pub fn structs(&self, analyzer: &(impl GraphLike + Search)) -> Vec<StructNode> {
         analyzer
             .search_children_depth(self.0.into(), &Edge::Struct, 1, 0)
@@ -169,6 +431,50 @@ impl ContractNode {
             .collect()
     }
 
+    /// Gets all events defined in the [`Contract`] and the contracts it inherits from
+    pub fn linearized_events(&self, analyzer: &(impl GraphLike + Search)) -> Vec<EventNode> {
+        let mut events: Vec<EventNode> = analyzer
+            .search_children_depth(self.0.into(), &Edge::Event, 1, 0)
+            .into_iter()
+            .map(EventNode::from)
+            .collect();
+        self.direct_inherited_contracts(analyzer)
+            .iter()
+            .for_each(|inherited| {
+                inherited
+                    .linearized_events(analyzer)
+                    .into_iter()
+                    .for_each(|event| {
+                        if !events.contains(&event) {
+                            events.push(event);
+                        }
+                    });
+            });
+        events
+    }
+
+    /// Gets all errors defined in the [`Contract`] and the contracts it inherits from
+    pub fn linearized_errors(&self, analyzer: &(impl GraphLike + Search)) -> Vec<ErrorNode> {
+        let mut errors: Vec<ErrorNode> = analyzer
+            .search_children_depth(self.0.into(), &Edge::Error, 1, 0)
+            .into_iter()
+            .map(ErrorNode::from)
+            .collect();
+        self.direct_inherited_contracts(analyzer)
+            .iter()
+            .for_each(|inherited| {
+                inherited
+                    .linearized_errors(analyzer)
+                    .into_iter()
+                    .for_each(|error| {
+                        if !errors.contains(&error) {
+                            errors.push(error);
+                        }
+                    });
+            });
+        errors
+    }
+
     /// Gets all associated modifiers from the underlying node data for the [`Contract`]
     pub fn modifiers(&self, analyzer: &(impl GraphLike + Search)) -> Vec<FunctionNode> {
         analyzer
@@ -192,6 +498,229 @@ impl ContractNode {
     }
 }
 
+/// Joins ranges into a single concrete range covering all of them. Returns `None` if any range is unknown or
+/// the ranges' bounds can't be compared
+pub fn join_ranges(
+    ranges: Vec<Option<SolcRange>>,
+    analyzer: &impl GraphLike,
+) -> Result<Option<SolcRange>, GraphError> {
+    let mut joined: Option<SolcRange> = None;
+    for range in ranges {
+        let Some(range) = range else {
+            return Ok(None);
+        };
+        let min = range.evaled_range_min(analyzer)?;
+        let max = range.evaled_range_max(analyzer)?;
+        joined = match joined {
+            None => Some(SolcRange::new(min, max, vec![])),
+            Some(curr) => {
+                let curr_min = curr.evaled_range_min(analyzer)?;
+                let curr_max = curr.evaled_range_max(analyzer)?;
+                let min = match min.range_ord(&curr_min) {
+                    Some(Ordering::Less) => min,
+                    Some(_) => curr_min,
+                    None => return Ok(None),
+                };
+                let max = match max.range_ord(&curr_max) {
+                    Some(Ordering::Greater) => max,
+                    Some(_) => curr_max,
+                    None => return Ok(None),
+                };
+                Some(SolcRange::new(min, max, vec![]))
+            }
+        };
+    }
+    Ok(joined)
+}
+
+/// Where a storage variable lives
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StorageSlot {
+    pub var: VarNode,
+    /// The slot the variable starts at
+    pub slot: U256,
+    /// The offset in bytes from the least significant byte of the slot. Always 0 if `size` is `None`
+    pub offset: u8,
+    /// The size in bytes of a value type, which shares its slot with the value types around it if they fit.
+    /// `None` for types that start and end at slot boundaries
+    pub size: Option<u8>,
+    /// The number of slots the variable spans
+    pub slots: U256,
+}
+
+impl StorageSlot {
+    /// Whether the variable is a value type that takes up a whole slot, i.e. `uint256` or `bytes32`
+    pub fn is_full_word(&self) -> bool {
+        self.size == Some(32)
+    }
+
+    /// Whether the slot is one of the slots the variable spans
+    pub fn contains(&self, slot: U256) -> bool {
+        self.slot <= slot && slot - self.slot < self.slots
+    }
+
+    /// Whether the variable is a mapping or dynamic array of value types that take up a whole slot, so each value
+    /// is alone in the slot [`mapping_slot`] or [`dynamic_array_slot`] locates
+    pub fn full_word_elements(
+        &self,
+        analyzer: &impl GraphLike,
+    ) -> Result<Option<StorageElements>, GraphError> {
+        let ty = self.var.underlying(analyzer)?.ty;
+        let (elements, elem_ty) = match analyzer.node(ty) {
+            Node::Builtin(Builtin::Mapping(_, value_ty)) => (StorageElements::Mapping, value_ty),
+            Node::Builtin(Builtin::Array(elem_ty)) => (StorageElements::DynamicArray, elem_ty),
+            _ => return Ok(None),
+        };
+        match storage_footprint(vartype_idx(elem_ty), analyzer)? {
+            StorageFootprint::Bytes(32) => Ok(Some(elements)),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// How the values of a mapping or dynamic array are located from the slot of the variable
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StorageElements {
+    /// At `keccak256(key . slot)`, see [`mapping_slot`]
+    Mapping,
+    /// From `keccak256(slot)` on, see [`dynamic_array_slot`]
+    DynamicArray,
+}
+
+/// The slot of the value of a mapping at a key, i.e. `keccak256(key . slot)`. The key is given as the 32 bytes
+/// it is padded to
+pub fn mapping_slot(key: H256, slot: U256) -> U256 {
+    let mut preimage = key.as_bytes().to_vec();
+    let mut slot_bytes = [0u8; 32];
+    slot.to_big_endian(&mut slot_bytes);
+    preimage.extend(slot_bytes);
+    U256::from_big_endian(&keccak256(preimage))
+}
+
+/// The slot of the first element of a dynamic array, i.e. `keccak256(slot)`
+pub fn dynamic_array_slot(slot: U256) -> U256 {
+    let mut slot_bytes = [0u8; 32];
+    slot.to_big_endian(&mut slot_bytes);
+    U256::from_big_endian(&keccak256(slot_bytes))
+}
+
+/// The slot an ERC-7201 namespace starts at, i.e.
+/// `keccak256(abi.encode(uint256(keccak256(id)) - 1)) & ~bytes32(uint256(0xff))`
+pub fn erc7201_slot(id: &str) -> U256 {
+    let (hashed_id, _) =
+        U256::from_big_endian(&keccak256(id.as_bytes())).overflowing_sub(U256::one());
+    let mut preimage = [0u8; 32];
+    hashed_id.to_big_endian(&mut preimage);
+    U256::from_big_endian(&keccak256(preimage)) & !U256::from(0xff)
+}
+
+/// How much storage a value of a type takes
+enum StorageFootprint {
+    /// A value type of this many bytes, that can share a slot
+    Bytes(u8),
+    /// A type that takes up whole slots
+    Slots(U256),
+}
+
+/// Lays out values of the given types consecutively from slot 0, packing value types into shared slots. Returns
+/// the slot, offset, size and number of slots of each value, and the number of slots used in total
+#[allow(clippy::type_complexity)]
+fn pack_storage(
+    tys: &[NodeIdx],
+    analyzer: &impl GraphLike,
+) -> Result<(Vec<(U256, u8, Option<u8>, U256)>, U256), GraphError> {
+    let mut packed = vec![];
+    let mut slot = U256::zero();
+    let mut offset: u8 = 0;
+    for ty in tys {
+        match storage_footprint(*ty, analyzer)? {
+            StorageFootprint::Bytes(size) => {
+                if offset as u16 + size as u16 > 32 {
+                    slot += U256::one();
+                    offset = 0;
+                }
+                packed.push((slot, offset, Some(size), U256::one()));
+                offset += size;
+            }
+            StorageFootprint::Slots(slots) => {
+                if offset > 0 {
+                    slot += U256::one();
+                    offset = 0;
+                }
+                packed.push((slot, 0, None, slots));
+                slot += slots;
+            }
+        }
+    }
+    if offset > 0 {
+        slot += U256::one();
+    }
+    Ok((packed, slot))
+}
+
+fn storage_footprint(
+    ty: NodeIdx,
+    analyzer: &impl GraphLike,
+) -> Result<StorageFootprint, GraphError> {
+    Ok(match analyzer.node(ty) {
+        Node::Builtin(builtin) => match builtin {
+            Builtin::Address | Builtin::AddressPayable | Builtin::Payable => {
+                StorageFootprint::Bytes(20)
+            }
+            Builtin::Bool => StorageFootprint::Bytes(1),
+            Builtin::Int(size) | Builtin::Uint(size) => StorageFootprint::Bytes((size / 8) as u8),
+            Builtin::Bytes(size) => StorageFootprint::Bytes(*size),
+            // function types are assumed to be external: an address and a selector
+            Builtin::Func(..) => StorageFootprint::Bytes(24),
+            Builtin::Rational => StorageFootprint::Bytes(32),
+            Builtin::String | Builtin::DynamicBytes | Builtin::Array(_) | Builtin::Mapping(..) => {
+                StorageFootprint::Slots(U256::one())
+            }
+            Builtin::SizedArray(len, elem_ty) => {
+                match storage_footprint(vartype_idx(elem_ty), analyzer)? {
+                    StorageFootprint::Bytes(size) => {
+                        let per_slot = U256::from(32 / size as u64);
+                        StorageFootprint::Slots((*len + per_slot - 1) / per_slot)
+                    }
+                    StorageFootprint::Slots(slots) => StorageFootprint::Slots(*len * slots),
+                }
+            }
+        },
+        Node::Contract(_) => StorageFootprint::Bytes(20),
+        Node::Enum(enu) => StorageFootprint::Bytes(if enu.variants().len() > 256 { 2 } else { 1 }),
+        Node::Ty(ty) => storage_footprint(ty.ty, analyzer)?,
+        Node::Struct(_) => {
+            let tys = StructNode::from(ty)
+                .fields(analyzer)
+                .iter()
+                .map(|field| Ok(field.underlying(analyzer)?.ty))
+                .collect::<Result<Vec<_>, GraphError>>()?;
+            let (_packed, slots) = pack_storage(&tys, analyzer)?;
+            StorageFootprint::Slots(slots)
+        }
+        Node::VarType(vt) => storage_footprint(vartype_idx(vt), analyzer)?,
+        e => {
+            return Err(GraphError::NodeConfusion(format!(
+                "Node type confusion: expected node to be a type but it was: {e:?}"
+            )))
+        }
+    })
+}
+
+/// The node of the type a [`VarType`] refers to
+fn vartype_idx(vt: &VarType) -> NodeIdx {
+    match vt {
+        VarType::BuiltIn(bn, _) => (*bn).into(),
+        VarType::Concrete(cn) => (*cn).into(),
+        VarType::User(TypeNode::Contract(n), _) => (*n).into(),
+        VarType::User(TypeNode::Struct(n), _) => (*n).into(),
+        VarType::User(TypeNode::Enum(n), _) => (*n).into(),
+        VarType::User(TypeNode::Ty(n), _) => (*n).into(),
+        VarType::User(TypeNode::Func(n), _) => (*n).into(),
+        VarType::User(TypeNode::Unresolved(n), _) => *n,
+    }
+}
+
 impl From<ContractNode> for NodeIdx {
     fn from(val: ContractNode) -> Self {
         val.0.into()
@@ -1,7 +1,9 @@
 use crate::analyzer::GraphError;
 use crate::analyzer::{AnalyzerLike, GraphLike};
 use crate::AsDotStr;
+use crate::Edge;
 use crate::{Node, NodeIdx};
+use petgraph::{visit::EdgeRef, Direction};
 use solang_parser::pt::{ErrorDefinition, ErrorParameter, Expression, Identifier, Loc};
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
@@ -15,6 +17,28 @@ impl ErrorNode {
             ))),
         }
     }
+
+    /// Gets the name of the error
+    pub fn name(&self, analyzer: &impl GraphLike) -> Result<String, GraphError> {
+        Ok(self
+            .underlying(analyzer)?
+            .name
+            .clone()
+            .expect("Unnamed error")
+            .name)
+    }
+
+    /// Gets the parameters of the error, in definition order
+    pub fn params(&self, analyzer: &impl GraphLike) -> Vec<ErrorParamNode> {
+        let mut params = analyzer
+            .graph()
+            .edges_directed(self.0.into(), Direction::Incoming)
+            .filter(|edge| Edge::ErrorParam == *edge.weight())
+            .map(|edge| ErrorParamNode::from(edge.source()))
+            .collect::<Vec<_>>();
+        params.sort_by_key(|param| param.underlying(analyzer).unwrap().order);
+        params
+    }
 }
 impl AsDotStr for ErrorNode {
     fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
@@ -66,6 +90,29 @@ impl From<ErrorDefinition> for Error {
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct ErrorParamNode(pub usize);
 
+impl ErrorParamNode {
+    pub fn underlying<'a>(
+        &self,
+        analyzer: &'a impl GraphLike,
+    ) -> Result<&'a ErrorParam, GraphError> {
+        match analyzer.node(*self) {
+            Node::ErrorParam(param) => Ok(param),
+            e => Err(GraphError::NodeConfusion(format!(
+                "Node type confusion: expected node to be ErrorParam but it was: {e:?}"
+            ))),
+        }
+    }
+
+    /// Gets the name of the parameter, if it has one
+    pub fn maybe_name(&self, analyzer: &impl GraphLike) -> Result<Option<String>, GraphError> {
+        Ok(self
+            .underlying(analyzer)?
+            .name
+            .as_ref()
+            .map(|ident| ident.name.clone()))
+    }
+}
+
 impl From<NodeIdx> for ErrorParamNode {
     fn from(idx: NodeIdx) -> Self {
         ErrorParamNode(idx.index())
@@ -82,6 +129,7 @@ impl From<ErrorParamNode> for NodeIdx {
 pub struct ErrorParam {
     pub loc: Loc,
     pub ty: NodeIdx,
+    pub order: usize,
     pub name: Option<Identifier>,
 }
 
@@ -95,10 +143,12 @@ impl ErrorParam {
     pub fn new(
         analyzer: &mut (impl GraphLike + AnalyzerLike<Expr = Expression>),
         param: ErrorParameter,
+        order: usize,
     ) -> Self {
         ErrorParam {
             loc: param.loc,
             ty: analyzer.parse_expr(&param.ty, None),
+            order,
             name: param.name,
         }
     }
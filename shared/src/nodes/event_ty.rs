@@ -0,0 +1,171 @@
+use crate::analyzer::GraphError;
+use crate::analyzer::{AnalyzerLike, GraphLike};
+use crate::AsDotStr;
+use crate::Edge;
+use crate::{Node, NodeIdx};
+use petgraph::{visit::EdgeRef, Direction};
+use solang_parser::pt::{EventDefinition, EventParameter, Expression, Identifier, Loc};
+
+/// A wrapper of a node index that corresponds to an [`Event`]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct EventNode(pub usize);
+impl EventNode {
+    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> Result<&'a Event, GraphError> {
+        match analyzer.node(*self) {
+            Node::Event(event) => Ok(event),
+            e => Err(GraphError::NodeConfusion(format!(
+                "Node type confusion: expected node to be Event but it was: {e:?}"
+            ))),
+        }
+    }
+
+    /// Gets the name of the event
+    pub fn name(&self, analyzer: &impl GraphLike) -> Result<String, GraphError> {
+        Ok(self
+            .underlying(analyzer)?
+            .name
+            .clone()
+            .expect("Unnamed event")
+            .name)
+    }
+
+    /// Gets the sourcecode location of the event definition
+    pub fn loc(&self, analyzer: &impl GraphLike) -> Result<Loc, GraphError> {
+        Ok(self.underlying(analyzer)?.loc)
+    }
+
+    /// Gets the parameters of the event, in definition order
+    pub fn params(&self, analyzer: &impl GraphLike) -> Vec<EventParamNode> {
+        let mut params = analyzer
+            .graph()
+            .edges_directed(self.0.into(), Direction::Incoming)
+            .filter(|edge| Edge::EventParam == *edge.weight())
+            .map(|edge| EventParamNode::from(edge.source()))
+            .collect::<Vec<_>>();
+        params.sort_by_key(|param| param.underlying(analyzer).unwrap().order);
+        params
+    }
+}
+
+impl AsDotStr for EventNode {
+    fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
+        let underlying = self.underlying(analyzer).unwrap();
+        format!(
+            "event {}",
+            if let Some(name) = &underlying.name {
+                name.name.clone()
+            } else {
+                "".to_string()
+            },
+        )
+    }
+}
+
+impl From<EventNode> for NodeIdx {
+    fn from(val: EventNode) -> Self {
+        val.0.into()
+    }
+}
+
+impl From<NodeIdx> for EventNode {
+    fn from(idx: NodeIdx) -> Self {
+        EventNode(idx.index())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Event {
+    pub loc: Loc,
+    pub name: Option<Identifier>,
+    pub anonymous: bool,
+}
+
+impl From<Event> for Node {
+    fn from(val: Event) -> Self {
+        Node::Event(val)
+    }
+}
+
+impl From<EventDefinition> for Event {
+    fn from(event: EventDefinition) -> Event {
+        Event {
+            loc: event.loc,
+            name: event.name,
+            anonymous: event.anonymous,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct EventParamNode(pub usize);
+
+impl EventParamNode {
+    pub fn underlying<'a>(
+        &self,
+        analyzer: &'a impl GraphLike,
+    ) -> Result<&'a EventParam, GraphError> {
+        match analyzer.node(*self) {
+            Node::EventParam(param) => Ok(param),
+            e => Err(GraphError::NodeConfusion(format!(
+                "Node type confusion: expected node to be EventParam but it was: {e:?}"
+            ))),
+        }
+    }
+
+    /// Gets the name of the parameter, if it has one
+    pub fn maybe_name(&self, analyzer: &impl GraphLike) -> Result<Option<String>, GraphError> {
+        Ok(self
+            .underlying(analyzer)?
+            .name
+            .as_ref()
+            .map(|ident| ident.name.clone()))
+    }
+
+    /// Whether the parameter is `indexed`, i.e. it is a topic of the log
+    pub fn indexed(&self, analyzer: &impl GraphLike) -> Result<bool, GraphError> {
+        Ok(self.underlying(analyzer)?.indexed)
+    }
+}
+
+impl From<NodeIdx> for EventParamNode {
+    fn from(idx: NodeIdx) -> Self {
+        EventParamNode(idx.index())
+    }
+}
+
+impl From<EventParamNode> for NodeIdx {
+    fn from(val: EventParamNode) -> Self {
+        val.0.into()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EventParam {
+    pub loc: Loc,
+    pub ty: NodeIdx,
+    pub order: usize,
+    pub name: Option<Identifier>,
+    pub indexed: bool,
+}
+
+impl From<EventParam> for Node {
+    fn from(val: EventParam) -> Self {
+        Node::EventParam(val)
+    }
+}
+
+impl EventParam {
+    pub fn new(
+        analyzer: &mut (impl GraphLike + AnalyzerLike<Expr = Expression>),
+        param: EventParameter,
+        order: usize,
+    ) -> Self {
+        EventParam {
+            loc: param.loc,
+            ty: analyzer.parse_expr(&param.ty, None),
+            order,
+            name: param.name,
+            indexed: param.indexed,
+        }
+    }
+}
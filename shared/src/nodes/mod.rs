@@ -28,6 +28,8 @@ mod func_ty;
 pub use func_ty::*;
 mod err_ty;
 pub use err_ty::*;
+mod event_ty;
+pub use event_ty::*;
 mod var_ty;
 pub use var_ty::*;
 mod ty_ty;
@@ -222,6 +224,8 @@ impl VarType {
             | Node::FunctionCall
             | Node::FunctionReturn(..)
             | Node::ErrorParam(..)
+            | Node::Event(..)
+            | Node::EventParam(..)
             | Node::Field(..)
             | Node::SourceUnitPart(..)
             | Node::SourceUnit(..)
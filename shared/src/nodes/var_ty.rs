@@ -1,6 +1,7 @@
 use crate::analyzer::Search;
 use crate::nodes::GraphError;
 
+use crate::range::SolcRange;
 use crate::ContractNode;
 use crate::VarType;
 use crate::{
@@ -115,6 +116,53 @@ impl VarNode {
             .name)
     }
 
+    /// Whether the variable is declared `constant`, i.e. it has no storage
+    pub fn is_const(&self, analyzer: &impl GraphLike) -> Result<bool, GraphError> {
+        Ok(self
+            .underlying(analyzer)?
+            .attrs
+            .iter()
+            .any(|attr| matches!(attr, VariableAttribute::Constant(_))))
+    }
+
+    /// Whether the variable's type has a range of values, unlike arrays, mappings and structs
+    pub fn has_value_range(&self, analyzer: &impl GraphLike) -> Result<bool, GraphError> {
+        let Some(ty) = VarType::try_from_idx(analyzer, self.underlying(analyzer)?.ty) else {
+            return Ok(false);
+        };
+        Ok(!ty.is_dyn(analyzer)?
+            && !ty.is_mapping(analyzer)?
+            && !ty.is_sized_array(analyzer)?
+            && ty.default_range(analyzer)?.is_some())
+    }
+
+    /// Gets the range of a storage variable at deployment, before any constructor runs: its initializer if
+    /// it is a literal and zero otherwise. Returns `None` for types without a value range and for initializers
+    /// that aren't known until the contract is analyzed
+    pub fn deploy_range(&self, analyzer: &impl GraphLike) -> Result<Option<SolcRange>, GraphError> {
+        if !self.has_value_range(analyzer)? {
+            return Ok(None);
+        }
+        let underlying = self.underlying(analyzer)?;
+        let Some(ty) = VarType::try_from_idx(analyzer, underlying.ty) else {
+            return Ok(None);
+        };
+        match underlying.initializer {
+            Some(init) => match VarType::try_from_idx(analyzer, init) {
+                Some(VarType::Concrete(cnode)) => {
+                    Ok(SolcRange::from(cnode.underlying(analyzer)?.clone()))
+                }
+                _ => Ok(None),
+            },
+            None if underlying.initializer_expr.is_some() => Ok(None),
+            None => match ty {
+                // the type's range is the full range of its values rather than its zero value
+                VarType::BuiltIn(bn, _) => bn.zero_range(analyzer),
+                ty => ty.delete_range_result(analyzer),
+            },
+        }
+    }
+
     pub fn const_value(
         &self,
         loc: Loc,
@@ -541,6 +541,8 @@ impl RangeElem<Concrete> for RangeExpr<Concrete> {
     }
 
     fn uncache(&mut self) {
+        self.minimized = None;
+        self.maximized = None;
         self.uncache_exec();
     }
 }
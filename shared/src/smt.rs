@@ -0,0 +1,433 @@
+use crate::analyzer::{GraphError, GraphLike};
+use crate::context::{ContextNode, ContextVarNode};
+use crate::nodes::{Builtin, Concrete, VarType};
+use crate::range::elem::RangeOp;
+use crate::range::elem_ty::Elem;
+use crate::range::Range;
+use crate::Node;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The default time a solver gets for each check, in milliseconds
+pub const DEFAULT_SMT_TIMEOUT_MS: u64 = 5000;
+
+/// A locally installed SMT solver that reads SMT-LIB2 scripts from stdin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmtSolver {
+    /// The path to the solver's binary
+    pub path: PathBuf,
+    /// The arguments that make the solver read a script from stdin
+    pub args: Vec<String>,
+    /// The answers to the scripts already checked, so that contexts whose dependencies encode to the same script
+    /// (i.e. after a `require` that adds no dependency) are only checked once
+    pub cache: RefCell<BTreeMap<String, SmtResult>>,
+}
+
+/// The answer of a solver to `(check-sat)`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SmtResult {
+    Sat,
+    Unsat,
+    /// The solver gave up, i.e. it timed out
+    Unknown,
+}
+
+impl SmtSolver {
+    /// A z3 binary, with the given timeout per check in milliseconds
+    pub fn z3(path: impl Into<PathBuf>, timeout_ms: u64) -> Self {
+        Self {
+            path: path.into(),
+            args: vec![
+                "-in".to_string(),
+                "-smt2".to_string(),
+                format!("-t:{timeout_ms}"),
+            ],
+            cache: Default::default(),
+        }
+    }
+
+    /// A cvc5 binary, with the given timeout per check in milliseconds
+    pub fn cvc5(path: impl Into<PathBuf>, timeout_ms: u64) -> Self {
+        Self {
+            path: path.into(),
+            args: vec![
+                "--lang".to_string(),
+                "smt2".to_string(),
+                format!("--tlimit-per={timeout_ms}"),
+            ],
+            cache: Default::default(),
+        }
+    }
+
+    /// Guesses the solver from the name of its binary, defaulting to z3
+    pub fn from_path(path: impl AsRef<Path>, timeout_ms: u64) -> Self {
+        let path = path.as_ref();
+        let is_cvc = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase().starts_with("cvc"))
+            .unwrap_or(false);
+        if is_cvc {
+            Self::cvc5(path, timeout_ms)
+        } else {
+            Self::z3(path, timeout_ms)
+        }
+    }
+
+    /// Runs the solver on a script ending in a single `(check-sat)`, or returns the answer to the same script
+    /// if it was already checked. Failures aren't cached
+    pub fn check(&self, script: &str) -> Result<SmtResult, String> {
+        if let Some(res) = self.cache.borrow().get(script) {
+            return Ok(*res);
+        }
+        let res = self.run(script)?;
+        self.cache.borrow_mut().insert(script.to_string(), res);
+        Ok(res)
+    }
+
+    /// Runs the solver on a script ending in a single `(check-sat)`
+    fn run(&self, script: &str) -> Result<SmtResult, String> {
+        let mut child = Command::new(&self.path)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Could not run {}: {e}", self.path.display()))?;
+        {
+            // stdin is closed when dropped at the end of this block, which ends the script
+            let mut stdin = child.stdin.take().expect("stdin is piped");
+            stdin
+                .write_all(script.as_bytes())
+                .map_err(|e| format!("Could not write to {}: {e}", self.path.display()))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Could not run {}: {e}", self.path.display()))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        match stdout.lines().map(str::trim).find(|line| !line.is_empty()) {
+            Some("sat") => Ok(SmtResult::Sat),
+            Some("unsat") => Ok(SmtResult::Unsat),
+            Some("unknown") => Ok(SmtResult::Unknown),
+            _ => Err(format!(
+                "Unexpected output from {}: {} {}",
+                self.path.display(),
+                stdout.trim(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+        }
+    }
+}
+
+/// Translates the dependencies of a context into an SMT-LIB2 script over 256 bit bit-vectors.
+///
+/// The encoding over-approximates the states that reach the context, so an UNSAT answer means the context is
+/// unreachable while a SAT answer proves nothing:
+///  - checked arithmetic wraps, which only adds states that would have reverted
+///  - operations, types and range elements that aren't modeled become fresh unconstrained symbols, and
+///    dependencies that can't be expressed as a boolean term are left out
+///  - each variable is bounded by its evaluated minimum and maximum, and related to the variables its range
+///    refers to directly. Exclusions aren't modeled
+#[derive(Debug, Default)]
+pub struct SmtEncoder {
+    decls: Vec<String>,
+    asserts: Vec<String>,
+    declared: BTreeSet<usize>,
+    fresh: usize,
+}
+
+impl SmtEncoder {
+    /// Encodes the dependencies of a context, returning `None` if none of them could be expressed
+    pub fn encode_ctx(
+        ctx: ContextNode,
+        analyzer: &impl GraphLike,
+    ) -> Result<Option<String>, GraphError> {
+        let mut encoder = Self::default();
+        let mut any_dep = false;
+        for dep in ctx.ctx_deps(analyzer)?.into_values() {
+            if let Some(term) = encoder.bool_term(dep, analyzer)? {
+                encoder.asserts.push(format!("(assert {term})"));
+                any_dep = true;
+            }
+        }
+        if !any_dep {
+            return Ok(None);
+        }
+        Ok(Some(encoder.script()))
+    }
+
+    /// The script made up of the declarations and assertions encoded so far
+    pub fn script(&self) -> String {
+        let mut script = "(set-logic QF_BV)\n".to_string();
+        self.decls
+            .iter()
+            .chain(self.asserts.iter())
+            .for_each(|line| {
+                script.push_str(line);
+                script.push('\n');
+            });
+        script.push_str("(check-sat)\n");
+        script
+    }
+
+    /// A boolean term for a boolean variable, if it is a constant, a comparison, a boolean combination of
+    /// expressible terms, or a plain boolean variable
+    fn bool_term(
+        &mut self,
+        var: ContextVarNode,
+        analyzer: &impl GraphLike,
+    ) -> Result<Option<String>, GraphError> {
+        if let Some(tmp) = var.tmp_of(analyzer)? {
+            return match (tmp.op, tmp.rhs) {
+                (RangeOp::Not, _) => Ok(self
+                    .bool_term(tmp.lhs, analyzer)?
+                    .map(|lhs| format!("(not {lhs})"))),
+                (RangeOp::And | RangeOp::Or, Some(rhs)) => {
+                    let (Some(lhs), Some(rhs)) = (
+                        self.bool_term(tmp.lhs, analyzer)?,
+                        self.bool_term(rhs, analyzer)?,
+                    ) else {
+                        return Ok(None);
+                    };
+                    let op = if tmp.op == RangeOp::And { "and" } else { "or" };
+                    Ok(Some(format!("({op} {lhs} {rhs})")))
+                }
+                (RangeOp::Eq | RangeOp::Neq, Some(rhs))
+                    if is_bool(tmp.lhs, analyzer)? || is_bool(rhs, analyzer)? =>
+                {
+                    let (Some(lhs), Some(rhs)) = (
+                        self.bool_term(tmp.lhs, analyzer)?,
+                        self.bool_term(rhs, analyzer)?,
+                    ) else {
+                        return Ok(None);
+                    };
+                    Ok(Some(cmp_term(tmp.op, false, &lhs, &rhs)))
+                }
+                (
+                    RangeOp::Lt
+                    | RangeOp::Lte
+                    | RangeOp::Gt
+                    | RangeOp::Gte
+                    | RangeOp::Eq
+                    | RangeOp::Neq,
+                    Some(rhs),
+                ) => {
+                    let signed = is_signed(tmp.lhs, analyzer)? || is_signed(rhs, analyzer)?;
+                    let lhs = self.bv_term(tmp.lhs, analyzer)?;
+                    let rhs = self.bv_term(rhs, analyzer)?;
+                    Ok(Some(cmp_term(tmp.op, signed, &lhs, &rhs)))
+                }
+                _ => Ok(None),
+            };
+        }
+
+        if var.is_const(analyzer)? {
+            return Ok(
+                match var
+                    .evaled_range_min(analyzer)?
+                    .and_then(|min| min.maybe_concrete())
+                    .map(|min| min.val)
+                {
+                    Some(Concrete::Bool(b)) => Some(b.to_string()),
+                    _ => None,
+                },
+            );
+        }
+
+        if is_bool(var, analyzer)? {
+            Ok(Some(self.declare(var, "Bool")))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// A bit-vector term for a variable: a literal for constants, an operation for temporary arithmetic
+    /// variables, and otherwise a symbol bounded by the variable's range
+    fn bv_term(
+        &mut self,
+        var: ContextVarNode,
+        analyzer: &impl GraphLike,
+    ) -> Result<String, GraphError> {
+        if var.is_const(analyzer)? {
+            let lit = var
+                .evaled_range_min(analyzer)?
+                .and_then(|min| min.maybe_concrete())
+                .and_then(|min| concrete_lit(&min.val));
+            return Ok(lit.unwrap_or_else(|| self.fresh_bv()));
+        }
+
+        if let Some(tmp) = var.tmp_of(analyzer)? {
+            let size = int_size(var, analyzer)?;
+            // operations that truncate to a type narrower than the 256 bits of the encoding aren't modeled
+            let truncates = matches!(
+                tmp.op,
+                RangeOp::Add(true)
+                    | RangeOp::Sub(true)
+                    | RangeOp::Mul(true)
+                    | RangeOp::Div(true)
+                    | RangeOp::Shl
+                    | RangeOp::BitNot
+            ) && size != Some(256);
+            let signed = is_signed(var, analyzer)?;
+            let term = match (bv_op(tmp.op, signed), tmp.rhs) {
+                _ if truncates || size.is_none() => None,
+                (Some("bvnot"), _) => Some(format!("(bvnot {})", self.bv_term(tmp.lhs, analyzer)?)),
+                (Some(op), Some(rhs)) => Some(format!(
+                    "({op} {} {})",
+                    self.bv_term(tmp.lhs, analyzer)?,
+                    self.bv_term(rhs, analyzer)?
+                )),
+                _ => match (tmp.op, tmp.rhs) {
+                    (RangeOp::Min | RangeOp::Max, Some(rhs)) => {
+                        let lhs = self.bv_term(tmp.lhs, analyzer)?;
+                        let rhs = self.bv_term(rhs, analyzer)?;
+                        let lt = cmp_term(RangeOp::Lt, signed, &lhs, &rhs);
+                        Some(if tmp.op == RangeOp::Min {
+                            format!("(ite {lt} {lhs} {rhs})")
+                        } else {
+                            format!("(ite {lt} {rhs} {lhs})")
+                        })
+                    }
+                    _ => None,
+                },
+            };
+            return Ok(term.unwrap_or_else(|| self.fresh_bv()));
+        }
+
+        let sym = format!("v{}", var.0);
+        if self.declared.contains(&var.0) {
+            return Ok(sym);
+        }
+        self.declare(var, "(_ BitVec 256)");
+
+        let signed = is_signed(var, analyzer)?;
+        let (min, max) = match var.ref_range(analyzer)? {
+            Some(range) => (
+                range.range_min().into_owned(),
+                range.range_max().into_owned(),
+            ),
+            None => return Ok(sym),
+        };
+        let evaled_min = var.evaled_range_min(analyzer)?;
+        let evaled_max = var.evaled_range_max(analyzer)?;
+        for (bound, evaled, op) in [
+            (min, evaled_min, RangeOp::Gte),
+            (max, evaled_max, RangeOp::Lte),
+        ] {
+            if let Some(lit) = evaled
+                .and_then(|evaled| evaled.maybe_concrete())
+                .and_then(|evaled| concrete_lit(&evaled.val))
+            {
+                self.asserts
+                    .push(format!("(assert {})", cmp_term(op, signed, &sym, &lit)));
+            }
+            // a bound that refers to another variable relates the two
+            if let Elem::Dynamic(dy) = bound {
+                if dy.idx.index() != var.0 && matches!(analyzer.node(dy.idx), Node::ContextVar(_)) {
+                    let other = self.bv_term(ContextVarNode::from(dy.idx), analyzer)?;
+                    self.asserts
+                        .push(format!("(assert {})", cmp_term(op, signed, &sym, &other)));
+                }
+            }
+        }
+        Ok(sym)
+    }
+
+    fn declare(&mut self, var: ContextVarNode, sort: &str) -> String {
+        let sym = format!("v{}", var.0);
+        if self.declared.insert(var.0) {
+            self.decls.push(format!("(declare-const {sym} {sort})"));
+        }
+        sym
+    }
+
+    fn fresh_bv(&mut self) -> String {
+        let sym = format!("f{}", self.fresh);
+        self.fresh += 1;
+        self.decls
+            .push(format!("(declare-const {sym} (_ BitVec 256))"));
+        sym
+    }
+}
+
+/// The comparison of two terms
+fn cmp_term(op: RangeOp, signed: bool, lhs: &str, rhs: &str) -> String {
+    let op = match (op, signed) {
+        (RangeOp::Eq, _) => "=",
+        (RangeOp::Neq, _) => return format!("(not (= {lhs} {rhs}))"),
+        (RangeOp::Lt, false) => "bvult",
+        (RangeOp::Lt, true) => "bvslt",
+        (RangeOp::Lte, false) => "bvule",
+        (RangeOp::Lte, true) => "bvsle",
+        (RangeOp::Gt, false) => "bvugt",
+        (RangeOp::Gt, true) => "bvsgt",
+        (RangeOp::Gte, false) => "bvuge",
+        (RangeOp::Gte, true) => "bvsge",
+        _ => unreachable!("not a comparison: {op:?}"),
+    };
+    format!("({op} {lhs} {rhs})")
+}
+
+/// The bit-vector function for an arithmetic or bitwise operation
+fn bv_op(op: RangeOp, signed: bool) -> Option<&'static str> {
+    Some(match (op, signed) {
+        (RangeOp::Add(_), _) => "bvadd",
+        (RangeOp::Sub(_), _) => "bvsub",
+        (RangeOp::Mul(_), _) => "bvmul",
+        (RangeOp::Div(_), false) => "bvudiv",
+        (RangeOp::Div(_), true) => "bvsdiv",
+        (RangeOp::Mod, false) => "bvurem",
+        (RangeOp::Mod, true) => "bvsrem",
+        (RangeOp::Shl, _) => "bvshl",
+        (RangeOp::Shr, false) => "bvlshr",
+        (RangeOp::Shr, true) => "bvashr",
+        (RangeOp::BitAnd, _) => "bvand",
+        (RangeOp::BitOr, _) => "bvor",
+        (RangeOp::BitXor, _) => "bvxor",
+        (RangeOp::BitNot, _) => "bvnot",
+        _ => return None,
+    })
+}
+
+/// A 256 bit literal for a concrete value. Signed integers are sign extended
+fn concrete_lit(c: &Concrete) -> Option<String> {
+    let val = match c {
+        Concrete::Int(_, val) => val.into_raw(),
+        c => c.into_u256()?,
+    };
+    Some(format!("(_ bv{val} 256)"))
+}
+
+fn is_bool(var: ContextVarNode, analyzer: &impl GraphLike) -> Result<bool, GraphError> {
+    Ok(match var.ty(analyzer)? {
+        VarType::BuiltIn(bn, _) => matches!(bn.underlying(analyzer)?, Builtin::Bool),
+        VarType::Concrete(c) => matches!(c.underlying(analyzer)?, Concrete::Bool(_)),
+        _ => false,
+    })
+}
+
+fn is_signed(var: ContextVarNode, analyzer: &impl GraphLike) -> Result<bool, GraphError> {
+    Ok(match var.ty(analyzer)? {
+        VarType::BuiltIn(bn, _) => matches!(bn.underlying(analyzer)?, Builtin::Int(_)),
+        VarType::Concrete(c) => matches!(c.underlying(analyzer)?, Concrete::Int(..)),
+        _ => false,
+    })
+}
+
+/// The size in bits of an integer variable. Arithmetic of other types isn't modeled
+fn int_size(var: ContextVarNode, analyzer: &impl GraphLike) -> Result<Option<u16>, GraphError> {
+    Ok(match var.ty(analyzer)? {
+        VarType::BuiltIn(bn, _) => match bn.underlying(analyzer)? {
+            Builtin::Uint(size) | Builtin::Int(size) => Some(*size),
+            _ => None,
+        },
+        VarType::Concrete(c) => match c.underlying(analyzer)? {
+            Concrete::Uint(size, _) | Concrete::Int(size, _) => Some(*size),
+            _ => None,
+        },
+        _ => None,
+    })
+}
@@ -4,7 +4,7 @@ use crate::analyzers::VarBoundAnalysis;
 use crate::analyzers::LocSpan;
 use crate::analyzers::{LocStrSpan, ReportConfig};
 
-use shared::analyzer::GraphLike;
+use shared::analyzer::{GraphError, GraphLike};
 use shared::{
     context::*,
     range::{range_string::*, Range, RangeEval, SolcRange},
@@ -16,6 +16,7 @@ use std::collections::{BTreeMap, BTreeSet};
 
 pub static MIN_COLOR: Color = Color::Fixed(111);
 pub static MAX_COLOR: Color = Color::Fixed(106);
+pub static EMIT_COLOR: Color = Color::Fixed(176);
 
 #[derive(PartialEq, Eq, Clone)]
 pub struct AnalysisItem {
@@ -128,6 +129,137 @@ impl OrderedAnalysis {
     }
 }
 
+/// An emitted event, with the bounds of its arguments at the time of the emit
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct EmitItem {
+    pub loc: LocStrSpan,
+    pub event: String,
+    pub args: Vec<(String, Vec<RangePart>)>,
+}
+
+impl EmitItem {
+    pub fn new(
+        file_mapping: &BTreeMap<usize, String>,
+        analyzer: &impl GraphLike,
+        report_config: &ReportConfig,
+        emit: &ContextEmit,
+    ) -> Result<Self, GraphError> {
+        let mut args = vec![];
+        for arg in emit.args.iter() {
+            args.push((
+                arg.display_name(analyzer)?,
+                arg_parts(analyzer, report_config, arg),
+            ));
+        }
+        Ok(Self {
+            loc: LocStrSpan::new(file_mapping, emit.loc),
+            event: emit.event.name(analyzer)?,
+            args,
+        })
+    }
+
+    pub fn to_cli_string(&self) -> String {
+        format!("emits {}", call_string(&self.event, &self.args, true))
+    }
+
+    pub fn to_normal_string(&self) -> String {
+        format!("emits {}", call_string(&self.event, &self.args, false))
+    }
+}
+
+/// The error a path reverted with, with the bounds of its arguments at the time of the revert.
+/// Arguments are named by the error's parameters where possible
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct RevertItem {
+    pub loc: LocStrSpan,
+    pub error: String,
+    pub args: Vec<(String, Vec<RangePart>)>,
+}
+
+impl RevertItem {
+    pub fn new(
+        file_mapping: &BTreeMap<usize, String>,
+        analyzer: &impl GraphLike,
+        report_config: &ReportConfig,
+        revert: &ContextRevert,
+    ) -> Result<Self, GraphError> {
+        let (error, param_names) = match revert.error {
+            Some(error) => (
+                error.name(analyzer)?,
+                error
+                    .params(analyzer)
+                    .iter()
+                    .map(|param| param.maybe_name(analyzer))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            None => ("Error".to_string(), vec![]),
+        };
+        let mut args = vec![];
+        for (i, arg) in revert.args.iter().enumerate() {
+            let name = match param_names.get(i).cloned().flatten() {
+                Some(name) => name,
+                None => arg.display_name(analyzer)?,
+            };
+            args.push((name, arg_parts(analyzer, report_config, arg)));
+        }
+        Ok(Self {
+            loc: LocStrSpan::new(file_mapping, revert.loc),
+            error,
+            args,
+        })
+    }
+
+    pub fn to_cli_string(&self) -> String {
+        format!(
+            "reverts with {}",
+            call_string(&self.error, &self.args, true)
+        )
+    }
+
+    pub fn to_normal_string(&self) -> String {
+        format!(
+            "reverts with {}",
+            call_string(&self.error, &self.args, false)
+        )
+    }
+}
+
+/// The range parts of an argument of an event or error
+fn arg_parts(
+    analyzer: &impl GraphLike,
+    report_config: &ReportConfig,
+    arg: &ContextVarNode,
+) -> Vec<RangePart> {
+    arg.ref_range(analyzer)
+        .unwrap()
+        .map(|range| range_parts(analyzer, report_config, &range).0)
+        .unwrap_or_default()
+}
+
+/// Formats an event or error with its arguments, i.e. `Transfer(from, amount ∈ [ 0, 100 ])`. Literals are
+/// already displayed by their name, so their parts are omitted
+fn call_string(name: &str, args: &[(String, Vec<RangePart>)], cli: bool) -> String {
+    format!(
+        "{name}({})",
+        args.iter()
+            .map(|(arg_name, parts)| {
+                let parts: &[RangePart] = match &parts[..] {
+                    [RangePart::Equal(val)] if val == arg_name => &[],
+                    parts => parts,
+                };
+                parts.iter().fold(arg_name.clone(), |acc, part| {
+                    if cli {
+                        format!("{acc}{}", part.clone().to_cli_string())
+                    } else {
+                        format!("{acc}{}", part.to_normal_string())
+                    }
+                })
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Hash)]
 pub enum RangePart {
     Equal(String),
@@ -1,6 +1,7 @@
 use crate::analyzers::range_parts;
 use crate::analyzers::VarBoundAnalysis;
 use crate::analyzers::VarBoundAnalyzer;
+use crate::analyzers::{EmitItem, RevertItem, EMIT_COLOR};
 
 use crate::analyzers::{LocStrSpan, ReportConfig, ReportDisplay};
 use ariadne::ReportKind;
@@ -265,6 +266,23 @@ impl<'a> FunctionVarsBoundAnalysis {
                         })
                         .collect::<Vec<_>>(),
                 );
+                labels.extend(ctx.path_emits(analyzer).unwrap().iter().map(|emit| {
+                    let item =
+                        EmitItem::new(file_mapping, analyzer, &self.report_config, emit).unwrap();
+                    Label::new(item.loc.clone())
+                        .with_message(item.to_cli_string().fg(EMIT_COLOR))
+                        .with_color(EMIT_COLOR)
+                        .with_order(40)
+                }));
+                labels.extend(ctx.reverts_with(analyzer).unwrap().map(|revert| {
+                    let item =
+                        RevertItem::new(file_mapping, analyzer, &self.report_config, &revert)
+                            .unwrap();
+                    Label::new(item.loc.clone())
+                        .with_message(item.to_cli_string().fg(Color::Red))
+                        .with_color(Color::Red)
+                        .with_order(45)
+                }));
                 if !self_handled {
                     if let Some(body) = ctx
                         .associated_fn(analyzer)
@@ -0,0 +1,288 @@
+use crate::analyzers::{range_parts, LocStrSpan, ReportConfig, ReportDisplay};
+use crate::context::exprs::IntoExprErr;
+use crate::ExprErr;
+
+use ariadne::{Cache, Color, Config, Fmt, Label, Report, ReportKind, Span};
+use shared::analyzer::{AnalyzerLike, GraphError, GraphLike, Search};
+use shared::context::{Context, ContextEdge, ContextNode, ContextVar, ContextVarNode};
+use shared::nodes::{join_ranges, ContractNode, VarNode, VarType};
+use shared::range::{elem::RangeElem, Range, SolcRange};
+use shared::{Edge, Node};
+use solang_parser::pt::{Expression, Loc};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// The number of rounds of applying every function to the storage after which bounds that keep growing are
+/// widened to the full range of their type
+pub const INVARIANT_WIDEN_AFTER: usize = 3;
+
+/// The inferred storage invariants of a contract: the ranges its storage variables stay within across any
+/// sequence of calls after it is deployed
+#[derive(Debug, Clone)]
+pub struct InvariantAnalysis {
+    /// The contract's location string span
+    pub contract_loc: LocStrSpan,
+    /// The contract
+    pub contract: ContractNode,
+    /// The storage variables with a range of values, their location string spans and their invariant ranges. The
+    /// range is `None` if nothing is known about the variable
+    pub invariants: Vec<(VarNode, LocStrSpan, Option<SolcRange>)>,
+    /// The number of rounds it took to reach the fixpoint
+    pub rounds: usize,
+}
+
+impl ReportDisplay for InvariantAnalysis {
+    fn report_kind(&self) -> ReportKind {
+        ReportKind::Custom("Invariants", Color::Green)
+    }
+
+    fn msg(&self, analyzer: &impl GraphLike) -> String {
+        format!(
+            "Storage invariants for contract: {}, reached after {} rounds",
+            format!("contract {}", self.contract.name(analyzer).unwrap()).fg(Color::Green),
+            self.rounds
+        )
+    }
+
+    fn labels(&self, analyzer: &impl GraphLike) -> Vec<Label<LocStrSpan>> {
+        let config = ReportConfig::default();
+        self.invariants
+            .iter()
+            .enumerate()
+            .map(|(i, (var, span, maybe_range))| {
+                let name = var.name(analyzer).unwrap();
+                let msg = if let Some(range) = maybe_range {
+                    let (parts, _unsat) = range_parts(analyzer, &config, range);
+                    parts
+                        .into_iter()
+                        .fold(name, |acc, part| format!("{acc}{}", part.to_cli_string()))
+                } else {
+                    format!("{name}: unknown")
+                };
+                Label::new(span.clone())
+                    .with_message(msg)
+                    .with_color(Color::Green)
+                    .with_order(i as i32)
+            })
+            .collect()
+    }
+
+    fn reports(&self, analyzer: &impl GraphLike) -> Vec<Report<LocStrSpan>> {
+        let mut report = Report::build(
+            self.report_kind(),
+            self.contract_loc.source(),
+            self.contract_loc.start(),
+        )
+        .with_message(self.msg(analyzer))
+        .with_config(
+            Config::default()
+                .with_cross_gap(false)
+                .with_underlines(true)
+                .with_tab_width(4),
+        );
+        report.add_labels(self.labels(analyzer));
+        vec![report.finish()]
+    }
+
+    fn print_reports(&self, mut src: &mut impl Cache<String>, analyzer: &impl GraphLike) {
+        let reports = &self.reports(analyzer);
+        for report in reports.iter() {
+            report.print(&mut src).unwrap();
+        }
+    }
+
+    fn eprint_reports(&self, mut src: &mut impl Cache<String>, analyzer: &impl GraphLike) {
+        let reports = &self.reports(analyzer);
+        reports.iter().for_each(|report| {
+            report.eprint(&mut src).unwrap();
+        });
+    }
+}
+
+impl<T> InvariantAnalyzer for T where
+    T: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Search + Sized
+{
+}
+/// Infers the storage invariants of contracts
+pub trait InvariantAnalyzer:
+    AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Search + Sized
+{
+    /// Infers the ranges the storage variables of a contract stay within across any sequence of calls after it is
+    /// deployed, along with the number of rounds it took. Starting from the storage the constructors leave behind,
+    /// each round applies every public and external function to the storage and joins the storage they leave
+    /// behind on their successful paths with the storage before, until nothing changes. Bounds that still grow
+    /// after [`INVARIANT_WIDEN_AFTER`] rounds are widened to the full range of their type.
+    ///
+    /// The functions must have been analyzed from the full range of each storage variable (i.e. without
+    /// `storage_from_constructor`). A variable a function narrows (i.e. in a `require`) takes the range it was
+    /// narrowed to from the full range of its type, and reentrant calls are not accounted for
+    fn storage_invariants(
+        &mut self,
+        contract: ContractNode,
+    ) -> Result<(BTreeMap<VarNode, Option<SolcRange>>, usize), ExprErr> {
+        let loc = contract.loc(self).into_expr_err(Loc::Implicit)?;
+        let mut state = BTreeMap::default();
+        for (var, range) in contract.deployed_storage(self).into_expr_err(loc)? {
+            if var.has_value_range(self).into_expr_err(loc)? {
+                state.insert(var, range);
+            }
+        }
+
+        let body_ctxs = contract
+            .entry_functions(self)
+            .into_iter()
+            .filter_map(|func| func.maybe_body_ctx(self))
+            .collect::<Vec<_>>();
+        let Some(first_ctx) = body_ctxs.first() else {
+            return Ok((state, 0));
+        };
+        // the storage of each round lives in a context of its own, which needs some function to belong to
+        let state_fn = first_ctx.associated_fn(self).into_expr_err(loc)?;
+        let ctx_name = format!("{} invariants", contract.name(self).into_expr_err(loc)?);
+
+        let mut rounds = 0;
+        loop {
+            rounds += 1;
+            let state_ctx = ContextNode::from(self.add_node(Node::Context(Context::new(
+                state_fn,
+                ctx_name.clone(),
+                loc,
+            ))));
+            let mut storage = BTreeMap::default();
+            for (var, maybe_range) in state.iter() {
+                let Some(mut cvar) = ContextVar::maybe_from_user_ty(self, loc, (*var).into())
+                else {
+                    continue;
+                };
+                if let Some(range) = maybe_range {
+                    cvar.ty.set_range(range.clone()).into_expr_err(loc)?;
+                }
+                let cvar = ContextVarNode::from(self.add_node(Node::ContextVar(cvar)));
+                state_ctx.add_var(cvar, self).into_expr_err(loc)?;
+                self.add_edge(cvar, state_ctx, Edge::Context(ContextEdge::Variable));
+                storage.insert(var.name(self).into_expr_err(loc)?, (*var, cvar));
+            }
+            let storage_vars = storage
+                .iter()
+                .map(|(name, (_, cvar))| (name.clone(), *cvar))
+                .collect::<BTreeMap<_, _>>();
+
+            let mut next: BTreeMap<VarNode, Vec<Option<SolcRange>>> = state
+                .iter()
+                .map(|(var, range)| (*var, vec![range.clone()]))
+                .collect();
+            for body_ctx in body_ctxs.iter() {
+                let Some(effects) = state_ctx
+                    .storage_effects(loc, *body_ctx, &storage_vars, self)
+                    .into_expr_err(loc)?
+                else {
+                    continue;
+                };
+                for (name, value) in effects {
+                    let Some((var, _)) = storage.get(&name) else {
+                        continue;
+                    };
+                    let range = match value {
+                        Some(value) => value.range(self).into_expr_err(loc)?,
+                        None => None,
+                    };
+                    next.entry(*var).or_default().push(range);
+                }
+            }
+
+            let mut changed = false;
+            let mut next_state = BTreeMap::default();
+            for (var, ranges) in next {
+                let mut range = join_ranges(ranges, self).into_expr_err(loc)?;
+                let prev = state.get(&var).cloned().flatten();
+                if rounds > INVARIANT_WIDEN_AFTER
+                    && !same_range(&prev, &range, self).into_expr_err(loc)?
+                {
+                    range = widen_range(var, prev.clone(), range, self).into_expr_err(loc)?;
+                }
+                if !same_range(&prev, &range, self).into_expr_err(loc)? {
+                    changed = true;
+                }
+                next_state.insert(var, range);
+            }
+            state = next_state;
+            if !changed {
+                return Ok((state, rounds));
+            }
+        }
+    }
+
+    /// Infers the storage invariants of a contract, see [`InvariantAnalyzer::storage_invariants`]
+    fn invariants_for(
+        &mut self,
+        file_mapping: &BTreeMap<usize, String>,
+        contract: ContractNode,
+    ) -> Result<InvariantAnalysis, ExprErr> {
+        let (state, rounds) = self.storage_invariants(contract)?;
+        let loc = contract.loc(self).into_expr_err(Loc::Implicit)?;
+        let mut invariants = state
+            .into_iter()
+            .map(|(var, range)| {
+                let var_loc = var.underlying(self).into_expr_err(loc)?.loc;
+                Ok((var, LocStrSpan::new(file_mapping, var_loc), range))
+            })
+            .collect::<Result<Vec<_>, ExprErr>>()?;
+        invariants.sort_by_key(|(_, span, _)| span.clone());
+        Ok(InvariantAnalysis {
+            contract_loc: LocStrSpan::new(file_mapping, loc),
+            contract,
+            invariants,
+            rounds,
+        })
+    }
+}
+
+/// Whether two ranges have the same bounds. Unknown ranges are the same as each other
+fn same_range(
+    lhs: &Option<SolcRange>,
+    rhs: &Option<SolcRange>,
+    analyzer: &impl GraphLike,
+) -> Result<bool, GraphError> {
+    match (lhs, rhs) {
+        (None, None) => Ok(true),
+        (Some(lhs), Some(rhs)) => Ok(matches!(
+            lhs.evaled_range_min(analyzer)?
+                .range_ord(&rhs.evaled_range_min(analyzer)?),
+            Some(Ordering::Equal)
+        ) && matches!(
+            lhs.evaled_range_max(analyzer)?
+                .range_ord(&rhs.evaled_range_max(analyzer)?),
+            Some(Ordering::Equal)
+        )),
+        _ => Ok(false),
+    }
+}
+
+/// Widens the bounds of a variable's range that grew since the previous round to the bounds of its type
+fn widen_range(
+    var: VarNode,
+    prev: Option<SolcRange>,
+    range: Option<SolcRange>,
+    analyzer: &impl GraphLike,
+) -> Result<Option<SolcRange>, GraphError> {
+    let (Some(prev), Some(range)) = (prev, range) else {
+        return Ok(None);
+    };
+    let Some(ty) = VarType::try_from_idx(analyzer, var.underlying(analyzer)?.ty) else {
+        return Ok(None);
+    };
+    let Some(full) = ty.default_range(analyzer)? else {
+        return Ok(None);
+    };
+    let min = range.evaled_range_min(analyzer)?;
+    let max = range.evaled_range_max(analyzer)?;
+    let min = match min.range_ord(&prev.evaled_range_min(analyzer)?) {
+        Some(Ordering::Greater) | Some(Ordering::Equal) => min,
+        _ => full.evaled_range_min(analyzer)?,
+    };
+    let max = match max.range_ord(&prev.evaled_range_max(analyzer)?) {
+        Some(Ordering::Less) | Some(Ordering::Equal) => max,
+        _ => full.evaled_range_max(analyzer)?,
+    };
+    Ok(Some(SolcRange::new(min, max, vec![])))
+}
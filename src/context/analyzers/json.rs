@@ -0,0 +1,404 @@
+use crate::analyzers::{
+    range_parts, AnalysisItem, EmitItem, FunctionVarsBoundAnalysis, LocSpan, LocStrSpan, RangePart,
+    RevertItem, StrippedAnalysisItem, VarBoundAnalysis,
+};
+use crate::exprs::{ExprErr, IntoExprErr};
+
+use ariadne::Span;
+use serde::Serialize;
+use shared::analyzer::GraphLike;
+use shared::context::*;
+use solang_parser::pt::{Loc, StorageLocation};
+use std::collections::BTreeMap;
+
+/// The version of the JSON schema. Bump this whenever a field is removed, renamed, or changes meaning.
+/// Adding new fields is considered backwards compatible and does not require a bump.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Top level JSON document emitted by `--format json`
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonReport {
+    /// Schema version, see [JSON_SCHEMA_VERSION]
+    pub version: u32,
+    /// Bounds analysis for each analyzed function
+    pub functions: Vec<JsonFunctionReport>,
+    /// Errors encountered while analyzing
+    pub errors: Vec<JsonError>,
+}
+
+impl JsonReport {
+    pub fn new(functions: Vec<JsonFunctionReport>, errors: Vec<JsonError>) -> Self {
+        Self {
+            version: JSON_SCHEMA_VERSION,
+            functions,
+            errors,
+        }
+    }
+}
+
+/// A source span: the file name and the byte offsets into it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonSpan {
+    pub file: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl From<&LocStrSpan> for JsonSpan {
+    fn from(span: &LocStrSpan) -> Self {
+        Self {
+            file: span.source().clone(),
+            start: span.start(),
+            end: span.end(),
+        }
+    }
+}
+
+impl JsonSpan {
+    pub fn new(file_mapping: &BTreeMap<usize, String>, loc: Loc) -> Self {
+        (&LocStrSpan::new(file_mapping, loc)).into()
+    }
+}
+
+/// How a context was killed, and where
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonKilled {
+    /// One of `ended`, `unreachable`, `revert`, `parse_error`
+    pub kind: &'static str,
+    pub message: String,
+    pub span: JsonSpan,
+}
+
+impl JsonKilled {
+    pub fn new(span: &LocStrSpan, kind: &KilledKind) -> Self {
+        Self {
+            kind: killed_kind_str(kind),
+            message: kind.analysis_str().to_string(),
+            span: span.into(),
+        }
+    }
+}
+
+pub fn killed_kind_str(kind: &KilledKind) -> &'static str {
+    match kind {
+        KilledKind::Ended => "ended",
+        KilledKind::Unreachable => "unreachable",
+        KilledKind::Revert => "revert",
+        KilledKind::ParseError => "parse_error",
+    }
+}
+
+/// A range, broken into its parts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JsonRangePart {
+    Equal { value: String },
+    Inclusion { min: String, max: String },
+    Exclusion { parts: Vec<JsonRangePart> },
+}
+
+impl From<&RangePart> for JsonRangePart {
+    fn from(part: &RangePart) -> Self {
+        match part {
+            RangePart::Equal(value) => JsonRangePart::Equal {
+                value: value.clone(),
+            },
+            RangePart::Inclusion(min, max) => JsonRangePart::Inclusion {
+                min: min.clone(),
+                max: max.clone(),
+            },
+            RangePart::Exclusion(parts) => JsonRangePart::Exclusion {
+                parts: parts.iter().map(Into::into).collect(),
+            },
+        }
+    }
+}
+
+/// A context dependency (i.e. the result of a `require` or `if` condition) with its range
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonConditional {
+    pub name: String,
+    pub parts: Vec<JsonRangePart>,
+}
+
+impl From<&(String, Vec<RangePart>)> for JsonConditional {
+    fn from((name, parts): &(String, Vec<RangePart>)) -> Self {
+        Self {
+            name: name.clone(),
+            parts: parts.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A single bound change of a variable
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonAnalysisItem {
+    pub name: String,
+    /// Whether this is the initial bound of the variable
+    pub init: bool,
+    pub order: i32,
+    /// One of `memory`, `storage`, `calldata`, or null
+    pub storage: Option<&'static str>,
+    pub span: JsonSpan,
+    pub parts: Vec<JsonRangePart>,
+    pub unsat: bool,
+}
+
+impl From<&AnalysisItem> for JsonAnalysisItem {
+    fn from(item: &AnalysisItem) -> Self {
+        Self {
+            name: item.name.clone(),
+            init: item.init,
+            order: item.order,
+            storage: item.storage.as_ref().map(storage_str),
+            span: (&item.loc).into(),
+            parts: item.parts.iter().map(Into::into).collect(),
+            unsat: item.unsat,
+        }
+    }
+}
+
+impl JsonAnalysisItem {
+    pub fn from_stripped(
+        file_mapping: &BTreeMap<usize, String>,
+        item: &StrippedAnalysisItem,
+    ) -> Self {
+        let LocSpan(loc) = item.loc;
+        Self {
+            name: item.name.clone(),
+            init: item.init,
+            order: item.order,
+            storage: None,
+            span: JsonSpan::new(file_mapping, loc),
+            parts: item.parts.iter().map(Into::into).collect(),
+            unsat: item.unsat,
+        }
+    }
+}
+
+pub fn storage_str(storage: &StorageLocation) -> &'static str {
+    match storage {
+        StorageLocation::Memory(..) => "memory",
+        StorageLocation::Storage(..) => "storage",
+        StorageLocation::Calldata(..) => "calldata",
+    }
+}
+
+/// A return of a context
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonReturn {
+    pub name: String,
+    pub span: JsonSpan,
+    pub parts: Vec<JsonRangePart>,
+}
+
+/// An argument of an emitted event or of the error of a revert
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonArg {
+    pub name: String,
+    pub parts: Vec<JsonRangePart>,
+}
+
+/// An event emitted along a path
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonEmit {
+    pub event: String,
+    pub span: JsonSpan,
+    pub args: Vec<JsonArg>,
+}
+
+impl From<&EmitItem> for JsonEmit {
+    fn from(item: &EmitItem) -> Self {
+        Self {
+            event: item.event.clone(),
+            span: (&item.loc).into(),
+            args: item
+                .args
+                .iter()
+                .map(|(name, parts)| JsonArg {
+                    name: name.clone(),
+                    parts: parts.iter().map(Into::into).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The error a path reverted with
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonRevert {
+    pub error: String,
+    pub span: JsonSpan,
+    pub args: Vec<JsonArg>,
+}
+
+impl From<&RevertItem> for JsonRevert {
+    fn from(item: &RevertItem) -> Self {
+        Self {
+            error: item.error.clone(),
+            span: (&item.loc).into(),
+            args: item
+                .args
+                .iter()
+                .map(|(name, parts)| JsonArg {
+                    name: name.clone(),
+                    parts: parts.iter().map(Into::into).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The analysis of a single path (subcontext) through a function
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonContextReport {
+    pub path: String,
+    pub killed: Option<JsonKilled>,
+    pub conditionals: Vec<JsonConditional>,
+    pub items: Vec<JsonAnalysisItem>,
+    pub returns: Vec<JsonReturn>,
+    /// The events emitted along the path, in order
+    pub emits: Vec<JsonEmit>,
+    /// The error the path reverted with, if it reverted with a reason
+    pub reverts_with: Option<JsonRevert>,
+}
+
+/// The analysis of a function, with each of its paths
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonFunctionReport {
+    pub function: String,
+    pub span: JsonSpan,
+    pub killed: Option<JsonKilled>,
+    pub contexts: Vec<JsonContextReport>,
+}
+
+/// An error encountered during analysis
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonError {
+    pub message: String,
+    pub detail: String,
+    pub span: JsonSpan,
+}
+
+impl JsonError {
+    pub fn new(file_mapping: &BTreeMap<usize, String>, err: &ExprErr) -> Self {
+        Self {
+            message: err.report_msg().to_string(),
+            detail: err.msg().to_string(),
+            span: JsonSpan::new(file_mapping, err.loc()),
+        }
+    }
+}
+
+impl VarBoundAnalysis {
+    /// Converts the bound changes (and initial bound, if configured) into JSON items
+    pub fn json_items(&self, analyzer: &impl GraphLike) -> Vec<JsonAnalysisItem> {
+        let mut items = vec![];
+        if self.report_config.show_initial_bounds {
+            if let Some(init_item) = self.init_item(analyzer) {
+                items.push((&init_item).into());
+            }
+        }
+
+        items.extend(
+            self.bound_changes
+                .iter()
+                .enumerate()
+                .map(|(i, bound_change)| {
+                    let (parts, unsat) =
+                        range_parts(analyzer, &self.report_config, &bound_change.1);
+                    JsonAnalysisItem {
+                        name: self.var_display_name.clone(),
+                        init: false,
+                        order: i as i32,
+                        storage: self.storage.as_ref().map(storage_str),
+                        span: (&bound_change.0).into(),
+                        parts: parts.iter().map(Into::into).collect(),
+                        unsat,
+                    }
+                }),
+        );
+        items
+    }
+}
+
+impl FunctionVarsBoundAnalysis {
+    /// Converts the function analysis into its JSON representation
+    pub fn as_json(
+        &self,
+        file_mapping: &BTreeMap<usize, String>,
+        analyzer: &impl GraphLike,
+    ) -> Result<JsonFunctionReport, ExprErr> {
+        let loc = self.ctx_loc.1;
+        let mut contexts = vec![];
+        for (ctx, analyses) in self.vars_by_ctx.iter() {
+            let conditionals = analyses
+                .first()
+                .map(|analysis| analysis.conditionals(analyzer))
+                .unwrap_or_else(|| {
+                    VarBoundAnalysis {
+                        ctx: *ctx,
+                        report_config: self.report_config,
+                        ..Default::default()
+                    }
+                    .conditionals(analyzer)
+                });
+
+            let mut returns = vec![];
+            for (ret_loc, var) in ctx.return_nodes(analyzer).into_expr_err(loc)? {
+                let Some(range) = var.ref_range(analyzer).into_expr_err(loc)? else {
+                    continue;
+                };
+                let (parts, _unsat) = range_parts(analyzer, &self.report_config, &range);
+                returns.push(JsonReturn {
+                    name: var.display_name(analyzer).into_expr_err(loc)?,
+                    span: JsonSpan::new(file_mapping, ret_loc),
+                    parts: parts.iter().map(Into::into).collect(),
+                });
+            }
+
+            let mut emits = vec![];
+            for emit in ctx.path_emits(analyzer).into_expr_err(loc)? {
+                let item = EmitItem::new(file_mapping, analyzer, &self.report_config, &emit)
+                    .into_expr_err(loc)?;
+                emits.push((&item).into());
+            }
+
+            let reverts_with = match ctx.reverts_with(analyzer).into_expr_err(loc)? {
+                Some(revert) => {
+                    let item =
+                        RevertItem::new(file_mapping, analyzer, &self.report_config, &revert)
+                            .into_expr_err(loc)?;
+                    Some((&item).into())
+                }
+                None => None,
+            };
+
+            contexts.push(JsonContextReport {
+                path: ctx.path(analyzer),
+                killed: ctx
+                    .killed_loc(analyzer)
+                    .into_expr_err(loc)?
+                    .map(|(loc, kind)| JsonKilled::new(&LocStrSpan::new(file_mapping, loc), &kind)),
+                conditionals: conditionals.iter().map(Into::into).collect(),
+                items: analyses
+                    .iter()
+                    .flat_map(|analysis| analysis.json_items(analyzer))
+                    .collect(),
+                returns,
+                emits,
+                reverts_with,
+            });
+        }
+
+        Ok(JsonFunctionReport {
+            function: self.ctx.associated_fn_name(analyzer).into_expr_err(loc)?,
+            span: (&self.ctx_loc).into(),
+            killed: self
+                .ctx_killed
+                .as_ref()
+                .map(|(span, kind)| JsonKilled::new(span, kind)),
+            contexts,
+        })
+    }
+}
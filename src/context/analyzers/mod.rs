@@ -10,6 +10,18 @@ use std::collections::BTreeMap;
 
 mod func_analyzer;
 pub use func_analyzer::*;
+mod invariants;
+pub use invariants::*;
+mod json;
+pub use json::*;
+mod panics;
+pub use panics::*;
+mod reentrancy;
+pub use reentrancy::*;
+mod sarif;
+pub use sarif::*;
+mod unchecked;
+pub use unchecked::*;
 mod var_analyzer;
 pub use var_analyzer::*;
 
@@ -36,24 +48,22 @@ impl Span for LocSpan {
     fn source(&self) -> &Self::SourceId {
         match self.0 {
             Loc::File(ref f, _, _) => f,
-            Loc::Implicit => &0,
-            _ => todo!("handle non file loc"),
+            // locs outside of a file, i.e. of builtins, point at the start of the entry file
+            _ => &0,
         }
     }
 
     fn start(&self) -> usize {
         match self.0 {
             Loc::File(_, start, _) => start,
-            Loc::Implicit => 0,
-            _ => todo!("handle non file loc"),
+            _ => 0,
         }
     }
 
     fn end(&self) -> usize {
         match self.0 {
             Loc::File(_, _, end) => end,
-            Loc::Implicit => 0,
-            _ => todo!("handle non file loc"),
+            _ => 0,
         }
     }
 }
@@ -71,8 +81,8 @@ impl LocStrSpan {
     pub fn new(file_mapping: &BTreeMap<usize, String>, loc: Loc) -> Self {
         let source = match loc {
             Loc::File(ref f, _, _) => f,
-            Loc::Implicit => &0,
-            _ => todo!("handle non file loc"),
+            // locs outside of a file, i.e. of builtins, point at the start of the entry file
+            _ => &0,
         };
         LocStrSpan(
             file_mapping
@@ -93,16 +103,14 @@ impl Span for LocStrSpan {
     fn start(&self) -> usize {
         match self.1 {
             Loc::File(_, start, _) => start,
-            Loc::Implicit => 0,
-            _ => todo!("handle non file loc"),
+            _ => 0,
         }
     }
 
     fn end(&self) -> usize {
         match self.1 {
             Loc::File(_, _, end) => end,
-            Loc::Implicit => 0,
-            _ => todo!("handle non file loc"),
+            _ => 0,
         }
     }
 }
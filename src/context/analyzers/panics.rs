@@ -0,0 +1,318 @@
+use super::unchecked::{
+    as_i256, bounds, int_ty, op_str, overflow_likelihood, range_bounds, rhs_bounds, PanicLikelihood,
+};
+use crate::analyzers::{LocStrSpan, ReportDisplay};
+use crate::context::exprs::IntoExprErr;
+use crate::ExprErr;
+
+use ariadne::{Cache, Color, Config, Fmt, Label, Report, ReportKind, Span};
+use ethers_core::types::I256;
+use shared::analyzer::{AnalyzerLike, GraphError, GraphLike, Search};
+use shared::context::{ContextArith, ContextArrayAccess, KilledKind};
+use shared::nodes::{Concrete, ContractNode};
+use shared::range::{elem::RangeOp, Range};
+use solang_parser::pt::{Expression, Loc};
+use std::collections::BTreeMap;
+
+/// A Solidity panic an arithmetic operation or array access can revert with
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum PanicKind {
+    /// An overflow or underflow of checked arithmetic, `Panic(0x11)`
+    Overflow,
+    /// A division or modulo by zero, `Panic(0x12)`
+    DivisionByZero,
+    /// A `pop()` of an empty array, `Panic(0x31)`
+    EmptyArrayPop,
+    /// An index access past the end of an array, fixed size array or `bytesN`, `Panic(0x32)`
+    IndexOutOfBounds,
+}
+
+impl PanicKind {
+    /// The panic code the operation reverts with
+    pub fn code(&self) -> u8 {
+        match self {
+            PanicKind::Overflow => 0x11,
+            PanicKind::DivisionByZero => 0x12,
+            PanicKind::EmptyArrayPop => 0x31,
+            PanicKind::IndexOutOfBounds => 0x32,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PanicKind::Overflow => "arithmetic overflow",
+            PanicKind::DivisionByZero => "division by zero",
+            PanicKind::EmptyArrayPop => "pop of an empty array",
+            PanicKind::IndexOutOfBounds => "index out of bounds",
+        }
+    }
+}
+
+/// An arithmetic operation or array access that may or always will revert with a panic
+#[derive(Debug, Clone)]
+pub struct PanicAnalysis {
+    pub kind: PanicKind,
+    /// Whether the operation reverts on every path through it, or only some
+    pub likelihood: PanicLikelihood,
+    /// The name of the function the operation is in
+    pub func_name: String,
+    /// The operator: `+`, `-`, `*`, `/`, `%`, `++`, `--`, `[]` or `pop()`
+    pub op: String,
+    /// The operation's location string span
+    pub loc: LocStrSpan,
+}
+
+impl ReportDisplay for PanicAnalysis {
+    fn report_kind(&self) -> ReportKind {
+        match self.likelihood {
+            PanicLikelihood::Always => ReportKind::Custom("Panic", Color::Red),
+            _ => ReportKind::Custom("Possible Panic", Color::Yellow),
+        }
+    }
+
+    fn msg(&self, _analyzer: &impl GraphLike) -> String {
+        format!(
+            "`{}` {} with Panic(0x{:02x}) ({}) in function: {}",
+            self.op,
+            self.likelihood.as_str(),
+            self.kind.code(),
+            self.kind.as_str(),
+            self.func_name.clone().fg(Color::Cyan)
+        )
+    }
+
+    fn labels(&self, _analyzer: &impl GraphLike) -> Vec<Label<LocStrSpan>> {
+        let color = match self.likelihood {
+            PanicLikelihood::Always => Color::Red,
+            _ => Color::Yellow,
+        };
+        vec![Label::new(self.loc.clone())
+            .with_message(format!(
+                "{} {}",
+                self.kind.as_str(),
+                self.likelihood.as_str()
+            ))
+            .with_color(color)]
+    }
+
+    fn reports(&self, analyzer: &impl GraphLike) -> Vec<Report<LocStrSpan>> {
+        let mut report = Report::build(self.report_kind(), self.loc.source(), self.loc.start())
+            .with_message(self.msg(analyzer))
+            .with_config(
+                Config::default()
+                    .with_cross_gap(false)
+                    .with_underlines(true)
+                    .with_tab_width(4),
+            );
+        report.add_labels(self.labels(analyzer));
+        vec![report.finish()]
+    }
+
+    fn print_reports(&self, mut src: &mut impl Cache<String>, analyzer: &impl GraphLike) {
+        let reports = &self.reports(analyzer);
+        for report in reports.iter() {
+            report.print(&mut src).unwrap();
+        }
+    }
+
+    fn eprint_reports(&self, mut src: &mut impl Cache<String>, analyzer: &impl GraphLike) {
+        let reports = &self.reports(analyzer);
+        reports.iter().for_each(|report| {
+            report.eprint(&mut src).unwrap();
+        });
+    }
+}
+
+impl<T> PanicAnalyzer for T where
+    T: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Search + Sized
+{
+}
+/// Finds arithmetic and array accesses that can revert with a panic
+pub trait PanicAnalyzer:
+    AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Search + Sized
+{
+    /// Finds the arithmetic operations of a contract's functions whose operands' ranges let them overflow or
+    /// divide by zero on some reachable path of the function, and the index accesses whose index can be past the
+    /// array's length and `pop()`s of arrays that can be empty, ordered by function. An operation that panics on
+    /// every path through it always reverts; otherwise it may revert. Operands without concrete bounds are
+    /// assumed to take any value of their type, and relations between symbolic operands (e.g. an index after
+    /// `require(i < arr.length)`) aren't proven
+    fn panics_for(
+        &mut self,
+        file_mapping: &BTreeMap<usize, String>,
+        contract: ContractNode,
+    ) -> Result<Vec<PanicAnalysis>, ExprErr> {
+        let loc = contract.loc(self).into_expr_err(Loc::Implicit)?;
+
+        let mut panics: BTreeMap<(String, Loc, PanicKind), (String, PanicLikelihood)> =
+            BTreeMap::default();
+        for func in contract.funcs(self) {
+            let Some(body_ctx) = func.maybe_body_ctx(self) else {
+                continue;
+            };
+            let func_name = func.name(self).into_expr_err(loc)?;
+            let mut ctxs = vec![body_ctx];
+            ctxs.extend(body_ctx.descendants(self).into_expr_err(loc)?);
+            for ctx in ctxs {
+                // the operations of called functions are judged in the called function's own analysis
+                if ctx.associated_fn(self).into_expr_err(loc)? != func
+                    || matches!(
+                        ctx.killed_loc(self).into_expr_err(loc)?,
+                        Some((_, KilledKind::Unreachable))
+                    )
+                {
+                    continue;
+                }
+                for arith in ctx.ariths(self).into_expr_err(loc)? {
+                    let (overflow, div_by_zero) = match arith.op {
+                        RangeOp::Div(_) => (
+                            signed_div_overflow_likelihood(&arith, self)
+                                .into_expr_err(arith.loc)?,
+                            div_by_zero_likelihood(&arith, self).into_expr_err(arith.loc)?,
+                        ),
+                        RangeOp::Mod => (
+                            PanicLikelihood::Never,
+                            div_by_zero_likelihood(&arith, self).into_expr_err(arith.loc)?,
+                        ),
+                        _ if arith.unchecked => (PanicLikelihood::Never, PanicLikelihood::Never),
+                        _ => (
+                            overflow_likelihood(&arith, self).into_expr_err(arith.loc)?,
+                            PanicLikelihood::Never,
+                        ),
+                    };
+                    for (kind, likelihood) in [
+                        (PanicKind::Overflow, overflow),
+                        (PanicKind::DivisionByZero, div_by_zero),
+                    ] {
+                        panics
+                            .entry((func_name.clone(), arith.loc, kind))
+                            .and_modify(|(_, prev)| *prev = prev.join(likelihood))
+                            .or_insert_with(|| (op_str(&arith), likelihood));
+                    }
+                }
+                for access in ctx.array_accesses(self).into_expr_err(loc)? {
+                    let likelihood =
+                        out_of_bounds_likelihood(&access, self).into_expr_err(access.loc)?;
+                    let (kind, op) = match access.index {
+                        Some(_) => (PanicKind::IndexOutOfBounds, "[]"),
+                        None => (PanicKind::EmptyArrayPop, "pop()"),
+                    };
+                    panics
+                        .entry((func_name.clone(), access.loc, kind))
+                        .and_modify(|(_, prev)| *prev = prev.join(likelihood))
+                        .or_insert_with(|| (op.to_string(), likelihood));
+                }
+            }
+        }
+
+        Ok(panics
+            .into_iter()
+            .filter(|(_, (_, likelihood))| *likelihood != PanicLikelihood::Never)
+            .map(
+                |((func_name, arith_loc, kind), (op, likelihood))| PanicAnalysis {
+                    kind,
+                    likelihood,
+                    func_name,
+                    op,
+                    loc: LocStrSpan::new(file_mapping, arith_loc),
+                },
+            )
+            .collect())
+    }
+}
+
+/// Returns whether a division's or modulo's denominator can be zero. A denominator whose range excludes zero,
+/// e.g. after a `require(b != 0)`, can't be
+fn div_by_zero_likelihood(
+    arith: &ContextArith,
+    analyzer: &impl GraphLike,
+) -> Result<PanicLikelihood, GraphError> {
+    if arith.rhs.is_none() {
+        return Ok(PanicLikelihood::Never);
+    }
+    let Some(range) = &arith.rhs_range else {
+        return Ok(PanicLikelihood::May);
+    };
+    let excludes_zero = range.range_exclusions().iter().any(|excl| {
+        excl.maybe_concrete().is_some_and(|excl| {
+            excl.val.uint_val().is_some_and(|val| val.is_zero())
+                || excl.val.int_val().is_some_and(|val| val.is_zero())
+        })
+    });
+    if excludes_zero {
+        return Ok(PanicLikelihood::Never);
+    }
+    let Some((min, max)) = range_bounds(range, analyzer, as_i256_saturating)? else {
+        return Ok(PanicLikelihood::May);
+    };
+    Ok(if min.is_zero() && max.is_zero() {
+        PanicLikelihood::Always
+    } else if !min.is_positive() && !max.is_negative() {
+        PanicLikelihood::May
+    } else {
+        PanicLikelihood::Never
+    })
+}
+
+/// Returns whether a signed division overflows: the minimum of its type divided by -1
+fn signed_div_overflow_likelihood(
+    arith: &ContextArith,
+    analyzer: &impl GraphLike,
+) -> Result<PanicLikelihood, GraphError> {
+    let Some(ref ty @ Concrete::Int(..)) = int_ty(arith.lhs, analyzer)? else {
+        return Ok(PanicLikelihood::Never);
+    };
+    let neg_one = I256::from(-1);
+    let (Some(ty_min), Some((lmin, lmax)), Some((rmin, rmax))) = (
+        ty.min().and_then(|min| min.int_val()),
+        bounds(arith.lhs, analyzer, as_i256)?,
+        rhs_bounds(arith, analyzer, as_i256, neg_one)?,
+    ) else {
+        return Ok(PanicLikelihood::May);
+    };
+    Ok(if lmin != ty_min || rmin > neg_one || rmax < neg_one {
+        PanicLikelihood::Never
+    } else if lmax == ty_min && rmin == neg_one && rmax == neg_one {
+        PanicLikelihood::Always
+    } else {
+        PanicLikelihood::May
+    })
+}
+
+/// Returns whether an index access's index can be past the end of the array, or a `pop()`'s array can be empty
+fn out_of_bounds_likelihood(
+    access: &ContextArrayAccess,
+    analyzer: &impl GraphLike,
+) -> Result<PanicLikelihood, GraphError> {
+    let Some((len_min, len_max)) = bounds(access.len, analyzer, Concrete::uint_val)? else {
+        return Ok(PanicLikelihood::May);
+    };
+    let Some(index) = access.index else {
+        return Ok(if len_max.is_zero() {
+            PanicLikelihood::Always
+        } else if len_min.is_zero() {
+            PanicLikelihood::May
+        } else {
+            PanicLikelihood::Never
+        });
+    };
+    let Some((index_min, index_max)) = bounds(index, analyzer, Concrete::uint_val)? else {
+        return Ok(PanicLikelihood::May);
+    };
+    Ok(if index_min >= len_max {
+        PanicLikelihood::Always
+    } else if index_max >= len_min {
+        PanicLikelihood::May
+    } else {
+        PanicLikelihood::Never
+    })
+}
+
+/// Converts an evaluated bound of either signedness, saturating unsigned values too large for an I256. Only the
+/// sign of a denominator's bounds matters
+fn as_i256_saturating(c: &Concrete) -> Option<I256> {
+    match c {
+        Concrete::Uint(_, val) if *val > I256::MAX.into_raw() => Some(I256::MAX),
+        c => as_i256(c),
+    }
+}
@@ -0,0 +1,260 @@
+use crate::analyzers::{LocStrSpan, ReportDisplay};
+use crate::context::exprs::IntoExprErr;
+use crate::ExprErr;
+
+use ariadne::{Cache, Color, Config, Fmt, Label, Report, ReportKind, Span};
+use shared::analyzer::{AnalyzerLike, GraphError, GraphLike, Search};
+use shared::context::{ContextEffect, ContextNode};
+use shared::nodes::{ContractNode, FunctionNode};
+use solang_parser::pt::{Expression, Loc};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A write of a storage variable after an external call on the same path of a function, i.e. a violation of
+/// checks-effects-interactions
+#[derive(Debug, Clone)]
+pub struct ReentrancyAnalysis {
+    /// The function the call and the write are in
+    pub func: FunctionNode,
+    /// The name of the function
+    pub func_name: String,
+    /// The external call's location string span
+    pub call_loc: LocStrSpan,
+    /// What the external call calls: the function, or `call`/`delegatecall` for low level calls
+    pub callee: String,
+    /// The storage write's location string span
+    pub write_loc: LocStrSpan,
+    /// The name of the storage variable written after the call
+    pub var_name: String,
+    /// The other functions of the contract a transaction can enter through that access the storage variable,
+    /// and the location string span of the access. These can be reentered during the call and see the variable
+    /// before it is written
+    pub cross_fns: Vec<(String, LocStrSpan)>,
+}
+
+impl ReportDisplay for ReentrancyAnalysis {
+    fn report_kind(&self) -> ReportKind {
+        ReportKind::Custom("Reentrancy", Color::Red)
+    }
+
+    fn msg(&self, _analyzer: &impl GraphLike) -> String {
+        format!(
+            "Storage variable {} is written after an external call in function: {}",
+            self.var_name.clone().fg(Color::Cyan),
+            format!("function {}", self.func_name).fg(Color::Cyan)
+        )
+    }
+
+    fn labels(&self, _analyzer: &impl GraphLike) -> Vec<Label<LocStrSpan>> {
+        let mut labels = vec![
+            Label::new(self.call_loc.clone())
+                .with_message(format!("external call to {}", self.callee))
+                .with_color(Color::Red)
+                .with_order(0),
+            Label::new(self.write_loc.clone())
+                .with_message(format!("{} is written after the call", self.var_name))
+                .with_color(Color::Yellow)
+                .with_order(1),
+        ];
+        labels.extend(self.cross_fns.iter().enumerate().map(|(i, (name, span))| {
+            Label::new(span.clone())
+                .with_message(format!(
+                    "{name} accesses {} and can be reentered during the call",
+                    self.var_name
+                ))
+                .with_color(Color::Magenta)
+                .with_order(2 + i as i32)
+        }));
+        labels
+    }
+
+    fn reports(&self, analyzer: &impl GraphLike) -> Vec<Report<LocStrSpan>> {
+        let mut report = Report::build(
+            self.report_kind(),
+            self.call_loc.source(),
+            self.call_loc.start(),
+        )
+        .with_message(self.msg(analyzer))
+        .with_config(
+            Config::default()
+                .with_cross_gap(false)
+                .with_underlines(true)
+                .with_tab_width(4),
+        );
+        report.add_labels(self.labels(analyzer));
+        vec![report.finish()]
+    }
+
+    fn print_reports(&self, mut src: &mut impl Cache<String>, analyzer: &impl GraphLike) {
+        let reports = &self.reports(analyzer);
+        for report in reports.iter() {
+            report.print(&mut src).unwrap();
+        }
+    }
+
+    fn eprint_reports(&self, mut src: &mut impl Cache<String>, analyzer: &impl GraphLike) {
+        let reports = &self.reports(analyzer);
+        reports.iter().for_each(|report| {
+            report.eprint(&mut src).unwrap();
+        });
+    }
+}
+
+impl<T> ReentrancyAnalyzer for T where
+    T: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Search + Sized
+{
+}
+/// Finds checks-effects-interactions violations
+pub trait ReentrancyAnalyzer:
+    AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Search + Sized
+{
+    /// Finds the storage writes that follow an external call on the same successful path of each function a
+    /// transaction can enter the contract through. External calls are calls of other contracts' functions and low
+    /// level `call`s and `delegatecall`s; the writes the called function makes are its own. Writes of a variable,
+    /// element or member the path also wrote before the call aren't reported, as they release a lock taken before
+    /// the call (i.e. a reentrancy guard). Writing another element of a mapping or array doesn't release anything,
+    /// i.e. `balances[to]` after a call is reported even if `balances[from]` was written before it.
+    ///
+    /// Each violation lists the other entry functions that access the written variable, which a reentrant call
+    /// would see in the state before the write
+    fn reentrancy_for(
+        &mut self,
+        file_mapping: &BTreeMap<usize, String>,
+        contract: ContractNode,
+    ) -> Result<Vec<ReentrancyAnalysis>, ExprErr> {
+        let loc = contract.loc(self).into_expr_err(Loc::Implicit)?;
+        let funcs = contract
+            .entry_functions(self)
+            .into_iter()
+            .filter_map(|func| Some((func, func.maybe_body_ctx(self)?)))
+            .collect::<Vec<_>>();
+
+        // the storage variables each entry function accesses, and where it first does
+        let mut accesses: Vec<(FunctionNode, BTreeMap<String, Loc>)> = vec![];
+        for (func, body_ctx) in funcs.iter() {
+            let mut ctxs = vec![*body_ctx];
+            ctxs.extend(body_ctx.descendants(self).into_expr_err(loc)?);
+            let mut accessed: BTreeMap<String, Loc> = BTreeMap::default();
+            for ctx in ctxs {
+                for var in ctx.vars(self).clone().into_values() {
+                    if !var.is_storage(self).into_expr_err(loc)? {
+                        continue;
+                    }
+                    let name = var.root_name(self).into_expr_err(loc)?;
+                    let var_loc = var.loc(self).into_expr_err(loc)?;
+                    let first = accessed.entry(name).or_insert(var_loc);
+                    if var_loc < *first {
+                        *first = var_loc;
+                    }
+                }
+            }
+            accesses.push((*func, accessed));
+        }
+
+        let mut violations = BTreeMap::default();
+        for (func, body_ctx) in funcs.iter() {
+            for leaf in body_ctx.successful_leaves(self).into_expr_err(loc)? {
+                for (call_loc, callee, write_loc, var_name) in
+                    path_violations(leaf, self).into_expr_err(loc)?
+                {
+                    violations
+                        .entry((*func, call_loc, write_loc))
+                        .or_insert((callee, var_name));
+                }
+            }
+        }
+
+        violations
+            .into_iter()
+            .map(|((func, call_loc, write_loc), (callee, var_name))| {
+                let cross_fns = accesses
+                    .iter()
+                    .filter(|(other, _)| *other != func)
+                    .filter_map(|(other, accessed)| {
+                        let access_loc = accessed.get(&var_name)?;
+                        Some((*other, *access_loc))
+                    })
+                    .map(|(other, access_loc)| {
+                        Ok((
+                            other.name(self).into_expr_err(loc)?,
+                            LocStrSpan::new(file_mapping, access_loc),
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, ExprErr>>()?;
+                Ok(ReentrancyAnalysis {
+                    func,
+                    func_name: func.name(self).into_expr_err(loc)?,
+                    call_loc: LocStrSpan::new(file_mapping, call_loc),
+                    callee,
+                    write_loc: LocStrSpan::new(file_mapping, write_loc),
+                    var_name,
+                    cross_fns,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Walks the path from the root context to a leaf and returns the storage writes that follow its first external
+/// call, as the call's location, what it calls, the write's location and the name of the storage variable
+fn path_violations(
+    leaf: ContextNode,
+    analyzer: &impl GraphLike,
+) -> Result<Vec<(Loc, String, Loc, String)>, GraphError> {
+    let mut lineage = leaf.parent_list(analyzer)?;
+    lineage.reverse();
+    lineage.push(leaf);
+
+    let mut violations = vec![];
+    let mut call: Option<(Loc, String)> = None;
+    let mut written_before = BTreeSet::default();
+    // the contexts that made the external calls the path is currently inside of
+    let mut callers: Vec<ContextNode> = vec![];
+    for ctx in lineage {
+        let underlying = ctx.underlying(analyzer)?;
+        if underlying.returning_ctx.is_some() && underlying.returning_ctx == callers.last().copied()
+        {
+            callers.pop();
+        }
+        if let Some(ext_fn) = underlying.ext_fn_call {
+            if callers.is_empty() && call.is_none() {
+                call = Some((underlying.loc, ext_fn.name(analyzer)?));
+            }
+            if let Some(parent) = underlying.parent_ctx {
+                callers.push(parent);
+            }
+        }
+        if !callers.is_empty() {
+            continue;
+        }
+
+        for effect in underlying.effects.iter() {
+            match effect {
+                ContextEffect::LowLevelCall { loc, kind } => {
+                    if call.is_none() {
+                        call = Some((*loc, kind.clone()));
+                    }
+                }
+                ContextEffect::StorageWrite { loc, var } => {
+                    // only a write of the same element or member releases a lock, so the elements are told
+                    // apart by their full name, i.e. `balances[from]` and `balances[to]`
+                    let written = var.name(analyzer)?;
+                    match &call {
+                        Some((call_loc, callee)) if !written_before.contains(&written) => {
+                            violations.push((
+                                *call_loc,
+                                callee.clone(),
+                                *loc,
+                                var.root_name(analyzer)?,
+                            ));
+                        }
+                        Some(_) => {}
+                        None => {
+                            written_before.insert(written);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(violations)
+}
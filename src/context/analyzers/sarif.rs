@@ -0,0 +1,416 @@
+use crate::analyzers::{
+    range_parts, CLIFunctionVarsBoundAnalysis, LocStrSpan, RangePart, ReportDisplay, RevertItem,
+    VarBoundAnalysis,
+};
+use crate::exprs::ExprErr;
+
+use ariadne::Span;
+use serde::Serialize;
+use shared::analyzer::GraphLike;
+use shared::context::*;
+use std::collections::BTreeMap;
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The rules pyrometer can emit results for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SarifRule {
+    /// A bound change of a variable
+    Bounds,
+    /// A path that is guaranteed to revert
+    Revert,
+    /// A path whose bounds are unsatisfiable
+    Unreachable,
+    /// An error encountered during analysis
+    AnalysisError,
+}
+
+impl SarifRule {
+    pub const ALL: [SarifRule; 4] = [
+        SarifRule::Bounds,
+        SarifRule::Revert,
+        SarifRule::Unreachable,
+        SarifRule::AnalysisError,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            SarifRule::Bounds => "pyrometer/bounds",
+            SarifRule::Revert => "pyrometer/revert",
+            SarifRule::Unreachable => "pyrometer/unreachable",
+            SarifRule::AnalysisError => "pyrometer/analysis-error",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            SarifRule::Bounds => "Variable bounds along an execution path",
+            SarifRule::Revert => "Execution path guaranteed to revert",
+            SarifRule::Unreachable => "Unsatisfiable bounds, therefore dead code",
+            SarifRule::AnalysisError => "Pyrometer was unable to analyze this code",
+        }
+    }
+
+    pub fn level(&self) -> SarifLevel {
+        match self {
+            SarifRule::Bounds => SarifLevel::Note,
+            SarifRule::Revert => SarifLevel::Warning,
+            SarifRule::Unreachable => SarifLevel::Warning,
+            SarifRule::AnalysisError => SarifLevel::Error,
+        }
+    }
+
+    pub fn from_killed_kind(kind: &KilledKind) -> Option<Self> {
+        match kind {
+            KilledKind::Revert => Some(SarifRule::Revert),
+            KilledKind::Unreachable => Some(SarifRule::Unreachable),
+            KilledKind::ParseError => Some(SarifRule::AnalysisError),
+            KilledKind::Ended => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SarifLevel {
+    Note,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub runs: Vec<SarifRun>,
+}
+
+impl SarifLog {
+    /// Creates a log of the results. The regions of the results' locations get their lines and columns from the
+    /// sources, by file name
+    pub fn new(mut results: Vec<SarifResult>, sources: &BTreeMap<String, String>) -> Self {
+        results
+            .iter_mut()
+            .flat_map(|result| {
+                result
+                    .locations
+                    .iter_mut()
+                    .chain(result.related_locations.iter_mut())
+            })
+            .for_each(|location| {
+                let physical_location = &mut location.physical_location;
+                if let Some(src) = sources.get(&physical_location.artifact_location.uri) {
+                    physical_location.region.set_lines(src);
+                }
+            });
+        Self {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: "pyrometer",
+                        information_uri: "https://github.com/nascentxyz/pyrometer",
+                        version: env!("CARGO_PKG_VERSION"),
+                        rules: SarifRule::ALL
+                            .iter()
+                            .map(|rule| SarifReportingDescriptor {
+                                id: rule.id(),
+                                short_description: SarifMessage::new(rule.description()),
+                                default_configuration: SarifConfiguration {
+                                    level: rule.level(),
+                                },
+                            })
+                            .collect(),
+                    },
+                },
+                results,
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifRun {
+    pub tool: SarifTool,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifTool {
+    pub driver: SarifDriver,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifDriver {
+    pub name: &'static str,
+    pub information_uri: &'static str,
+    pub version: &'static str,
+    pub rules: Vec<SarifReportingDescriptor>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifReportingDescriptor {
+    pub id: &'static str,
+    pub short_description: SarifMessage,
+    pub default_configuration: SarifConfiguration,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifConfiguration {
+    pub level: SarifLevel,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifMessage {
+    pub text: String,
+}
+
+impl SarifMessage {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: &'static str,
+    pub level: SarifLevel,
+    pub message: SarifMessage,
+    pub locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_locations: Vec<SarifLocation>,
+}
+
+impl SarifResult {
+    pub fn new(rule: SarifRule, message: impl Into<String>, span: &LocStrSpan) -> Self {
+        Self {
+            rule_id: rule.id(),
+            level: rule.level(),
+            message: SarifMessage::new(message),
+            locations: vec![span.into()],
+            related_locations: vec![],
+        }
+    }
+
+    pub fn with_related(mut self, span: &LocStrSpan, message: impl Into<String>) -> Self {
+        let mut location: SarifLocation = span.into();
+        location.message = Some(SarifMessage::new(message));
+        self.related_locations.push(location);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLocation {
+    pub physical_location: SarifPhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<SarifMessage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifPhysicalLocation {
+    pub artifact_location: SarifArtifactLocation,
+    pub region: SarifRegion,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifArtifactLocation {
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRegion {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+    pub byte_offset: usize,
+    pub byte_length: usize,
+}
+
+impl SarifRegion {
+    /// Sets the lines and columns of the region from the source it is in. Lines and columns are 1-based and
+    /// columns count UTF-16 code units, SARIF's default column kind. The end column is that of the character
+    /// after the region
+    pub fn set_lines(&mut self, src: &str) {
+        let (start_line, start_column) = line_column(src, self.byte_offset);
+        let (end_line, end_column) = line_column(src, self.byte_offset + self.byte_length);
+        self.start_line = Some(start_line);
+        self.start_column = Some(start_column);
+        self.end_line = Some(end_line);
+        self.end_column = Some(end_column);
+    }
+}
+
+/// The 1-based line and UTF-16 column of a byte offset into a source
+fn line_column(src: &str, offset: usize) -> (usize, usize) {
+    let before = &src.as_bytes()[..offset.min(src.len())];
+    let line_start = before
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map(|newline| newline + 1)
+        .unwrap_or(0);
+    let line = before.iter().filter(|byte| **byte == b'\n').count() + 1;
+    let column = String::from_utf8_lossy(&before[line_start..])
+        .encode_utf16()
+        .count()
+        + 1;
+    (line, column)
+}
+
+impl From<&LocStrSpan> for SarifLocation {
+    fn from(span: &LocStrSpan) -> Self {
+        Self {
+            physical_location: SarifPhysicalLocation {
+                artifact_location: SarifArtifactLocation {
+                    uri: span.source().clone(),
+                },
+                region: SarifRegion {
+                    start_line: None,
+                    start_column: None,
+                    end_line: None,
+                    end_column: None,
+                    byte_offset: span.start(),
+                    byte_length: span.end().saturating_sub(span.start()),
+                },
+            },
+            message: None,
+        }
+    }
+}
+
+/// Converts a [ReportDisplay] into SARIF results
+pub trait SarifDisplay: ReportDisplay {
+    fn sarif_results(&self, analyzer: &impl GraphLike) -> Vec<SarifResult>;
+}
+
+impl SarifDisplay for VarBoundAnalysis {
+    fn sarif_results(&self, analyzer: &impl GraphLike) -> Vec<SarifResult> {
+        let path = self.ctx.path(analyzer);
+        let mut results = vec![];
+        if self.report_config.show_initial_bounds {
+            if let Some(init_item) = self.init_item(analyzer) {
+                results.push(
+                    SarifResult::new(
+                        SarifRule::Bounds,
+                        format!(
+                            "Initial bounds of \"{}\"{} in {path}",
+                            init_item.name,
+                            parts_str(&init_item.parts)
+                        ),
+                        &init_item.loc,
+                    )
+                    .with_related(&self.func_span, "Function"),
+                );
+            }
+        }
+
+        results.extend(self.bound_changes.iter().map(|(span, range)| {
+            let (parts, unsat) = range_parts(analyzer, &self.report_config, range);
+            SarifResult::new(
+                SarifRule::Bounds,
+                format!(
+                    "\"{}\"{} in {path}{}",
+                    self.var_display_name,
+                    parts_str(&parts),
+                    if unsat {
+                        " - unsatisfiable range, unreachable"
+                    } else {
+                        ""
+                    }
+                ),
+                span,
+            )
+            .with_related(&self.func_span, "Function")
+        }));
+        results
+    }
+}
+
+impl<'a> SarifDisplay for CLIFunctionVarsBoundAnalysis<'a> {
+    fn sarif_results(&self, analyzer: &impl GraphLike) -> Vec<SarifResult> {
+        let analysis = &self.func_var_bound_analysis;
+        let fn_name = analysis.ctx.associated_fn_name(analyzer).unwrap();
+        let mut results = vec![];
+        if let Some((killed_span, kind)) = &analysis.ctx_killed {
+            if let Some(rule) = SarifRule::from_killed_kind(kind) {
+                results.push(
+                    SarifResult::new(
+                        rule,
+                        format!("{} (function {fn_name})", kind.analysis_str()),
+                        killed_span,
+                    )
+                    .with_related(&analysis.ctx_loc, "Entry function"),
+                );
+            }
+        }
+
+        analysis.vars_by_ctx.iter().for_each(|(ctx, analyses)| {
+            if *ctx != analysis.ctx {
+                if let Some((loc, kind)) = ctx.killed_loc(analyzer).unwrap() {
+                    if let Some(rule) = SarifRule::from_killed_kind(&kind) {
+                        let reason = ctx
+                            .reverts_with(analyzer)
+                            .unwrap()
+                            .map(|revert| {
+                                RevertItem::new(
+                                    self.file_mapping,
+                                    analyzer,
+                                    &analysis.report_config,
+                                    &revert,
+                                )
+                                .unwrap()
+                                .to_normal_string()
+                            })
+                            .unwrap_or_else(|| kind.analysis_str().to_string());
+                        results.push(
+                            SarifResult::new(
+                                rule,
+                                format!("{reason} (path {})", ctx.path(analyzer)),
+                                &LocStrSpan::new(self.file_mapping, loc),
+                            )
+                            .with_related(&analysis.ctx_loc, "Entry function"),
+                        );
+                    }
+                }
+            }
+            results.extend(
+                analyses
+                    .iter()
+                    .flat_map(|analysis| analysis.sarif_results(analyzer)),
+            );
+        });
+        results
+    }
+}
+
+impl ExprErr {
+    pub fn sarif_result(&self, file_mapping: &BTreeMap<usize, String>) -> SarifResult {
+        SarifResult::new(
+            SarifRule::AnalysisError,
+            format!("{}: {}", self.report_msg(), self.msg()),
+            &LocStrSpan::new(file_mapping, self.loc()),
+        )
+    }
+}
+
+fn parts_str(parts: &[RangePart]) -> String {
+    parts
+        .iter()
+        .map(|part| part.to_normal_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,421 @@
+use crate::analyzers::{LocStrSpan, ReportDisplay};
+use crate::context::exprs::IntoExprErr;
+use crate::ExprErr;
+
+use ariadne::{Cache, Color, Config, Fmt, Label, Report, ReportKind, Span};
+use ethers_core::types::{I256, U256};
+use shared::analyzer::{AnalyzerLike, GraphError, GraphLike, Search};
+use shared::context::{ContextArith, ContextVarNode, KilledKind};
+use shared::nodes::{Builtin, Concrete, ContractNode, VarType};
+use shared::range::{elem::RangeOp, Range, SolcRange};
+use solang_parser::pt::{Expression, Loc};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// What the ranges of an arithmetic operation's operands show about its overflow check
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum UncheckedMathKind {
+    /// A checked operation that can't overflow or underflow on any path. Wrapping it in an `unchecked` block
+    /// saves the gas of the check
+    CanBeUnchecked,
+    /// An operation in an `unchecked` block that can overflow or underflow on some path
+    UncheckedOverflow,
+}
+
+impl UncheckedMathKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UncheckedMathKind::CanBeUnchecked => "can be unchecked",
+            UncheckedMathKind::UncheckedOverflow => "unchecked overflow",
+        }
+    }
+}
+
+/// Whether an operation panics, given the ranges of its operands
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum PanicLikelihood {
+    Never,
+    May,
+    Always,
+}
+
+impl PanicLikelihood {
+    /// Combines the likelihoods of two paths through the same operation
+    pub fn join(self, other: Self) -> Self {
+        if self == other {
+            self
+        } else {
+            PanicLikelihood::May
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PanicLikelihood::Never => "never reverts",
+            PanicLikelihood::May => "may revert",
+            PanicLikelihood::Always => "always reverts",
+        }
+    }
+}
+
+/// An arithmetic operation whose overflow check doesn't match what its operands' ranges show
+#[derive(Debug, Clone)]
+pub struct UncheckedMathAnalysis {
+    pub kind: UncheckedMathKind,
+    /// The name of the function the operation is in
+    pub func_name: String,
+    /// The operator: `+`, `-`, `*`, `++` or `--`
+    pub op: String,
+    /// The operation's location string span
+    pub loc: LocStrSpan,
+}
+
+impl ReportDisplay for UncheckedMathAnalysis {
+    fn report_kind(&self) -> ReportKind {
+        match self.kind {
+            UncheckedMathKind::CanBeUnchecked => {
+                ReportKind::Custom("Gas Optimization", Color::Green)
+            }
+            UncheckedMathKind::UncheckedOverflow => {
+                ReportKind::Custom("Unchecked Math", Color::Red)
+            }
+        }
+    }
+
+    fn msg(&self, _analyzer: &impl GraphLike) -> String {
+        match self.kind {
+            UncheckedMathKind::CanBeUnchecked => format!(
+                "Checked `{}` can never overflow or underflow in function: {}, consider wrapping it in `unchecked {{ }}`",
+                self.op,
+                self.func_name.clone().fg(Color::Cyan)
+            ),
+            UncheckedMathKind::UncheckedOverflow => format!(
+                "Unchecked `{}` can overflow or underflow in function: {}",
+                self.op,
+                self.func_name.clone().fg(Color::Cyan)
+            ),
+        }
+    }
+
+    fn labels(&self, _analyzer: &impl GraphLike) -> Vec<Label<LocStrSpan>> {
+        let (msg, color) = match self.kind {
+            UncheckedMathKind::CanBeUnchecked => {
+                ("the operands' ranges never overflow", Color::Green)
+            }
+            UncheckedMathKind::UncheckedOverflow => {
+                ("the operands' ranges can overflow", Color::Red)
+            }
+        };
+        vec![Label::new(self.loc.clone())
+            .with_message(msg)
+            .with_color(color)]
+    }
+
+    fn reports(&self, analyzer: &impl GraphLike) -> Vec<Report<LocStrSpan>> {
+        let mut report = Report::build(self.report_kind(), self.loc.source(), self.loc.start())
+            .with_message(self.msg(analyzer))
+            .with_config(
+                Config::default()
+                    .with_cross_gap(false)
+                    .with_underlines(true)
+                    .with_tab_width(4),
+            );
+        report.add_labels(self.labels(analyzer));
+        vec![report.finish()]
+    }
+
+    fn print_reports(&self, mut src: &mut impl Cache<String>, analyzer: &impl GraphLike) {
+        let reports = &self.reports(analyzer);
+        for report in reports.iter() {
+            report.print(&mut src).unwrap();
+        }
+    }
+
+    fn eprint_reports(&self, mut src: &mut impl Cache<String>, analyzer: &impl GraphLike) {
+        let reports = &self.reports(analyzer);
+        reports.iter().for_each(|report| {
+            report.eprint(&mut src).unwrap();
+        });
+    }
+}
+
+impl<T> UncheckedMathAnalyzer for T where
+    T: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Search + Sized
+{
+}
+/// Compares the overflow checks of arithmetic operations to the ranges of their operands
+pub trait UncheckedMathAnalyzer:
+    AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Search + Sized
+{
+    /// Finds the checked `+`, `-`, `*`, `++` and `--` operations of a contract's functions that can't overflow or
+    /// underflow on any reachable path of the function, and the operations in `unchecked` blocks that can on some
+    /// path. An operation is judged by the evaluated ranges of its operands on each path through it, so relations
+    /// between symbolic operands (e.g. `a - b` after `require(a >= b)`) aren't proven and the operation is
+    /// assumed to overflow
+    fn unchecked_math_for(
+        &mut self,
+        file_mapping: &BTreeMap<usize, String>,
+        contract: ContractNode,
+    ) -> Result<Vec<UncheckedMathAnalysis>, ExprErr> {
+        let loc = contract.loc(self).into_expr_err(Loc::Implicit)?;
+
+        // each operation, and whether it can overflow on any of the paths through it
+        let mut ops: BTreeMap<Loc, (String, ContextArith, bool)> = BTreeMap::default();
+        for func in contract.funcs(self) {
+            let Some(body_ctx) = func.maybe_body_ctx(self) else {
+                continue;
+            };
+            let func_name = func.name(self).into_expr_err(loc)?;
+            let mut ctxs = vec![body_ctx];
+            ctxs.extend(body_ctx.descendants(self).into_expr_err(loc)?);
+            for ctx in ctxs {
+                // the operations of called functions are judged in the called function's own analysis
+                if ctx.associated_fn(self).into_expr_err(loc)? != func
+                    || matches!(
+                        ctx.killed_loc(self).into_expr_err(loc)?,
+                        Some((_, KilledKind::Unreachable))
+                    )
+                {
+                    continue;
+                }
+                for arith in ctx.ariths(self).into_expr_err(loc)? {
+                    if !matches!(
+                        arith.op,
+                        RangeOp::Add(_) | RangeOp::Sub(_) | RangeOp::Mul(_) | RangeOp::Exp
+                    ) {
+                        continue;
+                    }
+                    let can_overflow = overflow_likelihood(&arith, self)
+                        .into_expr_err(arith.loc)?
+                        != PanicLikelihood::Never;
+                    let entry = ops
+                        .entry(arith.loc)
+                        .or_insert_with(|| (func_name.clone(), arith.clone(), false));
+                    entry.2 |= can_overflow;
+                }
+            }
+        }
+
+        Ok(ops
+            .into_values()
+            .filter_map(|(func_name, arith, can_overflow)| {
+                let kind = match (arith.unchecked, can_overflow) {
+                    (false, false) => UncheckedMathKind::CanBeUnchecked,
+                    (true, true) => UncheckedMathKind::UncheckedOverflow,
+                    _ => return None,
+                };
+                Some(UncheckedMathAnalysis {
+                    kind,
+                    func_name,
+                    op: op_str(&arith),
+                    loc: LocStrSpan::new(file_mapping, arith.loc),
+                })
+            })
+            .collect())
+    }
+}
+
+/// The operator of an arithmetic operation as written
+pub(super) fn op_str(arith: &ContextArith) -> String {
+    match (arith.op, arith.rhs) {
+        (RangeOp::Add(_), None) => "++".to_string(),
+        (RangeOp::Sub(_), None) => "--".to_string(),
+        (op, _) => op.to_string(),
+    }
+}
+
+/// Returns whether the operands' ranges make an addition, subtraction, multiplication or exponentiation leave the
+/// bounds of its type, regardless of whether it is checked. Operations of types other than integers, and operands without
+/// concrete bounds, may overflow
+pub(super) fn overflow_likelihood(
+    arith: &ContextArith,
+    analyzer: &impl GraphLike,
+) -> Result<PanicLikelihood, GraphError> {
+    let sides = match int_ty(arith.lhs, analyzer)? {
+        Some(ref ty @ Concrete::Uint(..)) => {
+            let (Some(ty_max), Some((lmin, lmax)), Some((rmin, rmax))) = (
+                ty.max().and_then(|max| max.uint_val()),
+                bounds(arith.lhs, analyzer, Concrete::uint_val)?,
+                rhs_bounds(arith, analyzer, Concrete::uint_val, U256::from(1))?,
+            ) else {
+                return Ok(PanicLikelihood::May);
+            };
+            // where a result lands relative to the type's bounds, or the side an overflow of U256 is on
+            let side = |res: Option<U256>, overflow: Ordering| match res {
+                Some(res) if res > ty_max => Ordering::Greater,
+                Some(_) => Ordering::Equal,
+                None => overflow,
+            };
+            match arith.op {
+                RangeOp::Add(_) => vec![
+                    side(lmin.checked_add(rmin), Ordering::Greater),
+                    side(lmax.checked_add(rmax), Ordering::Greater),
+                ],
+                RangeOp::Sub(_) => vec![
+                    side(lmin.checked_sub(rmax), Ordering::Less),
+                    side(lmax.checked_sub(rmin), Ordering::Less),
+                ],
+                RangeOp::Mul(_) => vec![
+                    side(lmin.checked_mul(rmin), Ordering::Greater),
+                    side(lmax.checked_mul(rmax), Ordering::Greater),
+                ],
+                RangeOp::Exp => vec![
+                    side(lmin.checked_pow(rmin), Ordering::Greater),
+                    side(lmax.checked_pow(rmax), Ordering::Greater),
+                ],
+                _ => return Ok(PanicLikelihood::Never),
+            }
+        }
+        Some(ref ty @ Concrete::Int(..)) => {
+            let (Some(ty_min), Some(ty_max), Some((lmin, lmax)), Some((rmin, rmax))) = (
+                ty.min().and_then(|min| min.int_val()),
+                ty.max().and_then(|max| max.int_val()),
+                bounds(arith.lhs, analyzer, as_i256)?,
+                rhs_bounds(arith, analyzer, as_i256, I256::from(1))?,
+            ) else {
+                return Ok(PanicLikelihood::May);
+            };
+            // where a result lands relative to the type's bounds, or the side an overflow of I256 is on
+            let side = |res: Option<I256>, up: bool| match res {
+                Some(res) if res > ty_max => Ordering::Greater,
+                Some(res) if res < ty_min => Ordering::Less,
+                Some(_) => Ordering::Equal,
+                None if up => Ordering::Greater,
+                None => Ordering::Less,
+            };
+            match arith.op {
+                RangeOp::Add(_) => vec![
+                    side(lmin.checked_add(rmin), !lmin.is_negative()),
+                    side(lmax.checked_add(rmax), !lmax.is_negative()),
+                ],
+                RangeOp::Sub(_) => vec![
+                    side(lmin.checked_sub(rmax), !lmin.is_negative()),
+                    side(lmax.checked_sub(rmin), !lmax.is_negative()),
+                ],
+                RangeOp::Mul(_) => [(lmin, rmin), (lmin, rmax), (lmax, rmin), (lmax, rmax)]
+                    .into_iter()
+                    .map(|(l, r)| side(l.checked_mul(r), l.is_negative() == r.is_negative()))
+                    .collect(),
+                RangeOp::Exp => {
+                    // a negative base alternates the sign of the result, so the extremes of the exponent are
+                    // taken with both parities
+                    let mut exps = vec![rmin, rmax];
+                    if rmin < rmax {
+                        exps.extend([rmin + I256::one(), rmax - I256::one()]);
+                    }
+                    [lmin, lmax]
+                        .into_iter()
+                        .flat_map(|l| exps.iter().map(move |r| (l, *r)))
+                        .map(|(l, r)| {
+                            let odd = r.into_raw().bit(0);
+                            side(checked_int_pow(l, r), !l.is_negative() || !odd)
+                        })
+                        .collect()
+                }
+                _ => return Ok(PanicLikelihood::Never),
+            }
+        }
+        _ => return Ok(PanicLikelihood::May),
+    };
+
+    // the results between the extremes are reachable, so the operation always overflows only if all of them are
+    // past the same bound
+    Ok(if sides.iter().all(|side| *side == Ordering::Equal) {
+        PanicLikelihood::Never
+    } else if sides.iter().all(|side| *side == Ordering::Greater)
+        || sides.iter().all(|side| *side == Ordering::Less)
+    {
+        PanicLikelihood::Always
+    } else {
+        PanicLikelihood::May
+    })
+}
+
+/// Raises a signed base to a non-negative exponent, or `None` if the result doesn't fit an I256
+fn checked_int_pow(base: I256, exp: I256) -> Option<I256> {
+    if base.unsigned_abs() <= U256::one() {
+        // 0, 1 and -1 only depend on whether the exponent is zero and on its parity
+        let exp = if exp.is_zero() {
+            0
+        } else if exp.into_raw().bit(0) {
+            1
+        } else {
+            2
+        };
+        return base.checked_pow(exp);
+    }
+    if exp.into_raw() > U256::from(u32::MAX) {
+        return None;
+    }
+    base.checked_pow(exp.into_raw().low_u32())
+}
+
+/// The integer type of a variable, as a concrete value of the type
+pub(super) fn int_ty(
+    var: ContextVarNode,
+    analyzer: &impl GraphLike,
+) -> Result<Option<Concrete>, GraphError> {
+    Ok(match var.ty(analyzer)? {
+        VarType::BuiltIn(bn, _) => match bn.underlying(analyzer)? {
+            Builtin::Uint(size) => Some(Concrete::Uint(*size, U256::zero())),
+            Builtin::Int(size) => Some(Concrete::Int(*size, I256::zero())),
+            _ => None,
+        },
+        VarType::Concrete(c) => match c.underlying(analyzer)? {
+            c @ (Concrete::Uint(..) | Concrete::Int(..)) => Some(c.clone()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// The evaluated minimum and maximum of a variable, if both are concrete values `val` can convert
+pub(super) fn bounds<V>(
+    var: ContextVarNode,
+    analyzer: &impl GraphLike,
+    val: impl Fn(&Concrete) -> Option<V>,
+) -> Result<Option<(V, V)>, GraphError> {
+    match var.ref_range(analyzer)? {
+        Some(range) => range_bounds(&range, analyzer, val),
+        None => Ok(None),
+    }
+}
+
+/// The evaluated minimum and maximum of a range, if both are concrete values `val` can convert
+pub(super) fn range_bounds<V>(
+    range: &SolcRange,
+    analyzer: &impl GraphLike,
+    val: impl Fn(&Concrete) -> Option<V>,
+) -> Result<Option<(V, V)>, GraphError> {
+    let (min, max) = (
+        range.evaled_range_min(analyzer)?,
+        range.evaled_range_max(analyzer)?,
+    );
+    Ok(min
+        .maybe_concrete()
+        .and_then(|min| val(&min.val))
+        .zip(max.maybe_concrete().and_then(|max| val(&max.val))))
+}
+
+/// The bounds of the right hand side at the time of the operation, or `one` for a `++` or `--`
+pub(super) fn rhs_bounds<V: Copy>(
+    arith: &ContextArith,
+    analyzer: &impl GraphLike,
+    val: impl Fn(&Concrete) -> Option<V>,
+    one: V,
+) -> Result<Option<(V, V)>, GraphError> {
+    match (&arith.rhs, &arith.rhs_range) {
+        (None, _) => Ok(Some((one, one))),
+        (Some(_), Some(range)) => range_bounds(range, analyzer, val),
+        (Some(_), None) => Ok(None),
+    }
+}
+
+/// Converts an evaluated bound of a signed integer, which may be an unsigned literal
+pub(super) fn as_i256(c: &Concrete) -> Option<I256> {
+    match c {
+        Concrete::Int(_, val) => Some(*val),
+        Concrete::Uint(_, val) if *val <= I256::MAX.into_raw() => Some(I256::from_raw(*val)),
+        _ => None,
+    }
+}
@@ -0,0 +1,153 @@
+use crate::context::exprs::IntoExprErr;
+use crate::context::func_call::FuncCaller;
+use crate::context::ContextBuilder;
+use crate::ExprErr;
+
+use shared::analyzer::{AnalyzerLike, GraphLike};
+use shared::context::*;
+use shared::nodes::{ContractNode, EventNode};
+use shared::Node;
+use solang_parser::pt::{Expression, Loc};
+
+impl<T> Emitter for T where T: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized + GraphLike
+{}
+/// Handles `emit` statements
+pub trait Emitter: GraphLike + AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
+    /// Evaluates the arguments of an `emit` statement and records the emitted event on the context
+    #[tracing::instrument(level = "trace", skip_all)]
+    fn emit_event(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        emit_expr: &Expression,
+    ) -> Result<(), ExprErr> {
+        let (event, input_exprs) = match emit_expr {
+            Expression::FunctionCall(_, event_expr, input_exprs) => {
+                let event = self.resolve_event(loc, ctx, event_expr, input_exprs.len())?;
+                (event, input_exprs.clone())
+            }
+            Expression::NamedFunctionCall(_, event_expr, named_args) => {
+                let event = self.resolve_event(loc, ctx, event_expr, named_args.len())?;
+                // order the arguments by the event's parameters
+                let input_exprs = event
+                    .params(self)
+                    .iter()
+                    .map(|param| {
+                        let name = param.maybe_name(self).into_expr_err(loc)?;
+                        named_args
+                            .iter()
+                            .find(|arg| Some(&arg.name.name) == name.as_ref())
+                            .map(|arg| arg.expr.clone())
+                            .ok_or_else(|| {
+                                ExprErr::InvalidFunctionInput(
+                                    loc,
+                                    format!("No argument for event parameter: {name:?}"),
+                                )
+                            })
+                    })
+                    .collect::<Result<Vec<_>, ExprErr>>()?;
+                (event, input_exprs)
+            }
+            e => {
+                return Err(ExprErr::ParseError(
+                    loc,
+                    format!("Expected an event in emit statement, got: {e:?}"),
+                ))
+            }
+        };
+
+        if input_exprs.is_empty() {
+            return ctx
+                .add_emit(
+                    ContextEmit {
+                        loc,
+                        event,
+                        args: vec![],
+                    },
+                    self,
+                )
+                .into_expr_err(loc);
+        }
+
+        self.parse_inputs(ctx, loc, &input_exprs)?;
+        self.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
+            let Some(inputs) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                return Err(ExprErr::NoRhs(loc, "Emit statement had no arguments".to_string()))
+            };
+            if let ExprRet::CtxKilled(kind) = inputs {
+                return ctx.kill(analyzer, loc, kind).into_expr_err(loc);
+            }
+            let args = inputs
+                .as_flat_vec()
+                .into_iter()
+                .map(|arg| ContextVarNode::from(arg).latest_version(analyzer))
+                .collect();
+            ctx.add_emit(ContextEmit { loc, event, args }, analyzer)
+                .into_expr_err(loc)
+        })
+    }
+
+    /// Finds the event being emitted, either by name (preferring events of the current contract
+    /// and the contracts it inherits from) or as a member of a contract, i.e. `emit IERC20.Transfer(..)`
+    fn resolve_event(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        event_expr: &Expression,
+        num_inputs: usize,
+    ) -> Result<EventNode, ExprErr> {
+        let (name, candidates) = match event_expr {
+            Expression::Variable(ident) => {
+                let mut events = ctx
+                    .maybe_associated_contract(self)
+                    .into_expr_err(loc)?
+                    .map(|contract| contract.linearized_events(self))
+                    .unwrap_or_default();
+                ctx.visible_events(self).into_iter().for_each(|event| {
+                    if !events.contains(&event) {
+                        events.push(event);
+                    }
+                });
+                (ident.name.clone(), events)
+            }
+            Expression::MemberAccess(_, member_expr, ident) => {
+                let contract = match &**member_expr {
+                    Expression::Variable(contract_ident) => self
+                        .user_types()
+                        .get(&contract_ident.name)
+                        .copied()
+                        .filter(|idx| matches!(self.node(*idx), Node::Contract(..))),
+                    _ => None,
+                };
+                let Some(contract) = contract else {
+                    return Err(ExprErr::MemberAccessNotFound(
+                        loc,
+                        format!("Could not find the contract of event: {event_expr:?}"),
+                    ))
+                };
+                (
+                    ident.name.clone(),
+                    ContractNode::from(contract).linearized_events(self),
+                )
+            }
+            e => {
+                return Err(ExprErr::ParseError(
+                    loc,
+                    format!("Expected an event name, got: {e:?}"),
+                ))
+            }
+        };
+
+        candidates
+            .into_iter()
+            .find(|event| {
+                event.name(self).unwrap() == name && event.params(self).len() == num_inputs
+            })
+            .ok_or_else(|| {
+                ExprErr::FunctionNotFound(
+                    loc,
+                    format!("Could not find event \"{name}\" with {num_inputs} parameters"),
+                )
+            })
+    }
+}
@@ -98,12 +98,9 @@ pub trait Array: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                     ctx.push_expr(inner_tys, analyzer).into_expr_err(loc)?;
                     return Ok(());
                 }
-                analyzer.index_into_array_inner(
-                    ctx,
-                    loc,
-                    inner_tys.flatten(),
-                    index_tys.clone().flatten(),
-                )
+                let inner_tys = inner_tys.flatten();
+                let index_tys = index_tys.clone().flatten();
+                analyzer.index_into_array_inner(ctx, loc, inner_tys, index_tys)
             })
         })
     }
@@ -129,7 +126,11 @@ pub trait Array: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                 let parent = ContextVarNode::from(parent).latest_version(self);
                 let idx = self.advance_var_in_ctx(index, loc, ctx)?;
                 if !parent.is_mapping(self).into_expr_err(loc)? && parent.is_indexable(self).into_expr_err(loc)? {
+                    ctx.add_sink(ContextSink { loc, kind: SinkKind::ArrayIndex, var: idx }, self).into_expr_err(loc)?;
                     let len_var = self.tmp_length(parent, ctx, loc).latest_version(self);
+                    // recorded for the panic analysis before the access requires the index to be in bounds.
+                    // Indexing into a mapping can't be out of bounds and isn't recorded
+                    ctx.add_array_access(ContextArrayAccess { loc, len: len_var, index: Some(idx) }, self).into_expr_err(loc)?;
                     self.handle_require_inner(
                         ctx,
                         loc,
@@ -141,43 +142,59 @@ pub trait Array: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                     )?;
                 }
 
-                let name = format!("{}[{}]", parent.name(self).into_expr_err(loc)?, index.name(self).into_expr_err(loc)?);
+                let index_var = self.index_var(ctx, loc, parent, index)?;
+                ctx.push_expr(ExprRet::Single(index_var.into()), self).into_expr_err(loc)?;
+                Ok(())
+            }
+            e => Err(ExprErr::ArrayIndex(loc, format!("Expected single expr evaluation of index expression, but was: {e:?}. This is a bug. Please report it at github.com/nascentxyz/pyrometer."))),
+        }
+    }
 
-                if let Some(index_var) = ctx.var_by_name_or_recurse(self, &name).into_expr_err(loc)? {
-                    let index_var = index_var.latest_version(self);
-                    let index_var = self.advance_var_in_ctx(index_var, loc, ctx)?;
-                    ctx.push_expr(ExprRet::Single(index_var.into()), self).into_expr_err(loc)?;
-                    Ok(())
-                } else {
-                    let ty = parent.ty(self).into_expr_err(loc)?.clone();
-                    let ty = ty.get_index_dynamic_ty(index, self).into_expr_err(loc)?;
-                    let index_var = ContextVar {
-                        loc: Some(loc),
-                        name: name.clone(),
-                        display_name: format!(
-                            "{}[{}]",
-                            parent.display_name(self).into_expr_err(loc)?,
-                            index.display_name(self).into_expr_err(loc)?
-                        ),
-                        storage: parent.storage(self).into_expr_err(loc)?.clone(),
-                        is_tmp: false,
-                        tmp_of: None,
-                        is_symbolic: true,
-                        is_return: false,
-                        ty,
-                    };
+    /// Gets the element of an array or mapping at an index, advancing the element if it was accessed before and
+    /// creating it otherwise
+    fn index_var(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        parent: ContextVarNode,
+        index: ContextVarNode,
+    ) -> Result<ContextVarNode, ExprErr> {
+        let name = format!(
+            "{}[{}]",
+            parent.name(self).into_expr_err(loc)?,
+            index.name(self).into_expr_err(loc)?
+        );
 
-                    let idx_node = self.add_node(Node::ContextVar(index_var));
-                    self.add_edge(idx_node, parent, Edge::Context(ContextEdge::IndexAccess));
-                    self.add_edge(idx_node, ctx, Edge::Context(ContextEdge::Variable));
-                    ctx.add_var(idx_node.into(), self).into_expr_err(loc)?;
-                    self.add_edge(index, idx_node, Edge::Context(ContextEdge::Index));
+        if let Some(index_var) = ctx.var_by_name_or_recurse(self, &name).into_expr_err(loc)? {
+            let index_var = index_var.latest_version(self);
+            self.advance_var_in_ctx(index_var, loc, ctx)
+        } else {
+            let ty = parent.ty(self).into_expr_err(loc)?.clone();
+            let ty = ty
+                .get_index_dynamic_ty(index.into(), self)
+                .into_expr_err(loc)?;
+            let index_var = ContextVar {
+                loc: Some(loc),
+                name,
+                display_name: format!(
+                    "{}[{}]",
+                    parent.display_name(self).into_expr_err(loc)?,
+                    index.display_name(self).into_expr_err(loc)?
+                ),
+                storage: parent.storage(self).into_expr_err(loc)?.clone(),
+                is_tmp: false,
+                tmp_of: None,
+                is_symbolic: true,
+                is_return: false,
+                ty,
+            };
 
-                    ctx.push_expr(ExprRet::Single(idx_node), self).into_expr_err(loc)?;
-                    Ok(())
-                }
-            }
-            e => Err(ExprErr::ArrayIndex(loc, format!("Expected single expr evaluation of index expression, but was: {e:?}. This is a bug. Please report it at github.com/nascentxyz/pyrometer."))),
+            let idx_node = self.add_node(Node::ContextVar(index_var));
+            self.add_edge(idx_node, parent, Edge::Context(ContextEdge::IndexAccess));
+            self.add_edge(idx_node, ctx, Edge::Context(ContextEdge::Variable));
+            ctx.add_var(idx_node.into(), self).into_expr_err(loc)?;
+            self.add_edge(index, idx_node, Edge::Context(ContextEdge::Index));
+            Ok(idx_node.into())
         }
     }
 }
@@ -53,11 +53,87 @@ pub trait BinOp: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                     return Ok(());
                 }
                 let lhs_paths = lhs_paths.flatten();
+                analyzer.record_arith(ctx, loc, &lhs_paths, &rhs_paths, op)?;
                 analyzer.op_match(ctx, loc, &lhs_paths, &rhs_paths, op, assign)
             })
         })
     }
 
+    /// Records an addition, subtraction, multiplication, exponentiation, division or modulo of a single left and
+    /// right hand side for the unchecked math and panic analyses. Operations of two literals are folded by the
+    /// compiler and aren't recorded
+    fn record_arith(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        lhs_paths: &ExprRet,
+        rhs_paths: &ExprRet,
+        op: RangeOp,
+    ) -> Result<(), ExprErr> {
+        if !matches!(
+            op,
+            RangeOp::Add(_)
+                | RangeOp::Sub(_)
+                | RangeOp::Mul(_)
+                | RangeOp::Exp
+                | RangeOp::Div(_)
+                | RangeOp::Mod
+        ) {
+            return Ok(());
+        }
+        let (lhs, rhs) = match (lhs_paths, rhs_paths) {
+            (ExprRet::Single(lhs), ExprRet::Single(rhs) | ExprRet::SingleLiteral(rhs))
+            | (ExprRet::SingleLiteral(lhs), ExprRet::Single(rhs)) => (*lhs, *rhs),
+            _ => return Ok(()),
+        };
+        let rhs = ContextVarNode::from(rhs).latest_version(self);
+        let arith = ContextArith {
+            loc,
+            op,
+            unchecked: !matches!(op, RangeOp::Div(_) | RangeOp::Mod)
+                && ctx.unchecked(self).into_expr_err(loc)?,
+            lhs: ContextVarNode::from(lhs).latest_version(self),
+            rhs: Some(rhs),
+            rhs_range: rhs.range(self).into_expr_err(loc)?,
+        };
+        ctx.add_arith(arith, self).into_expr_err(loc)
+    }
+
+    /// Records a negation of a signed integer, which overflows on the minimum of its type, for the unchecked math
+    /// and panic analyses. It is recorded as a subtraction from a zero of the same type
+    fn record_negation(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        var: ContextVarNode,
+    ) -> Result<(), ExprErr> {
+        let size = match var.ty(self).into_expr_err(loc)? {
+            VarType::BuiltIn(bn, _) => match bn.underlying(self).into_expr_err(loc)? {
+                Builtin::Int(size) => *size,
+                _ => return Ok(()),
+            },
+            VarType::Concrete(c) => match c.underlying(self).into_expr_err(loc)? {
+                Concrete::Int(size, _) => *size,
+                _ => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+        let zero = self.add_node(Node::Concrete(Concrete::Int(size, I256::zero())));
+        let zero = ContextVar::new_from_concrete(Loc::Implicit, ctx, zero.into(), self)
+            .into_expr_err(loc)?;
+        let zero = ContextVarNode::from(self.add_node(Node::ContextVar(zero)));
+        let unchecked = ctx.unchecked(self).into_expr_err(loc)?;
+        let arith = ContextArith {
+            loc,
+            op: RangeOp::Sub(unchecked),
+            unchecked,
+            lhs: zero,
+            rhs: Some(var),
+            rhs_range: var.range(self).into_expr_err(loc)?,
+        };
+        ctx.add_arith(arith, self).into_expr_err(loc)
+    }
+
     fn op_match(
         &mut self,
         ctx: ContextNode,
@@ -164,7 +240,9 @@ pub trait BinOp: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
         };
 
         let new_lhs = if assign {
-            self.advance_var_in_ctx(lhs_cvar, loc, ctx)?
+            let new_lhs = self.advance_var_in_ctx(lhs_cvar, loc, ctx)?;
+            self.record_storage_write(loc, ctx, new_lhs)?;
+            new_lhs
         } else {
             let mut new_lhs_underlying = ContextVar {
                 loc: Some(loc),
@@ -272,8 +350,8 @@ pub trait BinOp: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                             storage: None,
                             is_tmp: true,
                             tmp_of: Some(TmpConstruction::new(
-                                new_lhs,
-                                RangeOp::Gt,
+                                tmp_rhs,
+                                RangeOp::Neq,
                                 Some(zero_node.into()),
                             )),
                             is_symbolic: true,
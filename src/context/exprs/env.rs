@@ -3,7 +3,7 @@ use crate::context::func_call::FuncCaller;
 use crate::context::ExprErr;
 use crate::{context::ContextNode, AnalyzerLike};
 use shared::context::ExprRet;
-use shared::context::{ContextEdge, ContextVar};
+use shared::context::{ContextEdge, ContextVar, ContextVarNode};
 use shared::nodes::Builtin;
 use shared::nodes::Concrete;
 use shared::Edge;
@@ -22,7 +22,8 @@ pub trait Env: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
     ) -> Result<Option<()>, ExprErr> {
         match &*ident.name {
             "msg" | "tx" => {
-                ctx.push_expr(ExprRet::Single(self.msg().into()), self)
+                let msg = ctx.msg(self).into_expr_err(ident.loc)?;
+                ctx.push_expr(ExprRet::Single(msg.into()), self)
                     .into_expr_err(ident.loc)?;
                 Ok(Some(()))
             }
@@ -51,6 +52,24 @@ pub trait Env: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
         }
     }
 
+    /// Gets the address of the contract running the current call frame, i.e. `this`
+    fn this_var(&mut self, loc: Loc, ctx: ContextNode) -> Result<ContextVarNode, ExprErr> {
+        if let Some(this) = ctx
+            .var_by_name_or_recurse(self, "this")
+            .into_expr_err(loc)?
+        {
+            return Ok(this.latest_version(self));
+        }
+        let contract = ctx.associated_contract(self).into_expr_err(loc)?;
+        let mut var = ContextVar::new_from_contract(loc, contract, self).into_expr_err(loc)?;
+        var.name = "this".to_string();
+        var.display_name = "this".to_string();
+        let cvar = self.add_node(Node::ContextVar(var));
+        ctx.add_var(cvar.into(), self).into_expr_err(loc)?;
+        self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
+        Ok(cvar.into())
+    }
+
     fn block_access(
         &mut self,
         loc: Loc,
@@ -279,13 +298,14 @@ pub trait Env: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
     ) -> Result<ExprRet, ExprErr> {
         let name = format!("msg.{}", ident_name);
         tracing::trace!("Msg Env member access: {}", name);
+        let msg = ctx.msg(self).into_expr_err(loc)?;
 
         if let Some(attr_var) = ctx.var_by_name_or_recurse(self, &name).into_expr_err(loc)? {
             Ok(ExprRet::Single(attr_var.latest_version(self).into()))
         } else {
             let (node, name) = match ident_name {
                 "data" => {
-                    if let Some(d) = self.msg().underlying(self).into_expr_err(loc)?.data.clone() {
+                    if let Some(d) = msg.underlying(self).into_expr_err(loc)?.data.clone() {
                         let c = Concrete::from(d);
                         (
                             self.add_node(Node::Concrete(c)).into(),
@@ -307,7 +327,7 @@ pub trait Env: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                     }
                 }
                 "sender" => {
-                    if let Some(d) = self.msg().underlying(self).into_expr_err(loc)?.sender {
+                    if let Some(d) = msg.underlying(self).into_expr_err(loc)?.sender {
                         let c = Concrete::from(d);
                         (
                             self.add_node(Node::Concrete(c)).into(),
@@ -328,7 +348,7 @@ pub trait Env: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                     }
                 }
                 "sig" => {
-                    if let Some(d) = self.msg().underlying(self).into_expr_err(loc)?.sig {
+                    if let Some(d) = msg.underlying(self).into_expr_err(loc)?.sig {
                         let c = Concrete::from(d);
                         (
                             self.add_node(Node::Concrete(c)).into(),
@@ -349,7 +369,7 @@ pub trait Env: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                     }
                 }
                 "value" => {
-                    if let Some(d) = self.msg().underlying(self).into_expr_err(loc)?.value {
+                    if let Some(d) = msg.underlying(self).into_expr_err(loc)?.value {
                         let c = Concrete::from(d);
                         (
                             self.add_node(Node::Concrete(c)).into(),
@@ -370,7 +390,7 @@ pub trait Env: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                     }
                 }
                 "origin" => {
-                    if let Some(d) = self.msg().underlying(self).into_expr_err(loc)?.origin {
+                    if let Some(d) = msg.underlying(self).into_expr_err(loc)?.origin {
                         let c = Concrete::from(d);
                         (
                             self.add_node(Node::Concrete(c)).into(),
@@ -391,7 +411,7 @@ pub trait Env: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                     }
                 }
                 "gasprice" => {
-                    if let Some(d) = self.msg().underlying(self).into_expr_err(loc)?.gasprice {
+                    if let Some(d) = msg.underlying(self).into_expr_err(loc)?.gasprice {
                         let c = Concrete::from(d);
                         (
                             self.add_node(Node::Concrete(c)).into(),
@@ -412,7 +432,7 @@ pub trait Env: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                     }
                 }
                 "gaslimit" => {
-                    if let Some(d) = self.msg().underlying(self).into_expr_err(loc)?.gaslimit {
+                    if let Some(d) = msg.underlying(self).into_expr_err(loc)?.gaslimit {
                         let c = Concrete::from(d);
                         (self.add_node(Node::Concrete(c)).into(), "".to_string())
                     } else {
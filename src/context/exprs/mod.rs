@@ -71,6 +71,7 @@ pub enum ExprErr {
     TakeFromFork(Loc, String),
     GraphError(Loc, GraphError),
     Unresolved(Loc, String),
+    SmtSolver(Loc, String),
 }
 
 impl ExprErr {
@@ -105,6 +106,7 @@ impl ExprErr {
             TakeFromFork(loc, ..) => *loc,
             GraphError(loc, ..) => *loc,
             Unresolved(loc, ..) => *loc,
+            SmtSolver(loc, ..) => *loc,
         }
     }
 
@@ -132,6 +134,7 @@ impl ExprErr {
             InvalidFunctionInput(_, msg, ..) => msg,
             TakeFromFork(_, msg, ..) => msg,
             Unresolved(_, msg, ..) => msg,
+            SmtSolver(_, msg, ..) => msg,
             GraphError(_loc, shared::analyzer::GraphError::NodeConfusion(msg), ..) => msg,
             GraphError(_loc, shared::analyzer::GraphError::MaxStackDepthReached(msg), ..) => msg,
             GraphError(_loc, shared::analyzer::GraphError::MaxStackWidthReached(msg), ..) => msg,
@@ -169,6 +172,7 @@ impl ExprErr {
             NonStoragePush(..) => "Pushing on non-storage based array is unsupported",
             IntrinsicNamedArgs(..) => "Arguments in calls to intrinsic functions cannot be named",
             InvalidFunctionInput(..) => "Arguments to this function call do not match required types",
+            SmtSolver(..) => "The SMT solver could not check the conditions of this path",
             TakeFromFork(..) => "IR Error: Tried to take from an child context that ended up forking",
             GraphError(_loc, shared::analyzer::GraphError::NodeConfusion(_), ..) => "Graph IR Error: Node type confusion. This is potentially a bug. Please report it at https://github.com/nascentxyz/pyrometer",
             GraphError(_loc, shared::analyzer::GraphError::MaxStackDepthReached(_), ..) => "Max call depth reached - either recursion or loop",
@@ -15,6 +15,7 @@ use shared::{
         elem_ty::{Elem, RangeConcrete},
         Range, RangeEval, SolcRange,
     },
+    smt::{SmtEncoder, SmtResult},
     Edge,
 };
 use solang_parser::helpers::CodeLocation;
@@ -794,9 +795,37 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
             }
         }
 
+        if tmp_cvar.is_some() && self.smt_killable(ctx, loc)? {
+            tracing::trace!("smt killable");
+            ctx.kill(self, loc, KilledKind::Unreachable)
+                .into_expr_err(loc)?;
+            return Ok(None);
+        }
+
         Ok(tmp_cvar)
     }
 
+    /// Checks whether the dependencies of a context are unsatisfiable with the configured SMT solver. Without a
+    /// solver, or if the solver fails or can't decide, the context is assumed reachable
+    fn smt_killable(&mut self, ctx: ContextNode, loc: Loc) -> Result<bool, ExprErr> {
+        let Some(solver) = self.smt_solver() else {
+            return Ok(false);
+        };
+        if ctx.killed_loc(self).into_expr_err(loc)?.is_some() {
+            return Ok(false);
+        }
+        let Some(script) = SmtEncoder::encode_ctx(ctx, self).into_expr_err(loc)? else {
+            return Ok(false);
+        };
+        match solver.check(&script) {
+            Ok(res) => Ok(res == SmtResult::Unsat),
+            Err(e) => {
+                self.add_expr_err(ExprErr::SmtSolver(loc, e));
+                Ok(false)
+            }
+        }
+    }
+
     /// Checks and returns whether the require statement is killable (i.e. impossible)
     fn const_killable(&mut self, op: RangeOp, lhs_range: SolcRange, rhs_range: SolcRange) -> bool {
         // check that the op is satisfied, return it as a bool
@@ -2,8 +2,9 @@ use crate::context::exprs::IntoExprErr;
 use crate::context::ExprErr;
 use crate::context::{exprs::env::Env, ContextBuilder};
 use shared::nodes::VarNode;
+use shared::range::SolcRange;
 use shared::{analyzer::AnalyzerLike, context::*, Edge, Node};
-use solang_parser::pt::Expression;
+use solang_parser::pt::{Expression, FunctionTy, Loc};
 
 use solang_parser::pt::Identifier;
 
@@ -69,7 +70,7 @@ pub trait Variable: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                 None
             };
 
-            let var = if let Some(con) = const_var {
+            let mut var = if let Some(con) = const_var {
                 con
             } else {
                 match self.node(idx) {
@@ -95,6 +96,12 @@ pub trait Variable: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
                 }
             };
 
+            if var.storage.is_some() && self.storage_from_constructor() {
+                if let Some(range) = self.storage_entry_range(ident.loc, ctx, VarNode::from(idx))? {
+                    var.ty.set_range(range).into_expr_err(ident.loc)?;
+                }
+            }
+
             let new_cvarnode = self.add_node(Node::ContextVar(var));
 
             ctx.add_var(new_cvarnode.into(), self)
@@ -132,4 +139,33 @@ pub trait Variable: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
             Ok(())
         }
     }
+
+    /// Gets the range a storage variable has when it is first accessed in a context. In a constructor, that is
+    /// the value the contract is deployed with, and elsewhere the one the constructors leave behind
+    fn storage_entry_range(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        var: VarNode,
+    ) -> Result<Option<SolcRange>, ExprErr> {
+        let entry_fn = ctx
+            .first_ancestor(self)
+            .into_expr_err(loc)?
+            .associated_fn(self)
+            .into_expr_err(loc)?;
+        if entry_fn.underlying(self).into_expr_err(loc)?.ty != FunctionTy::Constructor {
+            return Ok(self.storage_entry().get(&var).cloned());
+        }
+
+        // a derived contract's constructor runs after its bases', so only its own variables are known
+        let is_own = entry_fn
+            .maybe_associated_contract(self)
+            .map(|contract| contract.vars(self).contains(&var))
+            .unwrap_or(false);
+        if is_own {
+            var.deploy_range(self).into_expr_err(loc)
+        } else {
+            Ok(None)
+        }
+    }
 }
@@ -11,7 +11,7 @@ use shared::nodes::TyNode;
 
 use shared::analyzer::Search;
 use shared::analyzer::{AnalyzerLike, GraphLike};
-use shared::nodes::Concrete;
+use shared::nodes::{Concrete, ConcreteNode};
 
 use shared::{
     context::*,
@@ -25,6 +25,7 @@ use shared::{
 };
 
 use solang_parser::pt::{Expression, Loc};
+use std::collections::BTreeSet;
 
 impl<T> IntrinsicFuncCaller for T where
     T: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized + GraphLike + Search
@@ -47,90 +48,106 @@ pub trait IntrinsicFuncCaller:
                 if let Some(func_name) = &underlying.name {
                     match &*func_name.name {
                         "abi.decode" => {
-                            // we skip the first because that is what is being decoded.
-                            // TODO: check if we have a concrete bytes value
-                            fn match_decode(
-                                ctx: ContextNode,
-                                loc: &Loc,
-                                ret: ExprRet,
-                                analyzer: &mut impl AnalyzerLike,
-                            ) -> Result<(), ExprErr> {
-                                match ret {
-                                    ExprRet::Single(expect_builtin) => {
-                                        match analyzer.node(expect_builtin) {
-                                            Node::Builtin(_) => {
-                                                let var = ContextVar::new_from_builtin(
-                                                    *loc,
-                                                    expect_builtin.into(),
-                                                    analyzer,
-                                                )
-                                                .into_expr_err(*loc)?;
-                                                let node = analyzer.add_node(Node::ContextVar(var));
-                                                ctx.add_var(node.into(), analyzer)
-                                                    .into_expr_err(*loc)?;
-                                                analyzer.add_edge(
-                                                    node,
-                                                    ctx,
-                                                    Edge::Context(ContextEdge::Variable),
-                                                );
-                                                ctx.push_expr(ExprRet::Single(node), analyzer)
-                                                    .into_expr_err(*loc)?;
-                                                Ok(())
-                                            }
-                                            Node::ContextVar(cvar) => {
-                                                let bn = analyzer
-                                                    .builtin_or_add(
-                                                        cvar.ty
-                                                            .as_builtin(analyzer)
-                                                            .into_expr_err(*loc)?,
-                                                    )
-                                                    .into();
-                                                let var = ContextVar::new_from_builtin(
-                                                    *loc, bn, analyzer,
-                                                )
-                                                .into_expr_err(*loc)?;
-                                                let node = analyzer.add_node(Node::ContextVar(var));
-                                                ctx.add_var(node.into(), analyzer)
-                                                    .into_expr_err(*loc)?;
-                                                analyzer.add_edge(
-                                                    node,
-                                                    ctx,
-                                                    Edge::Context(ContextEdge::Variable),
-                                                );
-                                                ctx.push_expr(ExprRet::Single(node), analyzer)
-                                                    .into_expr_err(*loc)?;
-                                                Ok(())
-                                            }
-                                            e => todo!("Unhandled type in abi.decode: {e:?}"),
-                                        }
-                                    }
-                                    ExprRet::Multi(inner) => inner.iter().try_for_each(|i| {
-                                        match_decode(ctx, loc, i.clone(), analyzer)
-                                    }),
-                                    ExprRet::CtxKilled(kind) => {
-                                        ctx.kill(analyzer, *loc, kind).into_expr_err(*loc)
+                            let calldata_args = is_calldata_args(&input_exprs[0]);
+                            if matches!(input_exprs[0], Expression::ArraySlice(..)) {
+                                // slices aren't parsed, but `msg.data[4:]` is known to hold the
+                                // arguments of the function being analyzed
+                                self.parse_ctx_expr(&input_exprs[1], ctx)?;
+                                return self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
+                                    let Some(tys) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                                        return Err(ExprErr::NoRhs(loc, "abi.decode was not given the types for decoding".to_string()))
+                                    };
+                                    if matches!(tys, ExprRet::CtxKilled(_)) {
+                                        ctx.push_expr(tys, analyzer).into_expr_err(loc)?;
+                                        return Ok(());
                                     }
-                                    e => panic!("This is invalid solidity: {:?}", e),
-                                }
+                                    let values = if calldata_args {
+                                        analyzer.calldata_args(ctx, loc)?
+                                    } else {
+                                        None
+                                    };
+                                    analyzer.abi_decode(ctx, loc, &tys, values)
+                                });
                             }
-                            self.parse_ctx_expr(&input_exprs[1], ctx)?;
+
+                            self.parse_ctx_expr(&input_exprs[0], ctx)?;
                             self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
-                                let Some(ret) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
-                                    return Err(ExprErr::NoRhs(loc, "abi.decode was not given the types for decoding".to_string()))
+                                let Some(data) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                                    return Err(ExprErr::NoRhs(loc, "abi.decode was not given the data to decode".to_string()))
                                 };
-                                if matches!(ret, ExprRet::CtxKilled(_)) {
-                                    ctx.push_expr(ret, analyzer).into_expr_err(loc)?;
+                                if matches!(data, ExprRet::CtxKilled(_)) {
+                                    ctx.push_expr(data, analyzer).into_expr_err(loc)?;
                                     return Ok(());
                                 }
-                                match_decode(ctx, &loc, ret, analyzer)
+
+                                analyzer.parse_ctx_expr(&input_exprs[1], ctx)?;
+                                analyzer.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
+                                    let Some(tys) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                                        return Err(ExprErr::NoRhs(loc, "abi.decode was not given the types for decoding".to_string()))
+                                    };
+                                    if matches!(tys, ExprRet::CtxKilled(_)) {
+                                        ctx.push_expr(tys, analyzer).into_expr_err(loc)?;
+                                        return Ok(());
+                                    }
+                                    let values = analyzer.abi_encoded_values(ctx, loc, &data)?;
+                                    analyzer.abi_decode(ctx, loc, &tys, values)
+                                })
                             })
                         }
                         "abi.encode"
                         | "abi.encodePacked"
-                        | "abi.encodeCall"
                         | "abi.encodeWithSignature"
                         | "abi.encodeWithSelector" => {
-                            // currently we dont support concrete abi encoding, TODO
+                            let is_encode = func_name.name == "abi.encode";
+                            let name = func_name.name.clone();
+                            let fold = move |args: &[Concrete]| match &*name {
+                                "abi.encode" => Concrete::abi_encode(args),
+                                "abi.encodePacked" => Concrete::abi_encode_packed(args),
+                                "abi.encodeWithSignature" => {
+                                    Concrete::abi_encode_with_signature(args.first()?, &args[1..])
+                                }
+                                _ => Concrete::abi_encode_with_selector(args.first()?, &args[1..]),
+                            };
+                            if input_exprs.is_empty() {
+                                self.fold_concrete_intrinsic(
+                                    ctx,
+                                    *loc,
+                                    &ExprRet::Multi(vec![]),
+                                    Builtin::DynamicBytes,
+                                    &fold,
+                                )?;
+                                return Ok(());
+                            }
+                            self.parse_inputs(ctx, *loc, input_exprs)?;
+                            self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
+                                let Some(inputs) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                                    return Err(ExprErr::NoRhs(loc, "abi encoding was not given inputs".to_string()))
+                                };
+                                if matches!(inputs, ExprRet::CtxKilled(_)) {
+                                    ctx.push_expr(inputs, analyzer).into_expr_err(loc)?;
+                                    return Ok(());
+                                }
+                                let encoded = analyzer.fold_concrete_intrinsic(
+                                    ctx,
+                                    loc,
+                                    &inputs,
+                                    Builtin::DynamicBytes,
+                                    &fold,
+                                )?;
+                                if is_encode {
+                                    let values = inputs
+                                        .as_flat_vec()
+                                        .into_iter()
+                                        .map(ContextVarNode::from)
+                                        .collect();
+                                    ctx.add_abi_encoding(encoded, values, analyzer)
+                                        .into_expr_err(loc)?;
+                                }
+                                Ok(())
+                            })
+                        }
+                        "abi.encodeCall" => {
+                            // the selector of a function pointer is not known, TODO
                             let bn = self.builtin_or_add(Builtin::DynamicBytes);
                             let cvar = ContextVar::new_from_builtin(*loc, bn.into(), self)
                                 .into_expr_err(*loc)?;
@@ -142,6 +159,7 @@ pub trait IntrinsicFuncCaller:
                             Ok(())
                         }
                         "delegatecall" | "staticcall" | "call" => {
+                            let kind = func_name.name.clone();
                             ctx.pop_expr_latest(*loc, self).into_expr_err(*loc)?;
                             // TODO: try to be smarter based on the address input
                             let booln = self.builtin_or_add(Builtin::Bool);
@@ -157,6 +175,13 @@ pub trait IntrinsicFuncCaller:
                             let node = self.add_node(Node::ContextVar(cvar));
                             ctx.add_var(node.into(), self).into_expr_err(*loc)?;
                             self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
+                            if kind != "staticcall" {
+                                ctx.add_effect(
+                                    ContextEffect::LowLevelCall { loc: *loc, kind },
+                                    self,
+                                )
+                                .into_expr_err(*loc)?;
+                            }
                             ctx.push_expr(
                                 ExprRet::Multi(vec![
                                     ExprRet::Single(bool_node),
@@ -197,6 +222,31 @@ pub trait IntrinsicFuncCaller:
                             })
                         }
                         "type" => self.parse_ctx_expr(&input_exprs[0], ctx),
+                        "selfdestruct" => {
+                            self.parse_ctx_expr(&input_exprs[0], ctx)?;
+                            self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
+                                let Some(recipient) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                                    return Err(ExprErr::NoRhs(loc, "selfdestruct(..) was not given a recipient".to_string()))
+                                };
+                                if matches!(recipient, ExprRet::CtxKilled(_)) {
+                                    ctx.push_expr(recipient, analyzer).into_expr_err(loc)?;
+                                    return Ok(());
+                                }
+                                let recipient = recipient.expect_single().into_expr_err(loc)?;
+                                ctx.add_sink(
+                                    ContextSink {
+                                        loc,
+                                        kind: SinkKind::SelfdestructRecipient,
+                                        var: ContextVarNode::from(recipient).latest_version(analyzer),
+                                    },
+                                    analyzer,
+                                )
+                                .into_expr_err(loc)?;
+                                // nothing after a `selfdestruct` is executed
+                                ctx.kill(analyzer, loc, KilledKind::Ended)
+                                    .into_expr_err(loc)
+                            })
+                        }
                         "push" => {
                             assert!(input_exprs.len() == 2);
                             self.parse_ctx_expr(&input_exprs[0], ctx)?;
@@ -264,6 +314,7 @@ pub trait IntrinsicFuncCaller:
                                     return Err(ExprErr::NoLhs(loc, "array[].pop() was not an array to pop from".to_string()))
                                 };
                                 let len = len.expect_single().into_expr_err(loc)?;
+                                ctx.add_array_access(ContextArrayAccess { loc, len: len.into(), index: None }, analyzer).into_expr_err(loc)?;
                                 let next_len = analyzer.advance_var_in_ctx(len.into(), loc, ctx)?;
                                 next_len.set_range_min(analyzer, Elem::from(len) - Elem::from(Concrete::from(U256::from(1)))).into_expr_err(loc)?;
                                 next_len.set_range_max(analyzer, Elem::from(len) - Elem::from(Concrete::from(U256::from(1)))).into_expr_err(loc)?;
@@ -281,17 +332,20 @@ pub trait IntrinsicFuncCaller:
                         "keccak256" => {
                             self.parse_ctx_expr(&input_exprs[0], ctx)?;
                             self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
-                                let Some(_input) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
-                                    return Err(ExprErr::NoRhs(loc, "abi.decode was not given the types for decoding".to_string()))
+                                let Some(input) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                                    return Err(ExprErr::NoRhs(loc, "keccak256 was not given an input".to_string()))
                                 };
-                                let var = ContextVar::new_from_builtin(
+                                if matches!(input, ExprRet::CtxKilled(_)) {
+                                    ctx.push_expr(input, analyzer).into_expr_err(loc)?;
+                                    return Ok(());
+                                }
+                                analyzer.fold_concrete_intrinsic(
+                                    ctx,
                                     loc,
-                                    analyzer.builtin_or_add(Builtin::Bytes(32)).into(),
-                                    analyzer,
-                                )
-                                .into_expr_err(loc)?;
-                                let cvar = analyzer.add_node(Node::ContextVar(var));
-                                ctx.push_expr(ExprRet::Single(cvar), analyzer).into_expr_err(loc)?;
+                                    &input,
+                                    Builtin::Bytes(32),
+                                    &|args| args.first()?.keccak256(),
+                                )?;
                                 Ok(())
                             })
                         }
@@ -299,20 +353,19 @@ pub trait IntrinsicFuncCaller:
                             self.parse_ctx_expr(&input_exprs[0], ctx)?;
                             self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
                                 let Some(input) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
-                                    return Err(ExprErr::NoRhs(loc, "abi.decode was not given the types for decoding".to_string()))
+                                    return Err(ExprErr::NoRhs(loc, "sha256 was not given an input".to_string()))
                                 };
                                 if matches!(input, ExprRet::CtxKilled(_)) {
                                     ctx.push_expr(input, analyzer).into_expr_err(loc)?;
                                     return Ok(());
                                 }
-                                let var = ContextVar::new_from_builtin(
+                                analyzer.fold_concrete_intrinsic(
+                                    ctx,
                                     loc,
-                                    analyzer.builtin_or_add(Builtin::Bytes(32)).into(),
-                                    analyzer,
-                                )
-                                .into_expr_err(loc)?;
-                                let cvar = analyzer.add_node(Node::ContextVar(var));
-                                ctx.push_expr(ExprRet::Single(cvar), analyzer).into_expr_err(loc)?;
+                                    &input,
+                                    Builtin::Bytes(32),
+                                    &|args| args.first()?.sha256(),
+                                )?;
                                 Ok(())
                             })
                         }
@@ -320,20 +373,19 @@ pub trait IntrinsicFuncCaller:
                             self.parse_ctx_expr(&input_exprs[0], ctx)?;
                             self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
                                 let Some(input) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
-                                    return Err(ExprErr::NoRhs(loc, "abi.decode was not given the types for decoding".to_string()))
+                                    return Err(ExprErr::NoRhs(loc, "ripemd160 was not given an input".to_string()))
                                 };
                                 if matches!(input, ExprRet::CtxKilled(_)) {
                                     ctx.push_expr(input, analyzer).into_expr_err(loc)?;
                                     return Ok(());
                                 }
-                                let var = ContextVar::new_from_builtin(
+                                analyzer.fold_concrete_intrinsic(
+                                    ctx,
                                     loc,
-                                    analyzer.builtin_or_add(Builtin::Bytes(32)).into(),
-                                    analyzer,
-                                )
-                                .into_expr_err(loc)?;
-                                let cvar = analyzer.add_node(Node::ContextVar(var));
-                                ctx.push_expr(ExprRet::Single(cvar), analyzer).into_expr_err(loc)?;
+                                    &input,
+                                    Builtin::Bytes(20),
+                                    &|args| args.first()?.ripemd160(),
+                                )?;
                                 Ok(())
                             })
                         }
@@ -875,6 +927,212 @@ pub trait IntrinsicFuncCaller:
         }
     }
 
+    /// Pushes and returns the result of a hashing or encoding intrinsic over `inputs`. If every
+    /// input is a constant with a concrete value, the exact result is computed by `fold`,
+    /// otherwise an unconstrained variable of type `ret_ty` is pushed.
+    fn fold_concrete_intrinsic(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        inputs: &ExprRet,
+        ret_ty: Builtin,
+        fold: &dyn Fn(&[Concrete]) -> Option<Concrete>,
+    ) -> Result<ContextVarNode, ExprErr> {
+        let concretes = inputs
+            .as_flat_vec()
+            .into_iter()
+            .map(|idx| self.concrete_value(ContextVarNode::from(idx), loc))
+            .collect::<Result<Option<Vec<_>>, ExprErr>>()?;
+        let var = if let Some(folded) = concretes.and_then(|concretes| fold(&concretes)) {
+            let cnode = ConcreteNode::from(self.add_node(Node::Concrete(folded)));
+            ContextVar::new_from_concrete(loc, ctx, cnode, self).into_expr_err(loc)?
+        } else {
+            ContextVar::new_from_builtin(loc, self.builtin_or_add(ret_ty).into(), self)
+                .into_expr_err(loc)?
+        };
+        let node = self.add_node(Node::ContextVar(var));
+        ctx.add_var(node.into(), self).into_expr_err(loc)?;
+        self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
+        ctx.push_expr(ExprRet::Single(node), self)
+            .into_expr_err(loc)?;
+        Ok(node.into())
+    }
+
+    /// Gets the concrete value of a variable if it is a constant
+    fn concrete_value(&self, var: ContextVarNode, loc: Loc) -> Result<Option<Concrete>, ExprErr> {
+        if var.is_concrete(self).into_expr_err(loc)? {
+            return Ok(Some(var.as_concrete(self).into_expr_err(loc)?));
+        }
+        if !var.is_const(self).into_expr_err(loc)? {
+            return Ok(None);
+        }
+        Ok(var
+            .evaled_range_min(self)
+            .into_expr_err(loc)?
+            .and_then(|min| min.maybe_concrete())
+            .map(|min| min.val))
+    }
+
+    /// Pushes the values decoded by `abi.decode` as the types `tys`. If the `values` that were
+    /// encoded are known, each decoded value of the same type is constrained to be equal to them.
+    fn abi_decode(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        tys: &ExprRet,
+        values: Option<Vec<ContextVarNode>>,
+    ) -> Result<(), ExprErr> {
+        let tys = tys.as_vec();
+        let values = values.filter(|values| values.len() == tys.len());
+        let mut decoded = tys
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| {
+                let (ExprRet::Single(ty) | ExprRet::SingleLiteral(ty)) = ty else {
+                    return Err(ExprErr::InvalidFunctionInput(
+                        loc,
+                        "abi.decode was given an invalid type to decode".to_string(),
+                    ));
+                };
+                let var = self.abi_decoded_var(ctx, loc, *ty)?;
+                if let Some(values) = &values {
+                    self.link_decoded(loc, var, values[i])?;
+                }
+                Ok(ExprRet::Single(var.into()))
+            })
+            .collect::<Result<Vec<_>, ExprErr>>()?;
+
+        let ret = if decoded.len() == 1 {
+            decoded.swap_remove(0)
+        } else {
+            ExprRet::Multi(decoded)
+        };
+        ctx.push_expr(ret, self).into_expr_err(loc)
+    }
+
+    /// Creates an unconstrained variable of a type given to `abi.decode`. Any type that can be
+    /// a variable can be decoded, including structs, arrays and contracts.
+    fn abi_decoded_var(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        ty: NodeIdx,
+    ) -> Result<ContextVarNode, ExprErr> {
+        let var = match self.node(ty) {
+            Node::Builtin(_) => {
+                ContextVar::new_from_builtin(loc, ty.into(), self).into_expr_err(loc)?
+            }
+            Node::ContextVar(cvar) => {
+                let bn = self
+                    .builtin_or_add(cvar.ty.as_builtin(self).into_expr_err(loc)?)
+                    .into();
+                ContextVar::new_from_builtin(loc, bn, self).into_expr_err(loc)?
+            }
+            _ => {
+                let Some(var_ty) = VarType::try_from_idx(self, ty) else {
+                    return Err(ExprErr::InvalidFunctionInput(
+                        loc,
+                        "abi.decode was given a type that can't be decoded".to_string(),
+                    ));
+                };
+                let name = var_ty.as_string(self).into_expr_err(loc)?;
+                ContextVar {
+                    loc: Some(loc),
+                    name: format!("tmp_{name}_{}", ctx.new_tmp(self).into_expr_err(loc)?),
+                    display_name: format!("tmp_{name}"),
+                    storage: None,
+                    is_tmp: true,
+                    tmp_of: None,
+                    is_symbolic: false,
+                    is_return: false,
+                    ty: var_ty,
+                }
+            }
+        };
+        let node = self.add_node(Node::ContextVar(var));
+        ctx.add_var(node.into(), self).into_expr_err(loc)?;
+        self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
+        Ok(node.into())
+    }
+
+    /// Constrains a decoded value to be the value that was encoded, if both have the same type
+    fn link_decoded(
+        &mut self,
+        loc: Loc,
+        decoded: ContextVarNode,
+        value: ContextVarNode,
+    ) -> Result<(), ExprErr> {
+        let decoded_ty = decoded.ty(self).into_expr_err(loc)?.as_builtin(self);
+        let value_ty = value.ty(self).into_expr_err(loc)?.as_builtin(self);
+        let same_ty = matches!((decoded_ty, value_ty), (Ok(d), Ok(v)) if d == v);
+        if same_ty && decoded.range(self).into_expr_err(loc)?.is_some() {
+            decoded
+                .set_range_min(self, Elem::from(value))
+                .into_expr_err(loc)?;
+            decoded
+                .set_range_max(self, Elem::from(value))
+                .into_expr_err(loc)?;
+        }
+        Ok(())
+    }
+
+    /// Gets the values that were `abi.encode`d into the bytes being decoded, following
+    /// assignments of the bytes back to the encoding
+    fn abi_encoded_values(
+        &self,
+        ctx: ContextNode,
+        loc: Loc,
+        data: &ExprRet,
+    ) -> Result<Option<Vec<ContextVarNode>>, ExprErr> {
+        let (ExprRet::Single(data) | ExprRet::SingleLiteral(data)) = data else {
+            return Ok(None);
+        };
+        let mut data = ContextVarNode::from(*data);
+        let mut seen = BTreeSet::new();
+        while seen.insert(data) {
+            if let Some(values) = ctx.abi_encoding(data, self).into_expr_err(loc)? {
+                return Ok(Some(values));
+            }
+            match (
+                data.range_min(self).into_expr_err(loc)?,
+                data.range_max(self).into_expr_err(loc)?,
+            ) {
+                (Some(Elem::Dynamic(min)), Some(Elem::Dynamic(max))) if min.idx == max.idx => {
+                    data = ContextVarNode::from(min.idx);
+                }
+                _ => return Ok(None),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Gets the parameters of the function being analyzed as they were on entry, which are what
+    /// `msg.data[4:]` decodes to. Inside of a call to another function they aren't known.
+    fn calldata_args(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+    ) -> Result<Option<Vec<ContextVarNode>>, ExprErr> {
+        let func = ctx.associated_fn(self).into_expr_err(loc)?;
+        let entry = ctx.first_ancestor(self).into_expr_err(loc)?;
+        if entry.associated_fn(self).into_expr_err(loc)? != func {
+            return Ok(None);
+        }
+
+        func.params(self)
+            .iter()
+            .map(|param| {
+                let Some(name) = param.maybe_name(self).into_expr_err(loc)? else {
+                    return Ok(None);
+                };
+                Ok(ctx
+                    .var_by_name_or_recurse(self, &name)
+                    .into_expr_err(loc)?
+                    .map(|var| var.first_version(self)))
+            })
+            .collect()
+    }
+
     #[tracing::instrument(level = "trace", skip_all)]
     fn concat(
         &mut self,
@@ -1058,3 +1316,16 @@ pub trait IntrinsicFuncCaller:
         }
     }
 }
+
+/// Whether an expression is `msg.data[4:]`, the arguments of the call after its selector
+fn is_calldata_args(expr: &Expression) -> bool {
+    let Expression::ArraySlice(_, base, Some(start), None) = expr else {
+        return false;
+    };
+    let Expression::MemberAccess(_, msg, member) = &**base else {
+        return false;
+    };
+    matches!(&**msg, Expression::Variable(ident) if ident.name == "msg")
+        && member.name == "data"
+        && matches!(&**start, Expression::NumberLiteral(_, int, exp, _) if int == "4" && exp.is_empty())
+}
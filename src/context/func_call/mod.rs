@@ -1,4 +1,4 @@
-use crate::context::exprs::IntoExprErr;
+use crate::context::exprs::{Env, IntoExprErr, Require};
 use crate::context::func_call::{
     internal_call::InternalFuncCaller, intrinsic_call::IntrinsicFuncCaller,
     namespaced_call::NameSpaceFuncCaller,
@@ -14,8 +14,10 @@ use shared::context::*;
 use solang_parser::helpers::CodeLocation;
 use std::collections::BTreeMap;
 
+use ethers_core::types::U256;
+use shared::range::elem_ty::Elem;
 use shared::range::Range;
-use solang_parser::pt::{Expression, Loc, NamedArgument, StorageLocation};
+use solang_parser::pt::{Expression, Loc, NamedArgument, Statement, StorageLocation};
 
 use crate::VarType;
 
@@ -83,6 +85,48 @@ pub trait FuncCaller:
         }
     }
 
+    /// Calls a function with a `{value: .., gas: ..}` block. The value is recorded for the external call it sets
+    /// up, to become the callee's `msg.value`. The other options don't affect the analysis
+    fn fn_call_block_expr(
+        &mut self,
+        ctx: ContextNode,
+        loc: &Loc,
+        func_expr: &Expression,
+        call_block: &Statement,
+        input_exprs: &[Expression],
+    ) -> Result<(), ExprErr> {
+        let Statement::Args(_, args) = call_block else {
+            return self.fn_call_expr(ctx, loc, func_expr, input_exprs);
+        };
+        let Some(value) = args.iter().find(|arg| arg.name.name == "value") else {
+            return self.fn_call_expr(ctx, loc, func_expr, input_exprs);
+        };
+        // the call's context is created at the function's name
+        let name_loc = match func_expr {
+            Expression::MemberAccess(_, _, ident) => ident.loc,
+            _ => func_expr.loc(),
+        };
+
+        self.parse_ctx_expr(&value.expr, ctx)?;
+        self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
+            let Some(value) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                return Err(ExprErr::NoRhs(
+                    loc,
+                    "Call block was not given a value".to_string(),
+                ));
+            };
+            if matches!(value, ExprRet::CtxKilled(_)) {
+                ctx.push_expr(value, analyzer).into_expr_err(loc)?;
+                return Ok(());
+            }
+            let value = ContextVarNode::from(value.expect_single().into_expr_err(loc)?);
+            let value = value.latest_version(analyzer);
+            ctx.add_call_value(name_loc, value, analyzer)
+                .into_expr_err(loc)?;
+            analyzer.fn_call_expr(ctx, &loc, func_expr, input_exprs)
+        })
+    }
+
     fn match_intrinsic_fallback(
         &mut self,
         ctx: ContextNode,
@@ -348,6 +392,9 @@ pub trait FuncCaller:
         curr_ctx
             .set_child_call(callee_ctx, self)
             .into_expr_err(loc)?;
+        if fn_ext {
+            self.enter_call_frame(loc, curr_ctx, callee_ctx)?;
+        }
         let ctx_fork = self.add_node(Node::FunctionCall);
         self.add_edge(ctx_fork, curr_ctx, Edge::Context(ContextEdge::Subcontext));
         self.add_edge(ctx_fork, func_node, Edge::Context(ContextEdge::Call));
@@ -359,6 +406,67 @@ pub trait FuncCaller:
         Ok(callee_ctx)
     }
 
+    /// Gives the context of an external call its own call frame environment. `msg.sender` is the calling contract's
+    /// `address(this)` and `msg.value` is the call's `{value: ..}` option, or zero without one. The calldata is
+    /// unknown and `tx` stays the transaction's. The frame's `Msg` leaves the sender unset, as the calling contract's
+    /// address isn't concrete; the frame's `msg.sender` variable is bound instead, and the callee's modifiers and
+    /// internal calls resolve it through the frame
+    fn enter_call_frame(
+        &mut self,
+        loc: Loc,
+        caller_ctx: ContextNode,
+        callee_ctx: ContextNode,
+    ) -> Result<(), ExprErr> {
+        let tx = caller_ctx
+            .msg(self)
+            .into_expr_err(loc)?
+            .underlying(self)
+            .into_expr_err(loc)?
+            .clone();
+        let value = caller_ctx.take_call_value(loc, self).into_expr_err(loc)?;
+        let frame = Msg {
+            value: if value.is_none() {
+                Some(U256::zero())
+            } else {
+                None
+            },
+            origin: tx.origin,
+            gasprice: tx.gasprice,
+            gaslimit: tx.gaslimit,
+            ..Default::default()
+        };
+        let frame = MsgNode::from(self.add_node(Node::Msg(frame)));
+        let callee = callee_ctx.underlying_mut(self).into_expr_err(loc)?;
+        callee.msg = Some(frame);
+        callee.frame_vars.clear();
+
+        let this = self.this_var(loc, caller_ctx)?;
+        let address = self.builtin_or_add(Builtin::Address);
+        let mut frame_vars = vec![("msg.sender", address, this)];
+        if let Some(value) = value {
+            let uint = self.builtin_or_add(Builtin::Uint(256));
+            frame_vars.push(("msg.value", uint, value));
+        }
+        for (name, ty, value) in frame_vars {
+            let mut var = ContextVar::new_from_builtin(loc, ty.into(), self).into_expr_err(loc)?;
+            var.name = name.to_string();
+            var.display_name = name.to_string();
+            var.is_tmp = false;
+            var.is_symbolic = true;
+            let cvar = ContextVarNode::from(self.add_node(Node::ContextVar(var)));
+            cvar.set_range_min(self, Elem::from(value))
+                .into_expr_err(loc)?;
+            cvar.set_range_max(self, Elem::from(value))
+                .into_expr_err(loc)?;
+            callee_ctx.add_var(cvar, self).into_expr_err(loc)?;
+            callee_ctx
+                .add_frame_var(name, cvar, self)
+                .into_expr_err(loc)?;
+            self.add_edge(cvar, callee_ctx, Edge::Context(ContextEdge::Variable));
+        }
+        Ok(())
+    }
+
     /// Maps inputs to function parameters such that if there is a renaming i.e. `a(uint256 x)` is called via `a(y)`,
     /// we map `y -> x` for future lookups
     fn map_inputs_to_params(
@@ -460,22 +568,32 @@ pub trait FuncCaller:
         //  3. Call modifier 0, then 1, then 2, ... then N.
         //  4. Call this function
         //  5. Finish modifier N.. then 2, then 1, then 0
+        if !entry_call && modifier_state.is_none() && self.summarize_calls() {
+            let mapping = params
+                .iter()
+                .zip(inputs.iter())
+                .map(|(param, input)| (*input, *param))
+                .collect::<BTreeMap<_, _>>();
+            if let Some(summary) = ctx
+                .join(loc, func_node, &mapping, self)
+                .into_expr_err(loc)?
+            {
+                return self.apply_summary(loc, ctx, func_node, summary);
+            }
+        }
+
+        if !entry_call && modifier_state.is_none() && self.closed_world() {
+            if let Some(impls) = self.closed_world_impls(loc, ctx, func_node)? {
+                return self.call_impls(loc, ctx, &impls, inputs, func_call_str);
+            }
+        }
+
         let callee_ctx = if entry_call {
             ctx
         } else {
             self.create_call_ctx(ctx, loc, func_node, modifier_state)?
         };
 
-        // TODO: implement joining
-        // if !entry_call {
-        //     let mapping = params
-        //         .iter()
-        //         .zip(inputs.iter())
-        //         .map(|(param, input)| (*input, *param))
-        //         .collect::<BTreeMap<_, _>>();
-        //     ctx.join(func_node, &mapping, self);
-        // }
-
         // handle remapping of variable names and bringing variables into the new context
         let renamed_inputs =
             self.map_inputs_to_params(loc, entry_call, params, inputs, callee_ctx)?;
@@ -525,6 +643,201 @@ pub trait FuncCaller:
         })
     }
 
+    /// Resolves an external call of a function without a body to its implementations in the deployable contracts
+    /// inheriting the function's contract. Returns `None` if the call can't be resolved: the function has a body or
+    /// isn't called externally, some contract doesn't implement it, or an implementation is already being called
+    /// on this path and would be re-entered without end
+    fn closed_world_impls(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        func_node: FunctionNode,
+    ) -> Result<Option<Vec<FunctionNode>>, ExprErr> {
+        let has_body = func_node
+            .underlying(self)
+            .into_expr_err(loc)?
+            .body
+            .is_some();
+        if has_body || !ctx.is_fn_ext(func_node, self).into_expr_err(loc)? {
+            return Ok(None);
+        }
+        let Some(contract) = func_node.maybe_associated_contract(self) else {
+            return Ok(None);
+        };
+        let candidates = contract.implementations(self);
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        let mut lineage = ctx.parent_list(self).into_expr_err(loc)?;
+        lineage.push(ctx);
+        let callers = lineage
+            .iter()
+            .map(|ctx| ctx.associated_fn(self))
+            .collect::<Result<Vec<_>, _>>()
+            .into_expr_err(loc)?;
+        let mut impls = vec![];
+        for candidate in candidates {
+            let Some(impl_func) = candidate
+                .implementation_of(func_node, self)
+                .into_expr_err(loc)?
+            else {
+                return Ok(None);
+            };
+            if callers.contains(&impl_func) {
+                return Ok(None);
+            }
+            if !impls.contains(&impl_func) {
+                impls.push(impl_func);
+            }
+        }
+        Ok(Some(impls))
+    }
+
+    /// Calls each of the implementations of an externally called function on its own fork of the context, with the
+    /// arguments of the call. Each is entered as an external call from the context, so it runs in its own call
+    /// frame and its paths are evaluated against the actual arguments
+    fn call_impls(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        impls: &[FunctionNode],
+        inputs: Vec<ContextVarNode>,
+        func_call_str: Option<&str>,
+    ) -> Result<(), ExprErr> {
+        let Some((impl_func, rest)) = impls.split_first() else {
+            return Ok(());
+        };
+        if rest.is_empty() {
+            let params = impl_func.params(self);
+            return self.func_call_inner(
+                false,
+                ctx,
+                *impl_func,
+                loc,
+                inputs,
+                params,
+                func_call_str,
+                None,
+            );
+        }
+
+        // one side of the fork calls this implementation, the other the rest of them
+        let impl_name = impl_func.loc_specified_name(self).into_expr_err(loc)?;
+        let rest_name = rest
+            .iter()
+            .map(|func| func.loc_specified_name(self))
+            .collect::<Result<Vec<_>, _>>()
+            .into_expr_err(loc)?
+            .join(", ");
+        let impl_ctx =
+            Context::new_subctx(ctx, None, loc, Some(&impl_name), None, false, self, None)
+                .into_expr_err(loc)?;
+        let impl_subctx = ContextNode::from(self.add_node(Node::Context(impl_ctx)));
+        let rest_ctx =
+            Context::new_subctx(ctx, None, loc, Some(&rest_name), None, false, self, None)
+                .into_expr_err(loc)?;
+        let rest_subctx = ContextNode::from(self.add_node(Node::Context(rest_ctx)));
+        ctx.set_child_fork(impl_subctx, rest_subctx, self)
+            .into_expr_err(loc)?;
+        let ctx_fork = self.add_node(Node::ContextFork);
+        self.add_edge(ctx_fork, ctx, Edge::Context(ContextEdge::ContextFork));
+        self.add_edge(
+            NodeIdx::from(impl_subctx.0),
+            ctx_fork,
+            Edge::Context(ContextEdge::Subcontext),
+        );
+        self.add_edge(
+            NodeIdx::from(rest_subctx.0),
+            ctx_fork,
+            Edge::Context(ContextEdge::Subcontext),
+        );
+
+        let params = impl_func.params(self);
+        self.func_call_inner(
+            false,
+            impl_subctx,
+            *impl_func,
+            loc,
+            inputs.clone(),
+            params,
+            func_call_str,
+            None,
+        )?;
+        self.call_impls(loc, rest_subctx, rest, inputs, func_call_str)
+    }
+
+    /// Applies the summary of an already analyzed function in place of executing it: the conditions
+    /// it puts on the arguments and storage are required, the storage variables it writes are updated
+    /// and its return values are pushed
+    fn apply_summary(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        func_node: FunctionNode,
+        summary: FunctionSummary,
+    ) -> Result<(), ExprErr> {
+        ctx.add_summarized_call(loc, func_node, self)
+            .into_expr_err(loc)?;
+        for (lhs, op, rhs) in summary.requires {
+            let Some((op, rhs_op, recursion_ops)) = op.require_parts() else {
+                continue;
+            };
+            let new_lhs = self.advance_var_in_ctx(lhs.latest_version(self), loc, ctx)?;
+            let new_rhs = self.advance_var_in_ctx(rhs.latest_version(self), loc, ctx)?;
+            self.require(new_lhs, new_rhs, ctx, loc, op, rhs_op, recursion_ops)?;
+            if let Some((_, kind)) = ctx.killed_loc(self).into_expr_err(loc)? {
+                return ctx
+                    .push_expr(ExprRet::CtxKilled(kind), self)
+                    .into_expr_err(loc);
+            }
+        }
+        summary.storage.into_iter().try_for_each(|(name, value)| {
+            let new_var =
+                if let Some(var) = ctx.var_by_name_or_recurse(self, &name).into_expr_err(loc)? {
+                    self.advance_var_in_ctx(var.latest_version(self), loc, ctx)?
+                } else {
+                    let mut new_var = value.underlying(self).into_expr_err(loc)?.clone();
+                    new_var.loc = Some(loc);
+                    new_var.name = name.clone();
+                    new_var.display_name = name;
+                    new_var.is_tmp = false;
+                    new_var.tmp_of = None;
+                    new_var.is_return = false;
+                    let new_var = ContextVarNode::from(self.add_node(Node::ContextVar(new_var)));
+                    ctx.add_var(new_var, self).into_expr_err(loc)?;
+                    self.add_edge(new_var, ctx, Edge::Context(ContextEdge::Variable));
+                    new_var
+                };
+            new_var
+                .set_range_min(self, Elem::from(value))
+                .into_expr_err(loc)?;
+            new_var
+                .set_range_max(self, Elem::from(value))
+                .into_expr_err(loc)?;
+            self.record_storage_write(loc, ctx, new_var)
+        })?;
+
+        let fn_name = func_node.name(self).into_expr_err(loc)?;
+        let rets = summary
+            .rets
+            .into_iter()
+            .enumerate()
+            .map(|(i, ret)| {
+                let tmp_ret = ret.as_tmp(loc, ctx, self).into_expr_err(loc)?;
+                tmp_ret.underlying_mut(self).into_expr_err(loc)?.is_return = true;
+                tmp_ret
+                    .underlying_mut(self)
+                    .into_expr_err(loc)?
+                    .display_name = format!("{fn_name}.{i}");
+                ctx.add_var(tmp_ret, self).into_expr_err(loc)?;
+                self.add_edge(tmp_ret, ctx, Edge::Context(ContextEdge::Variable));
+                Ok(ExprRet::Single(tmp_ret.into()))
+            })
+            .collect::<Result<_, ExprErr>>()?;
+        ctx.push_expr(ExprRet::Multi(rets), self).into_expr_err(loc)
+    }
+
     /// Actually executes the function
     #[tracing::instrument(level = "trace", skip_all)]
     fn execute_call_inner(
@@ -611,6 +924,25 @@ pub trait FuncCaller:
         loc: Loc,
         caller_ctx: ContextNode,
         callee_ctx: ContextNode,
+    ) -> Result<(), ExprErr> {
+        // a modifier calls the function it wraps one level shallower than itself, so the calls the function makes
+        // return to the function's depth rather than the modifier's
+        let min_depth = caller_ctx
+            .underlying(self)
+            .into_expr_err(loc)?
+            .depth
+            .min(callee_ctx.underlying(self).into_expr_err(loc)?.depth);
+        self.ctx_rets_from_depth(loc, caller_ctx, callee_ctx, min_depth)
+    }
+
+    /// Returns from the leaves of a call to the caller, following the calls the callee makes down to the
+    /// contexts at `min_depth` or deeper
+    fn ctx_rets_from_depth(
+        &mut self,
+        loc: Loc,
+        caller_ctx: ContextNode,
+        callee_ctx: ContextNode,
+        min_depth: usize,
     ) -> Result<(), ExprErr> {
         tracing::trace!(
             "Handling function call return for: {}, {}, depth: {:?}, {:?}",
@@ -621,16 +953,15 @@ pub trait FuncCaller:
         );
         match callee_ctx.underlying(self).into_expr_err(loc)?.child {
             Some(CallFork::Fork(w1, w2)) => {
-                self.ctx_rets(loc, caller_ctx, w1)?;
-                self.ctx_rets(loc, caller_ctx, w2)?;
+                self.ctx_rets_from_depth(loc, caller_ctx, w1, min_depth)?;
+                self.ctx_rets_from_depth(loc, caller_ctx, w2, min_depth)?;
                 Ok(())
             }
             Some(CallFork::Call(c))
-                if c.underlying(self).into_expr_err(loc)?.depth
-                    >= caller_ctx.underlying(self).into_expr_err(loc)?.depth =>
+                if c.underlying(self).into_expr_err(loc)?.depth >= min_depth =>
             {
                 // follow rabbit hole
-                self.ctx_rets(loc, caller_ctx, c)?;
+                self.ctx_rets_from_depth(loc, caller_ctx, c, min_depth)?;
                 Ok(())
             }
             _ => {
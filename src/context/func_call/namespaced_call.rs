@@ -7,7 +7,7 @@ use crate::context::{
 use shared::nodes::BuiltInNode;
 use shared::{
     analyzer::{AnalyzerLike, GraphLike},
-    context::{ContextNode, ContextVarNode, ExprRet},
+    context::{ContextNode, ContextSink, ContextVarNode, ExprRet, SinkKind},
     nodes::FunctionNode,
     Node, NodeIdx,
 };
@@ -225,6 +225,7 @@ pub trait NameSpaceFuncCaller:
                 let mut inputs = inputs.as_vec();
                 if let Node::ContextVar(_) = analyzer.node(member) { inputs.insert(0, ExprRet::Single(member)) }
                 let inputs = ExprRet::Multi(inputs);
+                analyzer.record_member_sinks(ctx, loc, &ident.name, &inputs)?;
 
                 let as_input_str = inputs.try_as_func_input_str(analyzer);
 
@@ -369,4 +370,32 @@ pub trait NameSpaceFuncCaller:
             }
         })
     }
+
+    /// Records the values flowing into a builtin member function of an address that a caller shouldn't control:
+    /// the target of a `delegatecall` and the amount of a `transfer` or `send`. The inputs lead with the member
+    fn record_member_sinks(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        func_name: &str,
+        inputs: &ExprRet,
+    ) -> Result<(), ExprErr> {
+        let inputs = inputs.as_flat_vec();
+        let (kind, input) = match func_name {
+            "delegatecall" => (SinkKind::DelegatecallTarget, inputs.first()),
+            "transfer" | "send" => (SinkKind::TransferAmount, inputs.get(1)),
+            _ => return Ok(()),
+        };
+        if let Some(input) = input {
+            if let Node::ContextVar(_) = self.node(*input) {
+                let sink = ContextSink {
+                    loc,
+                    kind,
+                    var: ContextVarNode::from(*input),
+                };
+                ctx.add_sink(sink, self).into_expr_err(loc)?;
+            }
+        }
+        Ok(())
+    }
 }
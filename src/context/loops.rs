@@ -1,4 +1,6 @@
+use crate::context::exprs::CondOp;
 use crate::context::exprs::IntoExprErr;
+use crate::context::exprs::Variable;
 use crate::ExprErr;
 use solang_parser::pt::Loc;
 use solang_parser::pt::Statement;
@@ -6,8 +8,11 @@ use solang_parser::pt::Statement;
 use crate::context::ContextBuilder;
 use shared::analyzer::GraphLike;
 use shared::context::*;
-use shared::{analyzer::AnalyzerLike, Node};
-use solang_parser::pt::Expression;
+use shared::range::elem::RangeElem;
+use shared::{analyzer::AnalyzerLike, Edge, Node, NodeIdx};
+use solang_parser::pt::{Expression, Identifier};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 
 impl<T> Looper for T where T: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized + GraphLike {}
 pub trait Looper: GraphLike + AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
@@ -17,64 +22,36 @@ pub trait Looper: GraphLike + AnalyzerLike<Expr = Expression, ExprErr = ExprErr>
         loc: Loc,
         ctx: ContextNode,
         maybe_init: &Option<Box<Statement>>,
-        _maybe_limiter: &Option<Box<Expression>>,
-        _maybe_post: &Option<Box<Statement>>,
+        maybe_limiter: &Option<Box<Expression>>,
+        maybe_post: &Option<Box<Statement>>,
         maybe_body: &Option<Box<Statement>>,
     ) -> Result<(), ExprErr> {
-        // TODO: improve this
         if let Some(initer) = maybe_init {
             self.parse_ctx_statement(initer, false, Some(ctx));
         }
 
-        if let Some(body) = maybe_body {
-            self.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
-                analyzer.reset_vars(loc, ctx, body)
-            })
-        } else {
-            Ok(())
-        }
-    }
-
-    fn reset_vars(&mut self, loc: Loc, ctx: ContextNode, body: &Statement) -> Result<(), ExprErr> {
-        let og_ctx = ctx;
-        let sctx = Context::new_subctx(ctx, None, loc, None, None, false, self, None)
-            .into_expr_err(loc)?;
-        let subctx = ContextNode::from(self.add_node(Node::Context(sctx)));
-        ctx.set_child_call(subctx, self).into_expr_err(loc)?;
-        self.parse_ctx_statement(body, false, Some(subctx));
-        self.apply_to_edges(subctx, loc, &|analyzer, ctx, loc| {
-            let vars = subctx.local_vars(analyzer).clone();
-            vars.iter().for_each(|(name, var)| {
-                // widen to max range
-                if let Some(inheritor_var) = ctx.var_by_name(analyzer, name) {
-                    let inheritor_var = inheritor_var.latest_version(analyzer);
-                    if let Some(r) = var
-                        .underlying(analyzer)
-                        .unwrap()
-                        .ty
-                        .default_range(analyzer)
-                        .unwrap()
-                    {
-                        let new_inheritor_var = analyzer
-                            .advance_var_in_ctx(inheritor_var, loc, ctx)
-                            .unwrap();
-                        let res = new_inheritor_var
-                            .set_range_min(analyzer, r.min)
-                            .into_expr_err(loc);
-                        let _ = analyzer.add_if_err(res);
-                        let res = new_inheritor_var
-                            .set_range_max(analyzer, r.max)
-                            .into_expr_err(loc);
-                        let _ = analyzer.add_if_err(res);
-                    }
+        self.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
+            if let Some(limiter) = maybe_limiter {
+                if analyzer.unroll_loop(
+                    loc,
+                    ctx,
+                    limiter,
+                    maybe_post.as_deref(),
+                    maybe_body.as_deref(),
+                    true,
+                )? {
+                    return Ok(());
                 }
-            });
+            }
 
-            let sctx =
-                Context::new_subctx(ctx, Some(og_ctx), loc, None, None, false, analyzer, None)
-                    .into_expr_err(loc)?;
-            let sctx = ContextNode::from(analyzer.add_node(Node::Context(sctx)));
-            ctx.set_child_call(sctx, analyzer).into_expr_err(loc)
+            analyzer.widen_loop(
+                loc,
+                ctx,
+                maybe_limiter.as_deref(),
+                maybe_post.as_deref(),
+                maybe_body.as_deref(),
+                true,
+            )
         })
     }
 
@@ -82,12 +59,449 @@ pub trait Looper: GraphLike + AnalyzerLike<Expr = Expression, ExprErr = ExprErr>
         &mut self,
         loc: Loc,
         ctx: ContextNode,
-        _limiter: &Expression,
+        limiter: &Expression,
         body: &Statement,
     ) -> Result<(), ExprErr> {
-        // TODO: improve this
         self.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
-            analyzer.reset_vars(loc, ctx, body)
+            if analyzer.unroll_loop(loc, ctx, limiter, None, Some(body), true)? {
+                Ok(())
+            } else {
+                analyzer.widen_loop(loc, ctx, Some(limiter), None, Some(body), true)
+            }
         })
     }
+
+    fn do_while_loop(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        limiter: &Expression,
+        body: &Statement,
+    ) -> Result<(), ExprErr> {
+        self.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
+            if analyzer.unroll_loop(loc, ctx, limiter, None, Some(body), false)? {
+                Ok(())
+            } else {
+                analyzer.widen_loop(loc, ctx, Some(limiter), None, Some(body), false)
+            }
+        })
+    }
+
+    /// Unrolls a loop, checking the limiter before each iteration (except the first of a `do while` loop).
+    ///
+    /// The iterations are evaluated in a subcontext of `ctx`. If the limiter doesn't decide whether an
+    /// iteration runs, e.g. because the counter starts from a symbolic value, or the loop may run for more
+    /// than [`max_loop_unroll`](AnalyzerLike::max_loop_unroll) iterations, the subcontext is discarded and
+    /// `false` is returned so the caller can fall back to widening
+    fn unroll_loop(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        limiter: &Expression,
+        maybe_post: Option<&Statement>,
+        maybe_body: Option<&Statement>,
+        check_first: bool,
+    ) -> Result<bool, ExprErr> {
+        let lctx = Context::new_subctx(ctx, None, loc, Some("loop"), None, false, self, None)
+            .into_expr_err(loc)?;
+        let loop_ctx = ContextNode::from(self.add_node(Node::Context(lctx)));
+        ctx.set_child_call(loop_ctx, self).into_expr_err(loc)?;
+        self.add_edge(
+            NodeIdx::from(loop_ctx.0),
+            ctx,
+            Edge::Context(ContextEdge::Subcontext),
+        );
+
+        let max_iters = self.max_loop_unroll();
+        let mut iter_ctxs = vec![loop_ctx];
+        for i in 0..=max_iters {
+            let mut next_iter_ctxs = vec![];
+            for iter_ctx in iter_ctxs {
+                let body_ctx = if i == 0 && !check_first {
+                    iter_ctx
+                } else if let Some(body_ctx) = self.loop_condition(loc, iter_ctx, limiter)? {
+                    body_ctx
+                } else {
+                    continue;
+                };
+
+                if body_ctx != iter_ctx || i == max_iters {
+                    // the loop may or may not run another iteration here, or may run for more
+                    // iterations than we are willing to unroll
+                    ctx.detach_child(self).into_expr_err(loc)?;
+                    return Ok(false);
+                }
+
+                next_iter_ctxs
+                    .extend(self.evaluate_iteration(loc, body_ctx, maybe_post, maybe_body)?);
+            }
+
+            if next_iter_ctxs.is_empty() {
+                return Ok(true);
+            }
+            iter_ctxs = next_iter_ctxs;
+        }
+        Ok(true)
+    }
+
+    /// Forks a context on a loop's limiter, like an `if` statement. Returns the context to evaluate
+    /// the next iteration in, or `None` if the loop can't continue. The context the loop exits in
+    /// is left as a live edge so that statements after the loop are evaluated in it
+    fn loop_condition(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        limiter: &Expression,
+    ) -> Result<Option<ContextNode>, ExprErr> {
+        let cctx = Context::new_subctx(ctx, None, loc, Some("loop"), None, false, self, None)
+            .into_expr_err(loc)?;
+        let continue_subctx = ContextNode::from(self.add_node(Node::Context(cctx)));
+        let ectx = Context::new_subctx(ctx, None, loc, Some("loop exit"), None, false, self, None)
+            .into_expr_err(loc)?;
+        let exit_subctx = ContextNode::from(self.add_node(Node::Context(ectx)));
+        ctx.set_child_fork(continue_subctx, exit_subctx, self)
+            .into_expr_err(loc)?;
+        let ctx_fork = self.add_node(Node::ContextFork);
+        self.add_edge(ctx_fork, ctx, Edge::Context(ContextEdge::ContextFork));
+        self.add_edge(
+            NodeIdx::from(continue_subctx.0),
+            ctx_fork,
+            Edge::Context(ContextEdge::Subcontext),
+        );
+        self.add_edge(
+            NodeIdx::from(exit_subctx.0),
+            ctx_fork,
+            Edge::Context(ContextEdge::Subcontext),
+        );
+
+        self.true_fork_if_cvar(limiter.clone(), continue_subctx)?;
+        let continue_killed = continue_subctx.is_killed(self).into_expr_err(loc)?;
+        self.false_fork_if_cvar(limiter.clone(), exit_subctx)?;
+        let exit_killed = exit_subctx.is_killed(self).into_expr_err(loc)?;
+
+        match (continue_killed, exit_killed) {
+            (true, true) => {
+                ctx.delete_child(self).into_expr_err(loc)?;
+                Ok(None)
+            }
+            (true, false) => {
+                // the loop is guaranteed to exit here, no need for the fork
+                ctx.delete_child(self).into_expr_err(loc)?;
+                self.false_fork_if_cvar(limiter.clone(), ctx)?;
+                Ok(None)
+            }
+            (false, true) => {
+                // the loop is guaranteed to continue here, no need for the fork
+                ctx.delete_child(self).into_expr_err(loc)?;
+                self.true_fork_if_cvar(limiter.clone(), ctx)?;
+                Ok(Some(ctx))
+            }
+            (false, false) => Ok(Some(continue_subctx)),
+        }
+    }
+
+    /// The contexts an iteration of a loop ended in that didn't revert or return, including those
+    /// that hit a `break` or `continue`
+    fn iteration_ends(&self, loc: Loc, ctx: ContextNode) -> Result<Vec<ContextNode>, ExprErr> {
+        let mut ends = ctx.all_edges(self).into_expr_err(loc)?;
+        if ends.is_empty() {
+            ends.push(ctx);
+        }
+        let mut live_ends = vec![];
+        for end in ends {
+            let underlying = end.underlying(self).into_expr_err(loc)?;
+            if underlying.killed.is_none() && underlying.ret.is_empty() {
+                live_ends.push(end);
+            }
+        }
+        Ok(live_ends)
+    }
+
+    /// Evaluates a loop that couldn't be unrolled. The variables an iteration changes are widened
+    /// until a fixpoint is reached, then a single iteration is evaluated from the widened state.
+    /// The loop exits with the limiter negated, so that after `while (i < n)` we know `i >= n`.
+    ///
+    /// Paths at the end of the evaluated iteration are merged into the loop's exit, see
+    /// [`exit_widened_iteration`](Looper::exit_widened_iteration)
+    fn widen_loop(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        maybe_limiter: Option<&Expression>,
+        maybe_post: Option<&Statement>,
+        maybe_body: Option<&Statement>,
+        check_first: bool,
+    ) -> Result<(), ExprErr> {
+        let mut loop_vars = self.loop_vars(loc, ctx)?;
+        let mut widenings: BTreeMap<String, (bool, bool)> = BTreeMap::new();
+        loop {
+            // evaluate an iteration in a throwaway subcontext to see which variables it changes
+            let pctx = Context::new_subctx(ctx, None, loc, Some("loop"), None, false, self, None)
+                .into_expr_err(loc)?;
+            let probe_ctx = ContextNode::from(self.add_node(Node::Context(pctx)));
+            ctx.set_child_call(probe_ctx, self).into_expr_err(loc)?;
+            self.add_edge(
+                NodeIdx::from(probe_ctx.0),
+                ctx,
+                Edge::Context(ContextEdge::Subcontext),
+            );
+            self.widen_vars(loc, probe_ctx, &widenings)?;
+            let changed = self.probe_iteration(
+                loc,
+                probe_ctx,
+                &loop_vars,
+                maybe_limiter,
+                maybe_post,
+                maybe_body,
+                check_first,
+            )?;
+            let first_accessed = self.storage_first_accessed(loc, probe_ctx, &loop_vars)?;
+            ctx.detach_child(self).into_expr_err(loc)?;
+
+            // a storage variable the loop accesses before anything else in the function does isn't in
+            // scope yet, so it is brought into scope for the next iteration to be compared against
+            let mut reached_fixpoint = first_accessed.is_empty();
+            for name in first_accessed {
+                let ident = Identifier { loc, name };
+                self.variable(&ident, ctx, None)?;
+                ctx.pop_expr_latest(loc, self).into_expr_err(loc)?;
+                loop_vars.insert(ident.name);
+            }
+            changed.into_iter().for_each(|(name, min, max)| {
+                let widening = widenings.entry(name).or_insert((false, false));
+                if (min && !widening.0) || (max && !widening.1) {
+                    reached_fixpoint = false;
+                }
+                widening.0 |= min;
+                widening.1 |= max;
+            });
+            if reached_fixpoint {
+                break;
+            }
+        }
+
+        self.widen_vars(loc, ctx, &widenings)?;
+        let body_ctx = match (check_first, maybe_limiter) {
+            (true, Some(limiter)) => {
+                // the exit path is left as a live edge, narrowed by the negated limiter
+                let Some(body_ctx) = self.loop_condition(loc, ctx, limiter)? else {
+                    return Ok(());
+                };
+                body_ctx
+            }
+            _ => ctx,
+        };
+
+        for iter_end in self.evaluate_iteration(loc, body_ctx, maybe_post, maybe_body)? {
+            self.exit_widened_iteration(loc, iter_end, maybe_limiter)?;
+        }
+        Ok(())
+    }
+
+    /// Merges a path at the end of an iteration evaluated from the widened state into the loop's
+    /// exit. The path's variables are within the widened state, so running another iteration from
+    /// it is already covered by the evaluated iteration; what is left is exiting the loop, which
+    /// the path continues to do in a subcontext narrowed by the negated limiter. A path that can't
+    /// exit, because the limiter holds or the loop has none and is only exited by a `break`, never
+    /// leaves the loop from here and is killed as unreachable
+    fn exit_widened_iteration(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        maybe_limiter: Option<&Expression>,
+    ) -> Result<(), ExprErr> {
+        let Some(limiter) = maybe_limiter else {
+            return ctx
+                .kill(self, loc, KilledKind::Unreachable)
+                .into_expr_err(loc);
+        };
+        let ectx = Context::new_subctx(ctx, None, loc, Some("loop exit"), None, false, self, None)
+            .into_expr_err(loc)?;
+        let exit_ctx = ContextNode::from(self.add_node(Node::Context(ectx)));
+        ctx.set_child_call(exit_ctx, self).into_expr_err(loc)?;
+        self.add_edge(
+            NodeIdx::from(exit_ctx.0),
+            ctx,
+            Edge::Context(ContextEdge::Subcontext),
+        );
+        self.false_fork_if_cvar(limiter.clone(), exit_ctx)?;
+        if exit_ctx.is_killed(self).into_expr_err(loc)? {
+            ctx.detach_child(self).into_expr_err(loc)?;
+            ctx.kill(self, loc, KilledKind::Unreachable)
+                .into_expr_err(loc)?;
+        }
+        Ok(())
+    }
+
+    /// Evaluates an iteration of a loop from a widened state and returns the variables whose ranges
+    /// grew, alongside whether their minimum and maximum grew
+    #[allow(clippy::too_many_arguments)]
+    fn probe_iteration(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        loop_vars: &BTreeSet<String>,
+        maybe_limiter: Option<&Expression>,
+        maybe_post: Option<&Statement>,
+        maybe_body: Option<&Statement>,
+        check_first: bool,
+    ) -> Result<Vec<(String, bool, bool)>, ExprErr> {
+        let mut pre_vars = vec![];
+        for name in loop_vars {
+            if let Some(var) = ctx.var_by_name_or_recurse(self, name).into_expr_err(loc)? {
+                pre_vars.push((name, var.latest_version(self)));
+            }
+        }
+
+        let body_ctx = match (check_first, maybe_limiter) {
+            (true, Some(limiter)) => {
+                let Some(body_ctx) = self.loop_condition(loc, ctx, limiter)? else {
+                    return Ok(vec![]);
+                };
+                body_ctx
+            }
+            _ => ctx,
+        };
+
+        let mut changed = vec![];
+        for iter_end in self.evaluate_iteration(loc, body_ctx, maybe_post, maybe_body)? {
+            for (name, pre_var) in pre_vars.iter() {
+                let Some(var) = iter_end.var_by_name_or_recurse(self, name).into_expr_err(loc)? else {
+                    continue;
+                };
+                let var = var.latest_version(self);
+                if var == *pre_var {
+                    continue;
+                }
+
+                let (Some(pre_min), Some(min)) = (
+                    pre_var.evaled_range_min(self).into_expr_err(loc)?,
+                    var.evaled_range_min(self).into_expr_err(loc)?,
+                ) else {
+                    continue;
+                };
+                let (Some(pre_max), Some(max)) = (
+                    pre_var.evaled_range_max(self).into_expr_err(loc)?,
+                    var.evaled_range_max(self).into_expr_err(loc)?,
+                ) else {
+                    continue;
+                };
+                // if the ranges can't be compared, assume they grew
+                let min_grew = !matches!(
+                    min.range_ord(&pre_min),
+                    Some(Ordering::Greater) | Some(Ordering::Equal)
+                );
+                let max_grew = !matches!(
+                    max.range_ord(&pre_max),
+                    Some(Ordering::Less) | Some(Ordering::Equal)
+                );
+                if min_grew || max_grew {
+                    changed.push(((*name).clone(), min_grew, max_grew));
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Evaluates the body and post statement of a loop iteration, returning the contexts the
+    /// iteration ended in. Paths that hit a `break` exit the loop and are left as live edges
+    fn evaluate_iteration(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        maybe_post: Option<&Statement>,
+        maybe_body: Option<&Statement>,
+    ) -> Result<Vec<ContextNode>, ExprErr> {
+        if let Some(body) = maybe_body {
+            self.parse_ctx_statement(body, ctx.unchecked(self).into_expr_err(loc)?, Some(ctx));
+        }
+
+        let mut ends = vec![];
+        for iter_end in self.iteration_ends(loc, ctx)? {
+            if let Some(LoopControl::Break) = iter_end.take_loop_control(self).into_expr_err(loc)? {
+                continue;
+            }
+
+            if let Some(post) = maybe_post {
+                self.parse_ctx_statement(post, false, Some(iter_end));
+            }
+            ends.extend(self.iteration_ends(loc, iter_end)?);
+        }
+        Ok(ends)
+    }
+
+    /// The names of the variables in scope before a loop
+    fn loop_vars(&self, loc: Loc, ctx: ContextNode) -> Result<BTreeSet<String>, ExprErr> {
+        let fn_node = ctx.associated_fn(self).into_expr_err(loc)?;
+        let mut names = BTreeSet::new();
+        let mut curr = Some(ctx);
+        while let Some(ctx) = curr {
+            names.extend(ctx.vars(self).keys().cloned());
+            curr = ctx.ancestor_in_fn(self, fn_node).into_expr_err(loc)?;
+        }
+        Ok(names)
+    }
+
+    /// The names of the contract's storage variables an iteration evaluated in `ctx` accessed that
+    /// weren't in scope before the loop
+    fn storage_first_accessed(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        loop_vars: &BTreeSet<String>,
+    ) -> Result<BTreeSet<String>, ExprErr> {
+        let Some(contract) = ctx.maybe_associated_contract(self).into_expr_err(loc)? else {
+            return Ok(BTreeSet::new());
+        };
+        let mut storage_names = BTreeSet::new();
+        for slot in contract.storage_layout(self).into_expr_err(loc)? {
+            storage_names.insert(slot.var.name(self).into_expr_err(loc)?);
+        }
+
+        let mut ctxs = vec![ctx];
+        ctxs.extend(ctx.descendants(self).into_expr_err(loc)?);
+        let mut accessed = BTreeSet::new();
+        for ctx in ctxs {
+            for (name, var) in ctx.vars(self) {
+                if !loop_vars.contains(name)
+                    && storage_names.contains(name)
+                    && var.is_storage(self).into_expr_err(loc)?
+                {
+                    accessed.insert(name.clone());
+                }
+            }
+        }
+        Ok(accessed)
+    }
+
+    /// Widens the minimum and/or maximum of variables to the bounds of their types
+    fn widen_vars(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        widenings: &BTreeMap<String, (bool, bool)>,
+    ) -> Result<(), ExprErr> {
+        for (name, (widen_min, widen_max)) in widenings.iter() {
+            let Some(var) = ctx.var_by_name_or_recurse(self, name).into_expr_err(loc)? else {
+                continue;
+            };
+            let var = var.latest_version(self);
+            let Some(r) = var
+                .underlying(self)
+                .into_expr_err(loc)?
+                .ty
+                .default_range(self)
+                .into_expr_err(loc)? else {
+                continue;
+            };
+            let new_var = self.advance_var_in_ctx(var, loc, ctx)?;
+            if *widen_min {
+                new_var.set_range_min(self, r.min).into_expr_err(loc)?;
+            }
+            if *widen_max {
+                new_var.set_range_max(self, r.max).into_expr_err(loc)?;
+            }
+        }
+        Ok(())
+    }
 }
@@ -26,6 +26,15 @@ use func_call::*;
 pub mod loops;
 use loops::*;
 
+pub mod emit;
+use emit::*;
+
+pub mod revert;
+use revert::*;
+
+pub mod try_catch;
+use try_catch::*;
+
 pub mod exprs;
 use exprs::*;
 
@@ -401,18 +410,40 @@ pub trait ContextBuilder:
                     let res = self.apply_to_edges(
                         ContextNode::from(parent.into()),
                         *loc,
-                        &|analyzer, ctx, loc| analyzer.while_loop(loc, ctx, while_expr, while_stmt),
+                        &|analyzer, ctx, loc| {
+                            analyzer.do_while_loop(loc, ctx, while_expr, while_stmt)
+                        },
                     );
                     let _ = self.widen_if_limit_hit(parent.into().into(), res);
                 }
             }
-            Continue(_loc) => {
+            Continue(loc) => {
                 tracing::trace!("parsing continue");
-                // TODO: We cheat in loops by just widening so continues dont matter yet
+                if let Some(parent) = parent_ctx {
+                    let res = self.apply_to_edges(
+                        ContextNode::from(parent.into()),
+                        *loc,
+                        &|analyzer, ctx, loc| {
+                            ctx.set_loop_control(LoopControl::Continue, analyzer)
+                                .into_expr_err(loc)
+                        },
+                    );
+                    let _ = self.add_if_err(res);
+                }
             }
-            Break(_loc) => {
+            Break(loc) => {
                 tracing::trace!("parsing break");
-                // TODO: We cheat in loops by just widening so breaks dont matter yet
+                if let Some(parent) = parent_ctx {
+                    let res = self.apply_to_edges(
+                        ContextNode::from(parent.into()),
+                        *loc,
+                        &|analyzer, ctx, loc| {
+                            ctx.set_loop_control(LoopControl::Break, analyzer)
+                                .into_expr_err(loc)
+                        },
+                    );
+                    let _ = self.add_if_err(res);
+                }
             }
             Assembly {
                 loc,
@@ -426,7 +457,10 @@ pub trait ContextBuilder:
                         .expect("No context for variable definition?")
                         .into(),
                 );
-                let res = self.apply_to_edges(ctx, *loc, &|analyzer, ctx, _loc| {
+                let res = self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
+                    // solidity writes memory outside of assembly that isn't tracked, so each block starts
+                    // without knowing any words
+                    ctx.forget_memory(analyzer).into_expr_err(loc)?;
                     analyzer.parse_ctx_yul_statement(&YulStatement::Block(yul_block.clone()), ctx);
                     Ok(())
                 });
@@ -466,26 +500,62 @@ pub trait ContextBuilder:
                     }
                 }
             }
-            Revert(loc, _maybe_err_path, _exprs) => {
+            Revert(loc, maybe_err_path, exprs) => {
                 tracing::trace!("parsing revert");
                 if let Some(parent) = parent_ctx {
                     let parent = ContextNode::from(parent.into());
                     let res = self.apply_to_edges(parent, *loc, &|analyzer, ctx, loc| {
-                        let res = ctx
-                            .kill(analyzer, loc, KilledKind::Revert)
-                            .into_expr_err(loc);
-                        let _ = analyzer.add_if_err(res);
-                        Ok(())
+                        analyzer.revert(loc, ctx, maybe_err_path, exprs)
                     });
-                    let _ = self.add_if_err(res);
+                    if self.add_if_err(res).is_none() {
+                        // the revert happens even if its error couldn't be evaluated
+                        let res = parent
+                            .kill(self, *loc, KilledKind::Revert)
+                            .into_expr_err(*loc);
+                        let _ = self.add_if_err(res);
+                    }
                 }
             }
-            RevertNamedArgs(_loc, _maybe_err_path, _named_args) => {
+            RevertNamedArgs(loc, maybe_err_path, named_args) => {
                 tracing::trace!("parsing named revert");
-                todo!("revert named args")
+                if let Some(parent) = parent_ctx {
+                    let parent = ContextNode::from(parent.into());
+                    let res = self.apply_to_edges(parent, *loc, &|analyzer, ctx, loc| {
+                        analyzer.revert_named_args(loc, ctx, maybe_err_path, named_args)
+                    });
+                    if self.add_if_err(res).is_none() {
+                        let res = parent
+                            .kill(self, *loc, KilledKind::Revert)
+                            .into_expr_err(*loc);
+                        let _ = self.add_if_err(res);
+                    }
+                }
+            }
+            Emit(loc, emit_expr) => {
+                tracing::trace!("parsing emit, {emit_expr:?}");
+                if let Some(parent) = parent_ctx {
+                    let res = self.apply_to_edges(
+                        ContextNode::from(parent.into()),
+                        *loc,
+                        &|analyzer, ctx, loc| analyzer.emit_event(loc, ctx, emit_expr),
+                    );
+                    // emitting an event doesn't affect control flow, so don't kill the context
+                    let _ = self.add_if_err(res);
+                }
+            }
+            Try(loc, try_expr, maybe_returns, clauses) => {
+                tracing::trace!("parsing try, {try_expr:?}");
+                if let Some(parent) = parent_ctx {
+                    let res = self.apply_to_edges(
+                        ContextNode::from(parent.into()),
+                        *loc,
+                        &|analyzer, ctx, loc| {
+                            analyzer.try_catch(loc, ctx, try_expr, maybe_returns, clauses)
+                        },
+                    );
+                    let _ = self.widen_if_limit_hit(parent.into().into(), res);
+                }
             }
-            Emit(_loc, _emit_expr) => {}
-            Try(_loc, _try_expr, _maybe_returns, _clauses) => {}
             Error(_loc) => {}
         }
     }
@@ -710,7 +780,7 @@ pub trait ContextBuilder:
             RationalNumberLiteral(loc, integer, fraction, exp, unit) => {
                 self.rational_number_literal(ctx, *loc, integer, fraction, exp, unit)
             }
-            Negate(_loc, expr) => match &**expr {
+            Negate(neg_loc, expr) => match &**expr {
                 NumberLiteral(loc, int, exp, _unit) => {
                     self.number_literal(ctx, *loc, int, exp, true)
                 }
@@ -730,6 +800,7 @@ pub trait ContextBuilder:
                         // Solidity is dumb and used to allow negation of unsigned integers.
                         // That means we have to cast this as a int256.
                         let var = rhs_paths.expect_single().into_expr_err(loc)?;
+                        analyzer.record_negation(ctx, *neg_loc, ContextVarNode::from(var).latest_version(analyzer))?;
 
                         let zero = analyzer.add_node(Node::Concrete(Concrete::from(I256::from(0i32))));
                         let zero = ContextVar::new_from_concrete(
@@ -909,31 +980,17 @@ pub trait ContextBuilder:
             NamedFunctionCall(loc, func_expr, input_args) => {
                 self.named_fn_call_expr(ctx, loc, func_expr, input_args)
             }
-            FunctionCall(loc, func_expr, input_exprs) => {
-                let updated_func_expr = match **func_expr {
-                    FunctionCallBlock(_loc, ref inner_func_expr, ref call_block) => {
-                        // we dont currently handle the `{value: .. gas: ..}` msg updating
-                        self.add_expr_err(ExprErr::FunctionCallBlockTodo(call_block.loc(), "Function call block is currently unsupported. Relevant changes on `msg` will not take affect".to_string()));
-                        inner_func_expr.clone()
-                    }
-                    _ => func_expr.clone(),
-                };
-
-                self.fn_call_expr(ctx, loc, &updated_func_expr, input_exprs)
-            }
+            FunctionCall(loc, func_expr, input_exprs) => match **func_expr {
+                FunctionCallBlock(_loc, ref inner_func_expr, ref call_block) => {
+                    self.fn_call_block_expr(ctx, loc, inner_func_expr, call_block, input_exprs)
+                }
+                _ => self.fn_call_expr(ctx, loc, func_expr, input_exprs),
+            },
             // member
             New(_loc, expr) => self.parse_ctx_expr(expr, ctx),
             This(loc) => {
-                let var = ContextVar::new_from_contract(
-                    *loc,
-                    ctx.associated_contract(self).into_expr_err(*loc)?,
-                    self,
-                )
-                .into_expr_err(*loc)?;
-                let cvar = self.add_node(Node::ContextVar(var));
-                ctx.add_var(cvar.into(), self).into_expr_err(*loc)?;
-                self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
-                ctx.push_expr(ExprRet::Single(cvar), self)
+                let this = self.this_var(*loc, ctx)?;
+                ctx.push_expr(ExprRet::Single(this.into()), self)
                     .into_expr_err(*loc)?;
                 Ok(())
             }
@@ -955,6 +1012,8 @@ pub trait ContextBuilder:
                         ExprRet::Single(cvar) | ExprRet::SingleLiteral(cvar) => {
                             let mut new_var =
                                 analyzer.advance_var_in_ctx(cvar.into(), *loc, ctx).unwrap();
+                            let res = analyzer.record_storage_write(*loc, ctx, new_var);
+                            let _ = analyzer.add_if_err(res);
                             let res = new_var.sol_delete_range(analyzer).into_expr_err(*loc);
                             let _ = analyzer.add_if_err(res);
                         }
@@ -1089,12 +1148,30 @@ pub trait ContextBuilder:
             }
             ExprRet::Single(var) => {
                 let cvar = ContextVarNode::from(*var);
+                let unchecked = ctx.unchecked(self).into_expr_err(loc)?;
+                ctx.add_arith(
+                    ContextArith {
+                        loc,
+                        op: if increment {
+                            RangeOp::Add(unchecked)
+                        } else {
+                            RangeOp::Sub(unchecked)
+                        },
+                        unchecked,
+                        lhs: cvar,
+                        rhs: None,
+                        rhs_range: None,
+                    },
+                    self,
+                )
+                .into_expr_err(loc)?;
                 let elem = Elem::from(cvar);
                 let one = Elem::from(Concrete::from(U256::from(1))).cast(elem.clone());
                 // if let Some(r) = cvar.range(self).into_expr_err(loc)? {
                 if increment {
                     if pre {
                         let new_cvar = self.advance_var_in_ctx(cvar, loc, ctx)?;
+                        self.record_storage_write(loc, ctx, new_cvar)?;
                         let res = new_cvar
                             .set_range_min(self, elem.clone() + one.clone())
                             .into_expr_err(loc);
@@ -1107,6 +1184,7 @@ pub trait ContextBuilder:
                     } else {
                         let dup = cvar.as_tmp(loc, ctx, self).into_expr_err(loc)?;
                         let new_cvar = self.advance_var_in_ctx(cvar, loc, ctx)?;
+                        self.record_storage_write(loc, ctx, new_cvar)?;
                         let res = new_cvar
                             .set_range_min(self, elem.clone() + one.clone())
                             .into_expr_err(loc);
@@ -1120,6 +1198,7 @@ pub trait ContextBuilder:
                     }
                 } else if pre {
                     let new_cvar = self.advance_var_in_ctx(cvar, loc, ctx)?;
+                    self.record_storage_write(loc, ctx, new_cvar)?;
                     let res = new_cvar
                         .set_range_min(self, elem.clone() - one.clone())
                         .into_expr_err(loc);
@@ -1133,6 +1212,7 @@ pub trait ContextBuilder:
                 } else {
                     let dup = cvar.as_tmp(loc, ctx, self).into_expr_err(loc)?;
                     let new_cvar = self.advance_var_in_ctx(cvar, loc, ctx)?;
+                    self.record_storage_write(loc, ctx, new_cvar)?;
                     let res = new_cvar
                         .set_range_min(self, elem.clone() - one.clone())
                         .into_expr_err(loc);
@@ -1263,6 +1343,7 @@ pub trait ContextBuilder:
         );
 
         let new_lhs = self.advance_var_in_ctx(lhs_cvar.latest_version(self), loc, ctx)?;
+        self.record_storage_write(loc, ctx, new_lhs)?;
         if rhs_cvar.underlying(self).into_expr_err(loc)?.is_return {
             if let Some(rhs_ctx) = rhs_cvar.maybe_ctx(self) {
                 self.add_edge(
@@ -1364,6 +1445,21 @@ pub trait ContextBuilder:
         Ok(ExprRet::Single(new_lhs.into()))
     }
 
+    /// Records a new version of a variable as a write of storage in the context's effects, if the
+    /// variable is in storage
+    fn record_storage_write(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        var: ContextVarNode,
+    ) -> Result<(), ExprErr> {
+        if var.is_storage(self).into_expr_err(loc)? {
+            ctx.add_effect(ContextEffect::StorageWrite { loc, var }, self)
+                .into_expr_err(loc)?;
+        }
+        Ok(())
+    }
+
     #[tracing::instrument(level = "trace", skip_all, fields(ctx = %ctx.path(self)))]
     fn advance_var_in_ctx(
         &mut self,
@@ -1,22 +1,61 @@
 use crate::analyzers::{VarBoundAnalyzer, *};
+use crate::context::exprs::IntoExprErr;
+use crate::ExprErr;
 use shared::{
     analyzer::*,
-    context::ContextNode,
-    nodes::{TypeNode, VarType},
-    NodeIdx,
+    context::{ContextEffect, ContextNode},
+    nodes::{ContractNode, TypeNode, VarType},
+    Edge, NodeIdx,
 };
 
-use ariadne::{Cache, Color, Config, Label, Report, ReportKind};
-use std::collections::BTreeMap;
+use ariadne::{Cache, Color, Config, Label, Report, ReportKind, Span};
+use solang_parser::pt::{Expression, Loc};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A write of the queried storage variable and the conditions under which it persists
+#[derive(Debug, Clone)]
+pub struct StorageWriteAccess {
+    /// The name of the function a transaction enters the contract through to make the write
+    pub func_name: String,
+    /// The name of the written variable: the queried one, or a field or element of it
+    pub var_name: String,
+    /// The write's location string span
+    pub loc: LocStrSpan,
+    /// The conditions each successful path through the write requires, e.g. `(msg.sender == owner)`. A path
+    /// without conditions lets anyone make the write
+    pub conditions: Vec<Vec<String>>,
+}
+
+impl StorageWriteAccess {
+    /// Returns whether some path makes the write without any condition
+    pub fn is_unconditional(&self) -> bool {
+        self.conditions.iter().any(|path| path.is_empty())
+    }
+
+    fn conditions_str(&self) -> String {
+        if self.is_unconditional() {
+            "without any condition".to_string()
+        } else {
+            let paths = self
+                .conditions
+                .iter()
+                .map(|path| path.join(" ∧ "))
+                .collect::<Vec<_>>();
+            format!("when {}", paths.join(" or when "))
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct AccessStorageWriteReport {
     pub msgs: Vec<String>,
+    /// The writes of the queried storage variable
+    pub writes: Vec<StorageWriteAccess>,
 }
 
 impl AccessStorageWriteReport {
-    pub fn new(msgs: Vec<String>) -> Self {
-        Self { msgs }
+    pub fn new(msgs: Vec<String>, writes: Vec<StorageWriteAccess>) -> Self {
+        Self { msgs, writes }
     }
 }
 
@@ -29,11 +68,33 @@ impl ReportDisplay for AccessStorageWriteReport {
     }
 
     fn labels(&self, _analyzer: &impl GraphLike) -> Vec<Label<LocStrSpan>> {
-        vec![]
+        self.writes
+            .iter()
+            .enumerate()
+            .map(|(i, write)| {
+                Label::new(write.loc.clone())
+                    .with_message(format!(
+                        "{} writes {} {}",
+                        write.func_name,
+                        write.var_name,
+                        write.conditions_str()
+                    ))
+                    .with_color(if write.is_unconditional() {
+                        Color::Red
+                    } else {
+                        Color::Yellow
+                    })
+                    .with_order(i as i32)
+            })
+            .collect()
     }
 
     fn reports(&self, analyzer: &impl GraphLike) -> Vec<Report<LocStrSpan>> {
-        let report = Report::build(self.report_kind(), "".to_string(), 0)
+        let (source, start) = match self.writes.first() {
+            Some(write) => (write.loc.source().clone(), write.loc.start()),
+            None => ("".to_string(), 0),
+        };
+        let mut report = Report::build(self.report_kind(), source, start)
             .with_message(self.msg(analyzer))
             .with_config(
                 Config::default()
@@ -41,6 +102,7 @@ impl ReportDisplay for AccessStorageWriteReport {
                     .with_underlines(true)
                     .with_tab_width(4),
             );
+        report.add_labels(self.labels(analyzer));
         vec![report.finish()]
     }
 
@@ -59,18 +121,122 @@ impl ReportDisplay for AccessStorageWriteReport {
     }
 }
 
-impl<T> AccessStorageWriteQuery for T where T: VarBoundAnalyzer + Search + AnalyzerLike + Sized {}
-pub trait AccessStorageWriteQuery: VarBoundAnalyzer + Search + AnalyzerLike + Sized {
-    #[allow(clippy::too_many_arguments)]
+impl<T> AccessStorageWriteQuery for T where
+    T: VarBoundAnalyzer + Search + AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized
+{
+}
+/// Answers which functions can write a storage variable, and under what conditions
+pub trait AccessStorageWriteQuery:
+    VarBoundAnalyzer + Search + AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized
+{
+    /// Finds the writes of a contract's storage variable, its fields and its elements on the successful paths of
+    /// the functions a transaction can enter the contract through, including the writes of the functions they
+    /// call. Each write comes with the conditions of the paths it persists on: the `require`s and branches
+    /// before or after it, e.g. a check of `msg.sender` against another storage variable.
+    ///
+    /// The contract is looked up in the source units of all the given entries
     fn access_query(
-        &self,
-        _entry: NodeIdx,
-        _file_mapping: &'_ BTreeMap<usize, String>,
-        _report_config: ReportConfig,
-        _contract_name: String,
-        _storage_var_name: String,
-    ) -> AccessStorageWriteReport {
-        todo!()
+        &mut self,
+        entries: &[NodeIdx],
+        file_mapping: &'_ BTreeMap<usize, String>,
+        contract_name: String,
+        storage_var_name: String,
+    ) -> Result<AccessStorageWriteReport, ExprErr> {
+        let Some(contract) = entries
+            .iter()
+            .flat_map(|entry| self.search_children(*entry, &Edge::Contract))
+            .map(ContractNode::from)
+            .find(|contract| matches!(contract.name(self), Ok(name) if name == contract_name))
+        else {
+            return Ok(AccessStorageWriteReport::new(
+                vec![format!("No contract named {contract_name}")],
+                vec![],
+            ));
+        };
+        let loc = contract.loc(self).into_expr_err(Loc::Implicit)?;
+        let constructor = contract.constructor(self);
+        let funcs = contract
+            .entry_functions(self)
+            .into_iter()
+            .filter(|func| Some(*func) != constructor)
+            .filter_map(|func| Some((func, func.maybe_body_ctx(self)?)))
+            .collect::<Vec<_>>();
+
+        let mut writes: BTreeMap<(String, Loc), (String, BTreeSet<Vec<String>>)> =
+            BTreeMap::default();
+        for (func, body_ctx) in funcs.iter() {
+            let func_name = func.name(self).into_expr_err(loc)?;
+            let mut names = self.recurse(*body_ctx, storage_var_name.clone());
+            names.push(storage_var_name.clone());
+            for leaf in body_ctx.successful_leaves(self).into_expr_err(loc)? {
+                let mut conditions = leaf
+                    .ctx_deps(self)
+                    .into_expr_err(loc)?
+                    .into_values()
+                    .map(|dep| dep.display_name(self))
+                    .collect::<Result<Vec<_>, _>>()
+                    .into_expr_err(loc)?;
+                conditions.sort();
+                conditions.dedup();
+
+                let mut lineage = leaf.parent_list(self).into_expr_err(loc)?;
+                lineage.reverse();
+                lineage.push(leaf);
+                for ctx in lineage {
+                    if ctx.in_external_call(self).into_expr_err(loc)? {
+                        continue;
+                    }
+                    for effect in ctx.effects(self).into_expr_err(loc)? {
+                        let ContextEffect::StorageWrite {
+                            loc: write_loc,
+                            var,
+                        } = effect
+                        else {
+                            continue;
+                        };
+                        let var_name = var.name(self).into_expr_err(loc)?;
+                        if !names.iter().any(|name| writes_to(&var_name, name)) {
+                            continue;
+                        }
+                        writes
+                            .entry((func_name.clone(), write_loc))
+                            .or_insert_with(|| (var_name, BTreeSet::default()))
+                            .1
+                            .insert(conditions.clone());
+                    }
+                }
+            }
+        }
+
+        let writes = writes
+            .into_iter()
+            .map(
+                |((func_name, write_loc), (var_name, conditions))| StorageWriteAccess {
+                    func_name,
+                    var_name,
+                    loc: LocStrSpan::new(file_mapping, write_loc),
+                    conditions: conditions.into_iter().collect(),
+                },
+            )
+            .collect::<Vec<_>>();
+        let msgs = if writes.is_empty() {
+            vec![format!(
+                "No function writes {contract_name}.{storage_var_name}"
+            )]
+        } else {
+            writes
+                .iter()
+                .map(|write| {
+                    format!(
+                        "{} writes {} {}",
+                        write.func_name,
+                        write.var_name,
+                        write.conditions_str()
+                    )
+                })
+                .collect()
+        };
+        Ok(AccessStorageWriteReport::new(msgs, writes))
     }
 
     fn recurse(&self, ctx: ContextNode, storage_var_name: String) -> Vec<String> {
@@ -91,3 +257,12 @@ pub trait AccessStorageWriteQuery: VarBoundAnalyzer + Search + AnalyzerLike + Si
         }
     }
 }
+
+/// Returns whether a write of the named variable writes the queried variable: it is the variable, or a field or
+/// element of it
+fn writes_to(written: &str, queried: &str) -> bool {
+    written == queried
+        || written
+            .strip_prefix(queried)
+            .is_some_and(|rest| rest.starts_with('.') || rest.starts_with('['))
+}
@@ -1,11 +1,16 @@
 use crate::analyzers::{VarBoundAnalyzer, *};
+use crate::context::exprs::IntoExprErr;
+use crate::ExprErr;
 use shared::{
     analyzer::*,
-    range::{range_string::ToRangeString, Range, SolcRange},
-    NodeIdx,
+    context::ContextEffect,
+    nodes::ContractNode,
+    range::{range_string::ToRangeString, Range, RangeEval, SolcRange},
+    Edge, NodeIdx,
 };
 
 use ariadne::{Cache, Color, Config, Fmt, Label, Report, ReportKind, Span};
+use solang_parser::pt::{Expression, Loc};
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
@@ -100,7 +105,14 @@ impl ReportDisplay for StorageRangeReport {
     }
 
     fn labels(&self, _analyzer: &impl GraphLike) -> Vec<Label<LocStrSpan>> {
-        vec![]
+        self.write_loc
+            .iter()
+            .map(|loc| {
+                Label::new(loc.clone())
+                    .with_message(format!("Last write of {}", self.analysis.var_name))
+                    .with_color(Color::Green)
+            })
+            .collect()
     }
 
     fn reports(&self, analyzer: &impl GraphLike) -> Vec<Report<LocStrSpan>> {
@@ -118,6 +130,7 @@ impl ReportDisplay for StorageRangeReport {
         );
 
         report.add_labels(self.analysis.labels(analyzer));
+        report.add_labels(self.labels(analyzer));
 
         let reports = vec![report.finish()];
         reports
@@ -138,19 +151,102 @@ impl ReportDisplay for StorageRangeReport {
     }
 }
 
-impl<T> StorageRangeQuery for T where T: VarBoundAnalyzer + Search + AnalyzerLike + Sized {}
-pub trait StorageRangeQuery: VarBoundAnalyzer + Search + AnalyzerLike + Sized {
+impl<T> StorageRangeQuery for T where
+    T: VarBoundAnalyzer + Search + AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized
+{
+}
+/// Answers which paths through a function can leave a storage variable with a target value
+pub trait StorageRangeQuery:
+    VarBoundAnalyzer + Search + AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized
+{
+    /// Finds the successful paths through a contract's function that leave a storage variable in a range
+    /// overlapping the target range. Each report carries the bounds of the variable along the path and the last
+    /// write of it on the path, if the path writes it.
+    ///
+    /// The contract is looked up in the source units of all the given entries
     #[allow(clippy::too_many_arguments)]
     fn func_query(
         &mut self,
-        _entry: NodeIdx,
-        _file_mapping: &'_ BTreeMap<usize, String>,
-        _report_config: ReportConfig,
-        _contract_name: String,
-        _func_name: String,
-        _storage_var_name: String,
-        _target: SolcRange,
-    ) -> Option<StorageRangeReport> {
-        todo!()
+        entries: &[NodeIdx],
+        file_mapping: &'_ BTreeMap<usize, String>,
+        report_config: ReportConfig,
+        contract_name: String,
+        func_name: String,
+        storage_var_name: String,
+        target: SolcRange,
+    ) -> Result<Vec<StorageRangeReport>, ExprErr> {
+        let Some(contract) = entries
+            .iter()
+            .flat_map(|entry| self.search_children(*entry, &Edge::Contract))
+            .map(ContractNode::from)
+            .find(|contract| matches!(contract.name(self), Ok(name) if name == contract_name))
+        else {
+            return Err(ExprErr::ContractFunctionNotFound(
+                Loc::Implicit,
+                format!("No contract named {contract_name}"),
+            ));
+        };
+        let loc = contract.loc(self).into_expr_err(Loc::Implicit)?;
+        let Some(func) = contract.funcs(self).into_iter().find(|func| {
+            let Ok(name) = func.name(self) else {
+                return false;
+            };
+            name == func_name || name.split('(').next() == Some(func_name.as_str())
+        }) else {
+            return Err(ExprErr::FunctionNotFound(
+                loc,
+                format!("No function named {func_name} in {contract_name}"),
+            ));
+        };
+        let Some(body_ctx) = func.maybe_body_ctx(self) else {
+            return Ok(vec![]);
+        };
+
+        let mut reports = vec![];
+        for leaf in body_ctx.successful_leaves(self).into_expr_err(loc)? {
+            let Some(var) = leaf
+                .storage_var_by_name(self, &storage_var_name)
+                .into_expr_err(loc)?
+            else {
+                continue;
+            };
+            let Some(range) = var.range(self).into_expr_err(loc)? else {
+                continue;
+            };
+            if !range.overlaps(&target, self) {
+                continue;
+            }
+
+            let mut lineage = leaf.parent_list(self).into_expr_err(loc)?;
+            lineage.reverse();
+            lineage.push(leaf);
+            let mut write_loc = None;
+            for ctx in lineage.iter() {
+                for effect in ctx.effects(self).into_expr_err(loc)? {
+                    let ContextEffect::StorageWrite {
+                        loc: effect_loc,
+                        var,
+                    } = effect
+                    else {
+                        continue;
+                    };
+                    if var.name(self).into_expr_err(loc)? == storage_var_name {
+                        write_loc = Some(LocStrSpan::new(file_mapping, effect_loc));
+                    }
+                }
+            }
+            let analysis = self.bounds_for_var_in_family_tree(
+                file_mapping,
+                lineage,
+                storage_var_name.clone(),
+                report_config,
+            );
+            reports.push(StorageRangeReport {
+                target: target.clone(),
+                write_loc,
+                analysis,
+            });
+        }
+        Ok(reports)
     }
 }
@@ -1,18 +1,44 @@
-use crate::analyzers::{VarBoundAnalyzer, *};
-use shared::context::CallFork;
+use crate::analyzers::{LocStrSpan, ReportDisplay, VarBoundAnalyzer};
+use crate::context::exprs::IntoExprErr;
+use crate::ExprErr;
 
-use shared::{analyzer::*, NodeIdx};
+use ariadne::{Cache, Color, Config, Label, Report, ReportKind, Span};
+use petgraph::{visit::EdgeRef, Direction};
+use shared::analyzer::{AnalyzerLike, GraphError, GraphLike, Search};
+use shared::context::{
+    CallFork, ContextEdge, ContextEffect, ContextNode, ContextVarNode, SinkKind,
+};
+use shared::nodes::ContractNode;
+use shared::range::elem::RangeElem;
+use shared::{Edge, NodeIdx};
+use solang_parser::pt::{Expression, Loc};
+use std::collections::{BTreeMap, BTreeSet};
 
-use ariadne::{Cache, Color, Config, Label, Report, ReportKind};
+/// A sensitive operation that data a caller controls flows into
+#[derive(Debug, Clone)]
+pub struct TaintedSink {
+    /// The name of the function a transaction enters the contract through to reach the operation
+    pub func_name: String,
+    /// What the operation is
+    pub kind: SinkKind,
+    /// The name of the value flowing into the operation
+    pub var_name: String,
+    /// The operation's location string span
+    pub loc: LocStrSpan,
+    /// Where the caller controlled data comes from, e.g. `msg.sender` or an input of a function
+    pub sources: Vec<String>,
+}
 
 #[derive(Debug, Clone)]
 pub struct TaintReport {
     pub msgs: Vec<String>,
+    /// The sensitive operations caller controlled data reaches
+    pub sinks: Vec<TaintedSink>,
 }
 
 impl TaintReport {
-    pub fn new(msgs: Vec<String>) -> Self {
-        Self { msgs }
+    pub fn new(msgs: Vec<String>, sinks: Vec<TaintedSink>) -> Self {
+        Self { msgs, sinks }
     }
 }
 
@@ -25,11 +51,28 @@ impl ReportDisplay for TaintReport {
     }
 
     fn labels(&self, _analyzer: &impl GraphLike) -> Vec<Label<LocStrSpan>> {
-        vec![]
+        self.sinks
+            .iter()
+            .enumerate()
+            .map(|(i, sink)| {
+                Label::new(sink.loc.clone())
+                    .with_message(format!(
+                        "{} from {}",
+                        sink.kind.as_str(),
+                        sink.sources.join(", ")
+                    ))
+                    .with_color(Color::Red)
+                    .with_order(i as i32)
+            })
+            .collect()
     }
 
     fn reports(&self, analyzer: &impl GraphLike) -> Vec<Report<LocStrSpan>> {
-        let report = Report::build(self.report_kind(), "".to_string(), 0)
+        let (source, start) = match self.sinks.first() {
+            Some(sink) => (sink.loc.source().clone(), sink.loc.start()),
+            None => ("".to_string(), 0),
+        };
+        let mut report = Report::build(self.report_kind(), source, start)
             .with_message(self.msg(analyzer))
             .with_config(
                 Config::default()
@@ -37,6 +80,7 @@ impl ReportDisplay for TaintReport {
                     .with_underlines(true)
                     .with_tab_width(4),
             );
+        report.add_labels(self.labels(analyzer));
         vec![report.finish()]
     }
 
@@ -55,14 +99,276 @@ impl ReportDisplay for TaintReport {
     }
 }
 
-impl<T> TaintQuery for T where T: VarBoundAnalyzer + Search + AnalyzerLike + Sized {}
-pub trait TaintQuery: VarBoundAnalyzer + Search + AnalyzerLike + Sized {
-    #[allow(clippy::too_many_arguments)]
-    fn taint_query(&self, _entry: NodeIdx, _contract_name: String) {
-        todo!()
+impl<T> TaintQuery for T where
+    T: VarBoundAnalyzer + Search + AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized
+{
+}
+/// Tracks the data a caller controls to the sensitive operations it reaches
+pub trait TaintQuery:
+    VarBoundAnalyzer + Search + AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized
+{
+    /// Finds the sensitive operations that values a caller controls reach in the functions a transaction can
+    /// enter the named contract through: `delegatecall` targets, `selfdestruct` recipients, `sstore` slots, array
+    /// indices and `transfer` or `send` amounts.
+    ///
+    /// Caller controlled values are the inputs of the functions, `msg.*`, `tx.origin`, `calldataload`s and the
+    /// returns of external calls. They flow into the variables constructed from them, the variables they are
+    /// assigned to, the inputs of the functions they are passed to and the storage variables they are written
+    /// to, which are then caller controlled in every function. Casts don't carry the taint of what they cast.
+    ///
+    /// The contract is looked up in the source units of all the given entries
+    fn taint_query(
+        &mut self,
+        entries: &[NodeIdx],
+        file_mapping: &BTreeMap<usize, String>,
+        contract_name: String,
+    ) -> Result<TaintReport, ExprErr> {
+        let Some(contract) = entries
+            .iter()
+            .flat_map(|entry| self.search_children(*entry, &Edge::Contract))
+            .map(ContractNode::from)
+            .find(|contract| matches!(contract.name(self), Ok(name) if name == contract_name))
+        else {
+            return Ok(TaintReport::new(
+                vec![format!("No contract named {contract_name}")],
+                vec![],
+            ));
+        };
+        let loc = contract.loc(self).into_expr_err(Loc::Implicit)?;
+        let constructor = contract.constructor(self);
+        let funcs = contract
+            .entry_functions(self)
+            .into_iter()
+            .filter(|func| Some(*func) != constructor)
+            .filter_map(|func| Some((func, func.maybe_body_ctx(self)?)))
+            .collect::<Vec<_>>();
+
+        let mut params = BTreeMap::default();
+        // the contexts of each function that evaluate the contract's own code
+        let mut ctxs = vec![];
+        for (func, body_ctx) in funcs.iter() {
+            let func_name = func.name(self).into_expr_err(loc)?;
+            for param in func.params(self) {
+                let Some(name) = param.maybe_name(self).into_expr_err(loc)? else {
+                    continue;
+                };
+                if let Some(var) = body_ctx.var_by_name(self, &name) {
+                    params.insert(
+                        var.first_version(self),
+                        format!("input {name} of {func_name}"),
+                    );
+                }
+            }
+
+            let mut func_ctxs = vec![*body_ctx];
+            if let Some(child) = body_ctx.underlying(self).into_expr_err(loc)?.child {
+                func_ctxs.extend(self.recurse_children(child).into_expr_err(loc)?);
+            }
+            for ctx in func_ctxs {
+                if !ctx.in_external_call(self).into_expr_err(loc)? {
+                    ctxs.push((func_name.clone(), ctx));
+                }
+            }
+        }
+
+        // a write of a caller controlled value taints the storage variable for every function, which can taint
+        // more writes
+        let mut taint = Taint {
+            params,
+            storage: BTreeMap::default(),
+            memo: BTreeMap::default(),
+        };
+        loop {
+            let mut changed = false;
+            for (_, ctx) in ctxs.iter() {
+                for effect in ctx.effects(self).into_expr_err(loc)? {
+                    let ContextEffect::StorageWrite { var, .. } = effect else {
+                        continue;
+                    };
+                    let sources = taint.sources(var, self).into_expr_err(loc)?;
+                    if sources.is_empty() {
+                        continue;
+                    }
+                    let name = var.root_name(self).into_expr_err(loc)?;
+                    let tainted = taint.storage.entry(name).or_default();
+                    let num_sources = tainted.len();
+                    tainted.extend(sources);
+                    changed |= tainted.len() != num_sources;
+                }
+            }
+            if !changed {
+                break;
+            }
+            taint.memo.clear();
+        }
+
+        let mut sinks: BTreeMap<(String, Loc, SinkKind), TaintedSink> = BTreeMap::default();
+        for (func_name, ctx) in ctxs.iter() {
+            for sink in ctx.sinks(self).into_expr_err(loc)? {
+                let sources = taint.sources(sink.var, self).into_expr_err(loc)?;
+                if sources.is_empty() {
+                    continue;
+                }
+                let var_name = sink.var.display_name(self).into_expr_err(loc)?;
+                let tainted = sinks
+                    .entry((func_name.clone(), sink.loc, sink.kind))
+                    .or_insert_with(|| TaintedSink {
+                        func_name: func_name.clone(),
+                        kind: sink.kind,
+                        var_name,
+                        loc: LocStrSpan::new(file_mapping, sink.loc),
+                        sources: vec![],
+                    });
+                tainted.sources.extend(sources);
+                tainted.sources.sort();
+                tainted.sources.dedup();
+            }
+        }
+
+        let sinks = sinks.into_values().collect::<Vec<_>>();
+        let msgs = if sinks.is_empty() {
+            vec![format!(
+                "No caller controlled data reaches a sensitive operation in {contract_name}"
+            )]
+        } else {
+            sinks
+                .iter()
+                .map(|sink| {
+                    format!(
+                        "{} reaches the {} in {} from {}",
+                        sink.var_name,
+                        sink.kind.as_str(),
+                        sink.func_name,
+                        sink.sources.join(", ")
+                    )
+                })
+                .collect()
+        };
+        Ok(TaintReport::new(msgs, sinks))
+    }
+
+    /// Returns the contexts a call or fork leads to and all contexts below them
+    fn recurse_children(&self, child: CallFork) -> Result<Vec<ContextNode>, GraphError> {
+        let children = match child {
+            CallFork::Call(call) => vec![call],
+            CallFork::Fork(w1, w2) => vec![w1, w2],
+        };
+        let mut ctxs = vec![];
+        for ctx in children {
+            ctxs.push(ctx);
+            ctxs.extend(ctx.descendants(self)?);
+        }
+        Ok(ctxs)
     }
+}
+
+/// The caller controlled values of a contract's entry functions
+struct Taint {
+    /// The inputs of the functions, by their first version
+    params: BTreeMap<ContextVarNode, String>,
+    /// The sources of the caller controlled values written to each storage variable, by the variable's name
+    storage: BTreeMap<String, BTreeSet<String>>,
+    /// The sources of the variables already looked at
+    memo: BTreeMap<ContextVarNode, BTreeSet<String>>,
+}
 
-    fn recurse_children(&self, _child: CallFork) {
-        todo!()
+impl Taint {
+    /// Returns where the caller controlled data a variable holds comes from. An empty set means the variable
+    /// isn't caller controlled
+    fn sources(
+        &mut self,
+        var: ContextVarNode,
+        analyzer: &impl GraphLike,
+    ) -> Result<BTreeSet<String>, GraphError> {
+        if let Some(sources) = self.memo.get(&var) {
+            return Ok(sources.clone());
+        }
+        // a variable that is still being looked at holds nothing, so cycles end
+        self.memo.insert(var, BTreeSet::default());
+
+        let underlying = var.underlying(analyzer)?;
+        let mut sources = BTreeSet::default();
+        if let Some(param) = self.params.get(&var) {
+            sources.insert(param.clone());
+        }
+        if underlying.name.starts_with("msg.") || underlying.name == "tx.origin" {
+            sources.insert(underlying.name.clone());
+        }
+        if underlying.display_name.starts_with("calldata[") {
+            sources.insert("calldata".to_string());
+        }
+        if underlying.is_return {
+            if let Some(ctx) = var.maybe_ctx(analyzer) {
+                if let Some(ext_fn) = ctx.underlying(analyzer)?.ext_fn_call {
+                    // what the other contract computes the return from is its own
+                    sources.insert(format!(
+                        "return of external call to {}",
+                        ext_fn.name(analyzer)?
+                    ));
+                    self.memo.insert(var, sources.clone());
+                    return Ok(sources);
+                }
+            }
+        }
+        if var.is_storage(analyzer)?
+            && var.previous_or_inherited_version(analyzer).is_none()
+            && var.index_to_array(analyzer).is_none()
+        {
+            if let Some(storage) = self.storage.get(&underlying.name) {
+                sources.extend(storage.iter().cloned());
+            }
+        }
+
+        for flow in flows_into(var, analyzer)? {
+            sources.extend(self.sources(flow, analyzer)?);
+        }
+        self.memo.insert(var, sources.clone());
+        Ok(sources)
+    }
+}
+
+/// Returns the variables whose values flow into a variable: what a temporary is constructed from, the value
+/// assigned to it or otherwise the version before it, the input a function's parameter is passed, the return
+/// a call's result is assigned from and the array an element is read from
+fn flows_into(
+    var: ContextVarNode,
+    analyzer: &impl GraphLike,
+) -> Result<Vec<ContextVarNode>, GraphError> {
+    let underlying = var.underlying(analyzer)?;
+    let mut flows = vec![];
+    if let Some(tmp) = underlying.tmp_of() {
+        flows.push(tmp.lhs);
+        flows.extend(tmp.rhs);
+    }
+    flows.extend(
+        analyzer
+            .graph()
+            .edges_directed(var.0.into(), Direction::Outgoing)
+            .filter(|edge| *edge.weight() == Edge::Context(ContextEdge::InputVariable))
+            .map(|edge| ContextVarNode::from(edge.target())),
+    );
+    flows.extend(
+        analyzer
+            .graph()
+            .edges_directed(var.0.into(), Direction::Incoming)
+            .filter(|edge| matches!(*edge.weight(), Edge::Context(ContextEdge::ReturnAssign(_))))
+            .map(|edge| ContextVarNode::from(edge.source())),
+    );
+    if let Some(arr) = var.index_to_array(analyzer) {
+        flows.push(arr);
+    }
+
+    match (var.range_min(analyzer)?, var.range_max(analyzer)?) {
+        // an assignment replaces the value with exactly the assigned one
+        (Some(min), Some(max)) if min == max => flows.extend(min.dependent_on()),
+        (min, max) => {
+            if underlying.is_tmp {
+                flows.extend(min.map(|min| min.dependent_on()).unwrap_or_default());
+                flows.extend(max.map(|max| max.dependent_on()).unwrap_or_default());
+            }
+            flows.extend(var.previous_or_inherited_version(analyzer));
+        }
     }
+    flows.retain(|flow| *flow != var);
+    Ok(flows)
 }
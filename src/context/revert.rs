@@ -0,0 +1,162 @@
+use crate::context::exprs::IntoExprErr;
+use crate::context::func_call::FuncCaller;
+use crate::context::ContextBuilder;
+use crate::ExprErr;
+
+use shared::analyzer::{AnalyzerLike, GraphLike};
+use shared::context::*;
+use shared::nodes::{ContractNode, ErrorNode};
+use shared::Node;
+use solang_parser::pt::{Expression, IdentifierPath, Loc, NamedArgument};
+
+impl<T> Reverter for T where
+    T: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized + GraphLike
+{
+}
+/// Handles `revert` statements
+pub trait Reverter: GraphLike + AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized {
+    /// Evaluates the arguments of a `revert` statement, records the error and arguments on the context
+    /// and kills it
+    #[tracing::instrument(level = "trace", skip_all)]
+    fn revert(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        maybe_err_path: &Option<IdentifierPath>,
+        input_exprs: &[Expression],
+    ) -> Result<(), ExprErr> {
+        let error = if let Some(err_path) = maybe_err_path {
+            Some(self.resolve_error(loc, ctx, err_path)?)
+        } else {
+            None
+        };
+
+        if input_exprs.is_empty() {
+            if error.is_some() {
+                ctx.set_reverts_with(
+                    ContextRevert {
+                        loc,
+                        error,
+                        args: vec![],
+                    },
+                    self,
+                )
+                .into_expr_err(loc)?;
+            }
+            return ctx.kill(self, loc, KilledKind::Revert).into_expr_err(loc);
+        }
+
+        self.parse_inputs(ctx, loc, input_exprs)?;
+        self.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
+            let Some(inputs) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                return Err(ExprErr::NoRhs(loc, "Revert statement had no arguments".to_string()))
+            };
+            if let ExprRet::CtxKilled(kind) = inputs {
+                return ctx.kill(analyzer, loc, kind).into_expr_err(loc);
+            }
+            let args = inputs
+                .as_flat_vec()
+                .into_iter()
+                .map(|arg| ContextVarNode::from(arg).latest_version(analyzer))
+                .collect();
+            ctx.set_reverts_with(ContextRevert { loc, error, args }, analyzer)
+                .into_expr_err(loc)?;
+            ctx.kill(analyzer, loc, KilledKind::Revert)
+                .into_expr_err(loc)
+        })
+    }
+
+    /// Evaluates a `revert` with named arguments, i.e. `revert InsufficientBalance({available: a, required: b})`,
+    /// by ordering the arguments by the error's parameters
+    fn revert_named_args(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        maybe_err_path: &Option<IdentifierPath>,
+        named_args: &[NamedArgument],
+    ) -> Result<(), ExprErr> {
+        let Some(err_path) = maybe_err_path else {
+            return Err(ExprErr::ParseError(
+                loc,
+                "Named arguments in a revert require an error".to_string(),
+            ))
+        };
+        let error = self.resolve_error(loc, ctx, err_path)?;
+        let input_exprs = error
+            .params(self)
+            .iter()
+            .map(|param| {
+                let name = param.maybe_name(self).into_expr_err(loc)?;
+                named_args
+                    .iter()
+                    .find(|arg| Some(&arg.name.name) == name.as_ref())
+                    .map(|arg| arg.expr.clone())
+                    .ok_or_else(|| {
+                        ExprErr::InvalidFunctionInput(
+                            loc,
+                            format!("No argument for error parameter: {name:?}"),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>, ExprErr>>()?;
+        self.revert(loc, ctx, maybe_err_path, &input_exprs)
+    }
+
+    /// Finds the error being reverted with, either by name (preferring errors of the current contract
+    /// and the contracts it inherits from) or as a member of a contract, i.e. `revert IVault.Locked()`
+    fn resolve_error(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        err_path: &IdentifierPath,
+    ) -> Result<ErrorNode, ExprErr> {
+        let (name, candidates) = match &err_path.identifiers[..] {
+            [ident] => {
+                let mut errors = ctx
+                    .maybe_associated_contract(self)
+                    .into_expr_err(loc)?
+                    .map(|contract| contract.linearized_errors(self))
+                    .unwrap_or_default();
+                ctx.visible_errors(self).into_iter().for_each(|error| {
+                    if !errors.contains(&error) {
+                        errors.push(error);
+                    }
+                });
+                (ident.name.clone(), errors)
+            }
+            [contract_ident, ident] => {
+                let Some(contract) = self
+                    .user_types()
+                    .get(&contract_ident.name)
+                    .copied()
+                    .filter(|idx| matches!(self.node(*idx), Node::Contract(..))) else {
+                    return Err(ExprErr::MemberAccessNotFound(
+                        loc,
+                        format!(
+                            "Could not find the contract of error: {}",
+                            contract_ident.name
+                        ),
+                    ))
+                };
+                (
+                    ident.name.clone(),
+                    ContractNode::from(contract).linearized_errors(self),
+                )
+            }
+            _ => {
+                return Err(ExprErr::ParseError(
+                    loc,
+                    format!("Unsupported error path: {err_path:?}"),
+                ))
+            }
+        };
+
+        // errors can't be overloaded, so the name is enough
+        candidates
+            .into_iter()
+            .find(|error| error.name(self).unwrap() == name)
+            .ok_or_else(|| {
+                ExprErr::FunctionNotFound(loc, format!("Could not find error \"{name}\""))
+            })
+    }
+}
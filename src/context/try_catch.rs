@@ -0,0 +1,247 @@
+use crate::context::exprs::IntoExprErr;
+use crate::context::ContextBuilder;
+use crate::ExprErr;
+use ethers_core::types::U256;
+
+use shared::analyzer::{AnalyzerLike, GraphLike};
+use shared::context::*;
+use shared::nodes::Concrete;
+use shared::range::elem_ty::Elem;
+use shared::{Edge, Node, NodeIdx};
+use solang_parser::pt::{CatchClause, Expression, Loc, Parameter, Statement, VariableDeclaration};
+
+/// The largest panic code solidity uses, `0x51` (call to a zero-initialized internal function)
+pub const MAX_PANIC_CODE: u64 = 0x51;
+
+/// A branch of a `try` statement
+#[derive(Debug, Clone, Copy)]
+enum TryBranch<'a> {
+    /// The call succeeded. Contains the `returns` parameters and the success block
+    Success(Option<&'a (Vec<(Loc, Option<Parameter>)>, Box<Statement>)>),
+    /// The call reverted and this catch clause handles it
+    Catch(&'a CatchClause),
+}
+
+impl<'a> TryBranch<'a> {
+    fn fork_name(&self) -> String {
+        match self {
+            TryBranch::Success(..) => "try".to_string(),
+            TryBranch::Catch(CatchClause::Simple(..)) => "catch".to_string(),
+            TryBranch::Catch(CatchClause::Named(_, ident, ..)) => format!("catch {}", ident.name),
+        }
+    }
+}
+
+impl<T> TryCatcher for T where
+    T: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized + GraphLike
+{
+}
+/// Handles `try`/`catch` statements
+pub trait TryCatcher:
+    GraphLike + AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized
+{
+    /// Evaluates the call, then forks the context into the success path and a path for each catch
+    /// clause. The call and its arguments are evaluated before forking, so the side effects of
+    /// evaluating the arguments are seen by the catch clauses too
+    #[tracing::instrument(level = "trace", skip_all)]
+    fn try_catch(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        try_expr: &Expression,
+        maybe_returns: &Option<(Vec<(Loc, Option<Parameter>)>, Box<Statement>)>,
+        clauses: &[CatchClause],
+    ) -> Result<(), ExprErr> {
+        // without a `returns`, the success block is parsed as a block of the call itself
+        let (try_expr, maybe_returns) = match (try_expr, maybe_returns) {
+            (Expression::FunctionCallBlock(_, call, block), None) => {
+                (&**call, &Some((vec![], block.clone())))
+            }
+            _ => (try_expr, maybe_returns),
+        };
+        self.parse_ctx_expr(try_expr, ctx)?;
+        self.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
+            // a call of a function without return values leaves nothing behind
+            let ret = ctx
+                .pop_expr_latest(loc, analyzer)
+                .into_expr_err(loc)?
+                .unwrap_or(ExprRet::Null);
+            if let ExprRet::CtxKilled(kind) = ret {
+                return ctx.kill(analyzer, loc, kind).into_expr_err(loc);
+            }
+            analyzer.try_branches(loc, ctx, &ret, maybe_returns, clauses)
+        })
+    }
+
+    /// Forks the context into the success path and a path for each catch clause
+    fn try_branches(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        ret: &ExprRet,
+        maybe_returns: &Option<(Vec<(Loc, Option<Parameter>)>, Box<Statement>)>,
+        clauses: &[CatchClause],
+    ) -> Result<(), ExprErr> {
+        let mut branches = vec![TryBranch::Success(maybe_returns.as_ref())];
+        branches.extend(clauses.iter().map(TryBranch::Catch));
+
+        // fork off each branch but the last, which gets the remaining context
+        let mut curr_ctx = ctx;
+        let mut branch_ctxs = vec![];
+        for (i, branch) in branches.iter().enumerate().take(branches.len() - 1) {
+            let rest_name = if i + 2 == branches.len() {
+                branches[i + 1].fork_name()
+            } else {
+                "catch".to_string()
+            };
+            let (branch_ctx, rest_ctx) =
+                self.fork_ctx(loc, curr_ctx, &branch.fork_name(), &rest_name)?;
+            branch_ctxs.push((branch_ctx, *branch));
+            curr_ctx = rest_ctx;
+        }
+        branch_ctxs.push((curr_ctx, *branches.last().unwrap()));
+
+        branch_ctxs
+            .into_iter()
+            .try_for_each(|(branch_ctx, branch)| match branch {
+                TryBranch::Success(maybe_returns) => {
+                    self.try_success(loc, branch_ctx, ret, maybe_returns)
+                }
+                TryBranch::Catch(clause) => self.catch_clause(loc, branch_ctx, clause),
+            })
+    }
+
+    /// Forks a context in two, like the branches of an `if` statement
+    fn fork_ctx(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        lhs_name: &str,
+        rhs_name: &str,
+    ) -> Result<(ContextNode, ContextNode), ExprErr> {
+        let lctx = Context::new_subctx(ctx, None, loc, Some(lhs_name), None, false, self, None)
+            .into_expr_err(loc)?;
+        let lhs_subctx = ContextNode::from(self.add_node(Node::Context(lctx)));
+        let rctx = Context::new_subctx(ctx, None, loc, Some(rhs_name), None, false, self, None)
+            .into_expr_err(loc)?;
+        let rhs_subctx = ContextNode::from(self.add_node(Node::Context(rctx)));
+        ctx.set_child_fork(lhs_subctx, rhs_subctx, self)
+            .into_expr_err(loc)?;
+        let ctx_fork = self.add_node(Node::ContextFork);
+        self.add_edge(ctx_fork, ctx, Edge::Context(ContextEdge::ContextFork));
+        self.add_edge(
+            NodeIdx::from(lhs_subctx.0),
+            ctx_fork,
+            Edge::Context(ContextEdge::Subcontext),
+        );
+        self.add_edge(
+            NodeIdx::from(rhs_subctx.0),
+            ctx_fork,
+            Edge::Context(ContextEdge::Subcontext),
+        );
+        Ok((lhs_subctx, rhs_subctx))
+    }
+
+    /// Binds the `returns` variables to the return values of the call of a `try` statement and
+    /// parses the success block
+    fn try_success(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        ret: &ExprRet,
+        maybe_returns: Option<&(Vec<(Loc, Option<Parameter>)>, Box<Statement>)>,
+    ) -> Result<(), ExprErr> {
+        let Some((returns, body)) = maybe_returns else {
+            return Ok(());
+        };
+
+        let rets = ret.clone().flatten().as_vec();
+        returns
+            .iter()
+            .zip(rets.iter())
+            .try_for_each(|((_, maybe_param), ret)| {
+                let Some(param) = maybe_param else {
+                    return Ok(());
+                };
+                self.bind_param(loc, ctx, param, Some(ret))
+            })?;
+
+        self.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
+            analyzer.parse_ctx_statement(
+                body,
+                ctx.unchecked(analyzer).into_expr_err(loc)?,
+                Some(ctx),
+            );
+            Ok(())
+        })
+    }
+
+    /// Binds the error payload of a catch clause and parses its block. An `Error(string)` reason is
+    /// an unknown string and a `Panic(uint)` code is bounded by the largest panic code
+    fn catch_clause(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        clause: &CatchClause,
+    ) -> Result<(), ExprErr> {
+        let (maybe_param, body, is_panic) = match clause {
+            CatchClause::Simple(_, maybe_param, body) => (maybe_param.as_ref(), body, false),
+            CatchClause::Named(_, ident, param, body) => (Some(param), body, ident.name == "Panic"),
+        };
+
+        if let Some(param) = maybe_param {
+            self.bind_param(loc, ctx, param, None)?;
+            if let (true, Some(name)) = (is_panic, &param.name) {
+                if let Some(code) = ctx.var_by_name(self, &name.name) {
+                    code.set_range_min(self, Elem::from(Concrete::from(U256::zero())))
+                        .into_expr_err(loc)?;
+                    code.set_range_max(
+                        self,
+                        Elem::from(Concrete::from(U256::from(MAX_PANIC_CODE))),
+                    )
+                    .into_expr_err(loc)?;
+                }
+            }
+        }
+
+        self.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
+            analyzer.parse_ctx_statement(
+                body,
+                ctx.unchecked(analyzer).into_expr_err(loc)?,
+                Some(ctx),
+            );
+            Ok(())
+        })
+    }
+
+    /// Defines a variable for a named `returns` or catch clause parameter, optionally assigning it a value
+    fn bind_param(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        param: &Parameter,
+        value: Option<&ExprRet>,
+    ) -> Result<(), ExprErr> {
+        if param.name.is_none() {
+            return Ok(());
+        }
+        let var_decl = VariableDeclaration {
+            loc: param.loc,
+            ty: param.ty.clone(),
+            storage: param.storage.clone(),
+            name: param.name.clone(),
+        };
+        self.parse_ctx_expr(&var_decl.ty, ctx)?;
+        self.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
+            let Some(lhs_paths) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                return Err(ExprErr::NoLhs(loc, "Parameter had no type".to_string()))
+            };
+            if matches!(lhs_paths, ExprRet::CtxKilled(_)) {
+                ctx.push_expr(lhs_paths, analyzer).into_expr_err(loc)?;
+                return Ok(());
+            }
+            analyzer.match_var_def(ctx, &var_decl, loc, &lhs_paths, value)?;
+            Ok(())
+        })
+    }
+}
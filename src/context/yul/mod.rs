@@ -3,6 +3,8 @@ use crate::context::ContextBuilder;
 use crate::context::ExprParser;
 use crate::AnalyzerLike;
 use crate::ExprErr;
+use crate::{Concrete, ConcreteNode};
+use ethers_core::types::U256;
 use shared::context::Context;
 use shared::context::ContextVar;
 use shared::context::ContextVarNode;
@@ -17,7 +19,7 @@ use solang_parser::helpers::CodeLocation;
 use solang_parser::pt::Expression;
 use solang_parser::pt::Loc;
 
-use solang_parser::pt::{YulExpression, YulFor, YulStatement, YulSwitch};
+use solang_parser::pt::{Identifier, YulExpression, YulFor, YulStatement, YulSwitch};
 
 mod yul_cond_op;
 pub use yul_cond_op::*;
@@ -142,8 +144,20 @@ pub trait YulBuilder:
                                 return Ok(());
                             }
 
-                            analyzer.match_assign_yul(ctx, loc, &nodes, ret)
-
+                            analyzer.match_assign_yul(ctx, loc, &nodes, ret.clone())?;
+                            // assigned like `:=`, so the variables alias the values they were declared with
+                            let lhs = match &nodes[..] {
+                                [node] => ExprRet::Single(node.latest_version(analyzer).into()),
+                                nodes => ExprRet::Multi(
+                                    nodes
+                                        .iter()
+                                        .map(|node| {
+                                            ExprRet::Single(node.latest_version(analyzer).into())
+                                        })
+                                        .collect(),
+                                ),
+                            };
+                            analyzer.match_assign_sides(ctx, loc, &lhs, &ret)
                         })
                     } else {
                         Ok(())
@@ -272,11 +286,67 @@ pub trait YulBuilder:
             StringLiteral(lit, _) => self.string_literal(ctx, lit.loc, &lit.string),
             Variable(ident) => self.variable(ident, ctx, None),
             FunctionCall(yul_func_call) => self.yul_func_call(yul_func_call, ctx),
-            SuffixAccess(_loc, _yul_member_expr, _ident) => Err(ExprErr::Todo(
-                expr.loc(),
-                "Yul member access not yet supported".to_string(),
-            )),
+            SuffixAccess(loc, yul_member_expr, ident) => {
+                self.yul_suffix_access(ctx, *loc, yul_member_expr, ident)
+            }
+        }
+    }
+
+    /// Resolves the `.slot` and `.offset` of a storage variable from the storage layout of the context's
+    /// contract. Those of local storage pointers aren't known, so they are unconstrained
+    fn yul_suffix_access(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        expr: &YulExpression,
+        suffix: &Identifier,
+    ) -> Result<(), ExprErr> {
+        let YulExpression::Variable(ident) = expr else {
+            return Err(ExprErr::Todo(
+                loc,
+                "Yul member access is only supported on variables".to_string(),
+            ));
+        };
+        if !matches!(&*suffix.name, "slot" | "offset") {
+            return Err(ExprErr::Todo(
+                loc,
+                format!("Yul member access `.{}` is not supported", suffix.name),
+            ));
+        }
+
+        let mut storage_slot = None;
+        if let Some(contract) = ctx.maybe_associated_contract(self).into_expr_err(loc)? {
+            for slot in contract.storage_layout(self).into_expr_err(loc)? {
+                if slot.var.name(self).into_expr_err(loc)? == ident.name {
+                    storage_slot = Some(slot);
+                }
+            }
         }
+
+        let var = match storage_slot {
+            Some(slot) => {
+                let val = if suffix.name == "slot" {
+                    slot.slot
+                } else {
+                    U256::from(slot.offset)
+                };
+                let cnode = ConcreteNode::from(self.add_node(Node::Concrete(Concrete::from(val))));
+                let mut var =
+                    ContextVar::new_from_concrete(loc, ctx, cnode, self).into_expr_err(loc)?;
+                var.display_name = format!("{}.{}", ident.name, suffix.name);
+                var
+            }
+            None => {
+                let b = self.builtin_or_add(Builtin::Uint(256));
+                let mut var =
+                    ContextVar::new_from_builtin(loc, b.into(), self).into_expr_err(loc)?;
+                var.display_name = format!("{}.{}", ident.name, suffix.name);
+                var
+            }
+        };
+        let node = self.add_node(Node::ContextVar(var));
+        ctx.push_expr(ExprRet::Single(node), self)
+            .into_expr_err(loc)
     }
 
     fn match_assign_yul(
@@ -1,7 +1,9 @@
+use crate::context::exprs::Array;
 use crate::context::exprs::BinOp;
 use crate::context::exprs::Cmp;
 use crate::context::exprs::Env;
 use crate::context::exprs::IntoExprErr;
+use crate::context::exprs::Variable;
 use crate::context::yul::YulBuilder;
 use crate::context::ContextBuilder;
 use crate::context::ExprErr;
@@ -12,7 +14,7 @@ use ethers_core::types::U256;
 use shared::analyzer::AnalyzerLike;
 use shared::analyzer::GraphLike;
 use shared::context::ExprRet;
-use shared::nodes::VarType;
+use shared::nodes::{StorageElements, StorageSlot, VarNode, VarType};
 use shared::range::elem_ty::RangeExpr;
 
 use solang_parser::pt::YulExpression;
@@ -23,7 +25,7 @@ use shared::range::{elem_ty::Elem, SolcRange};
 use shared::{context::ContextEdge, nodes::Builtin, Edge};
 use shared::{context::*, range::elem::RangeOp};
 use solang_parser::pt::YulFunctionCall;
-use solang_parser::pt::{Expression, Loc, StorageLocation};
+use solang_parser::pt::{Expression, Identifier, Loc, StorageLocation};
 
 impl<T> YulFuncCaller for T where
     T: AnalyzerLike<Expr = Expression, ExprErr = ExprErr> + Sized + GraphLike
@@ -70,9 +72,29 @@ pub trait YulFuncCaller:
                     .into_expr_err(*loc)?;
                 Ok(())
             }
-            "stop" | "revert" | "selfdestruct" | "invalid" => {
+            "stop" | "revert" | "invalid" => {
                 ctx.kill(self, *loc, KilledKind::Revert).into_expr_err(*loc)
             }
+            "selfdestruct" => {
+                self.parse_ctx_yul_expr(&arguments[0], ctx)?;
+                self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
+                    let Some(recipient) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                        return Err(ExprErr::NoRhs(loc, "Yul `selfdestruct` operation had no recipient".to_string()))
+                    };
+                    if matches!(recipient, ExprRet::CtxKilled(_)) {
+                        ctx.push_expr(recipient, analyzer).into_expr_err(loc)?;
+                        return Ok(());
+                    }
+                    analyzer.record_yul_sink(
+                        ctx,
+                        loc,
+                        SinkKind::SelfdestructRecipient,
+                        &recipient,
+                    )?;
+                    ctx.kill(analyzer, loc, KilledKind::Revert)
+                        .into_expr_err(loc)
+                })
+            }
             "return" => {
                 self.parse_ctx_yul_expr(&arguments[0], ctx)?;
                 self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
@@ -290,7 +312,7 @@ pub trait YulFuncCaller:
                     .into_expr_err(*loc)?;
                 Ok(())
             }
-            "msize" | "pc" | "mload" | "sload" | "gas" | "returndatasize" => {
+            "msize" | "pc" | "mload" | "gas" | "returndatasize" => {
                 // TODO: actually handle this. @MemoryModel
                 let b = Builtin::Uint(256);
                 let var = ContextVar::new_from_builtin(*loc, self.builtin_or_add(b).into(), self)
@@ -300,8 +322,61 @@ pub trait YulFuncCaller:
                     .into_expr_err(*loc)?;
                 Ok(())
             }
+            "sload" => {
+                if arguments.len() != 1 {
+                    return Err(ExprErr::InvalidFunctionInput(
+                        *loc,
+                        format!(
+                            "Yul function: `sload` expects 1 arguments found: {:?}",
+                            arguments.len()
+                        ),
+                    ));
+                }
+
+                self.parse_ctx_yul_expr(&arguments[0], ctx)?;
+                self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
+                    let Some(slot) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                        return Err(ExprErr::NoRhs(loc, "Yul `sload` operation had no slot".to_string()))
+                    };
+                    if matches!(slot, ExprRet::CtxKilled(_)) {
+                        ctx.push_expr(slot, analyzer).into_expr_err(loc)?;
+                        return Ok(());
+                    }
+
+                    // loading the slot of a whole word variable or element is reading it
+                    if let Some(elem) = analyzer.yul_storage_element(ctx, loc, &slot)? {
+                        ctx.push_expr(ExprRet::Single(elem.into()), analyzer)
+                            .into_expr_err(loc)?;
+                        return Ok(());
+                    }
+                    if let Some((slot, layout)) = analyzer.yul_storage_slots(ctx, loc, &slot)? {
+                        let at_slot = layout
+                            .into_iter()
+                            .filter(|storage_slot| storage_slot.contains(slot))
+                            .collect::<Vec<_>>();
+                        if let [storage_slot] = &at_slot[..] {
+                            if storage_slot.is_full_word()
+                                && analyzer.yul_storage_var(ctx, loc, storage_slot.var)?
+                            {
+                                return Ok(());
+                            }
+                        }
+                    }
+
+                    let b = Builtin::Uint(256);
+                    let mut var = ContextVar::new_from_builtin(loc, analyzer.builtin_or_add(b).into(), analyzer)
+                        .into_expr_err(loc)?;
+                    let elem = ContextVarNode::from(slot.expect_single().into_expr_err(loc)?);
+                    var.display_name = format!("sload({})", elem.display_name(analyzer).into_expr_err(loc)?);
+                    let node = analyzer.add_node(Node::ContextVar(var));
+                    ctx.push_expr(ExprRet::Single(node), analyzer)
+                        .into_expr_err(loc)
+                })
+            }
             "calldatacopy" => {
                 // TODO: actually handle this. @MemoryModel
+                // the copied calldata could overwrite any word that is known
+                ctx.forget_memory(self).into_expr_err(*loc)?;
                 Ok(())
             }
             "calldataload" => {
@@ -332,13 +407,32 @@ pub trait YulFuncCaller:
                 })
             }
             "keccak256" => {
-                let b = Builtin::Bytes(32);
-                let var = ContextVar::new_from_builtin(*loc, self.builtin_or_add(b).into(), self)
-                    .into_expr_err(*loc)?;
-                let node = self.add_node(Node::ContextVar(var));
-                ctx.push_expr(ExprRet::Single(node), self)
-                    .into_expr_err(*loc)?;
-                Ok(())
+                if arguments.len() != 2 {
+                    return Err(ExprErr::InvalidFunctionInput(
+                        *loc,
+                        format!(
+                            "Yul function: `keccak256` expects 2 arguments found: {:?}",
+                            arguments.len()
+                        ),
+                    ));
+                }
+
+                self.parse_inputs(ctx, *loc, arguments)?;
+                self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
+                    let Some(inputs) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                        return Err(ExprErr::NoRhs(loc, "Yul `keccak256` operation had no inputs".to_string()))
+                    };
+                    if matches!(inputs, ExprRet::CtxKilled(_)) {
+                        ctx.push_expr(inputs, analyzer).into_expr_err(loc)?;
+                        return Ok(());
+                    }
+
+                    inputs.expect_length(2).into_expr_err(loc)?;
+                    let inputs = inputs.as_vec();
+                    let hash = analyzer.yul_keccak256(ctx, loc, &inputs[0], &inputs[1])?;
+                    ctx.push_expr(ExprRet::Single(hash.into()), analyzer)
+                        .into_expr_err(loc)
+                })
             }
             "call" | "delegatecall" | "callcode" | "staticcall" => {
                 let b = Builtin::Uint(256);
@@ -351,6 +445,18 @@ pub trait YulFuncCaller:
                 range.max = Elem::from(Concrete::from(U256::from(1)));
                 var.ty.set_range(range).into_expr_err(*loc)?;
                 let node = self.add_node(Node::ContextVar(var));
+                // the return data is copied to memory
+                ctx.forget_memory(self).into_expr_err(*loc)?;
+                if id.name != "staticcall" {
+                    ctx.add_effect(
+                        ContextEffect::LowLevelCall {
+                            loc: *loc,
+                            kind: id.name.clone(),
+                        },
+                        self,
+                    )
+                    .into_expr_err(*loc)?;
+                }
                 ctx.push_expr(ExprRet::Single(node), self)
                     .into_expr_err(*loc)?;
                 Ok(())
@@ -367,6 +473,7 @@ pub trait YulFuncCaller:
                 Ok(())
             }
             "returndatacopy" => {
+                ctx.forget_memory(self).into_expr_err(*loc)?;
                 ctx.push_expr(ExprRet::Multi(vec![]), self)
                     .into_expr_err(*loc)?;
                 Ok(())
@@ -381,57 +488,72 @@ pub trait YulFuncCaller:
                 Ok(())
             }
             "mstore" | "mstore8" => {
-                // TODO: improve this. Right now we are extremely pessimistic and just say we know nothing about memory variables anymore.
-                // We should check if the location is a reference to an existing var and update based on that
-                // @MemoryModel
-                let vars = ctx.local_vars(self).clone();
-                vars.into_iter().try_for_each(|(_name, var)| {
-                    // widen to any  max range
-                    let latest_var = var.latest_version(self);
-                    if matches!(
-                        latest_var.underlying(self).into_expr_err(*loc)?.storage,
-                        Some(StorageLocation::Memory(_))
-                    ) {
-                        let res = latest_var.ty(self).into_expr_err(*loc)?;
-                        if let Some(r) = res.default_range(self).unwrap() {
-                            let new_var = self.advance_var_in_ctx(latest_var, *loc, ctx).unwrap();
-                            let res = new_var.set_range_min(self, r.min).into_expr_err(*loc);
-                            let _ = self.add_if_err(res);
-                            let res = new_var.set_range_max(self, r.max).into_expr_err(*loc);
-                            let _ = self.add_if_err(res);
-                        }
+                if arguments.len() != 2 {
+                    return Err(ExprErr::InvalidFunctionInput(
+                        *loc,
+                        format!(
+                            "Yul function: `{}` expects 2 arguments found: {:?}",
+                            id.name,
+                            arguments.len()
+                        ),
+                    ));
+                }
+
+                let whole_word = id.name == "mstore";
+                self.parse_inputs(ctx, *loc, arguments)?;
+                self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
+                    let Some(inputs) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                        return Err(ExprErr::NoRhs(loc, "Yul `mstore` operation had no inputs".to_string()))
+                    };
+                    if matches!(inputs, ExprRet::CtxKilled(_)) {
+                        ctx.push_expr(inputs, analyzer).into_expr_err(loc)?;
+                        return Ok(());
                     }
-                    Ok(())
-                })?;
-                ctx.push_expr(ExprRet::Multi(vec![]), self)
-                    .into_expr_err(*loc)?;
-                Ok(())
+
+                    inputs.expect_length(2).into_expr_err(loc)?;
+                    let inputs = inputs.as_vec();
+                    analyzer.yul_mstore(ctx, loc, &inputs[0], &inputs[1], whole_word)?;
+                    ctx.push_expr(ExprRet::Multi(vec![]), analyzer)
+                        .into_expr_err(loc)
+                })
             }
             "sstore" => {
-                // TODO: improve this. Right now we are extremely pessimistic and just say we know nothing about storage variables anymore.
-                // We should check if the location is a reference to an existing var and update based on that
-                let vars = ctx.local_vars(self).clone();
-                vars.iter().try_for_each(|(_name, var)| {
-                    // widen to any  max range
-                    let latest_var = var.latest_version(self);
-                    if matches!(
-                        latest_var.underlying(self).into_expr_err(*loc)?.storage,
-                        Some(StorageLocation::Storage(_))
-                    ) {
-                        let res = latest_var.ty(self).into_expr_err(*loc)?;
-                        if let Some(r) = res.default_range(self).unwrap() {
-                            let new_var = self.advance_var_in_ctx(latest_var, *loc, ctx).unwrap();
-                            let res = new_var.set_range_min(self, r.min).into_expr_err(*loc);
-                            let _ = self.add_if_err(res);
-                            let res = new_var.set_range_max(self, r.max).into_expr_err(*loc);
-                            let _ = self.add_if_err(res);
-                        }
+                if arguments.len() != 2 {
+                    return Err(ExprErr::InvalidFunctionInput(
+                        *loc,
+                        format!(
+                            "Yul function: `sstore` expects 2 arguments found: {:?}",
+                            arguments.len()
+                        ),
+                    ));
+                }
+
+                self.parse_ctx_yul_expr(&arguments[0], ctx)?;
+                self.apply_to_edges(ctx, *loc, &|analyzer, ctx, loc| {
+                    let Some(slot) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                        return Err(ExprErr::NoRhs(loc, "Yul `sstore` operation had no slot".to_string()))
+                    };
+                    if matches!(slot, ExprRet::CtxKilled(_)) {
+                        ctx.push_expr(slot, analyzer).into_expr_err(loc)?;
+                        return Ok(());
                     }
-                    Ok(())
-                })?;
-                ctx.push_expr(ExprRet::Multi(vec![]), self)
-                    .into_expr_err(*loc)?;
-                Ok(())
+                    analyzer.record_yul_sink(ctx, loc, SinkKind::SstoreSlot, &slot)?;
+
+                    analyzer.parse_ctx_yul_expr(&arguments[1], ctx)?;
+                    analyzer.apply_to_edges(ctx, loc, &|analyzer, ctx, loc| {
+                        let Some(value) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
+                            return Err(ExprErr::NoRhs(loc, "Yul `sstore` operation had no value".to_string()))
+                        };
+                        if matches!(value, ExprRet::CtxKilled(_)) {
+                            ctx.push_expr(value, analyzer).into_expr_err(loc)?;
+                            return Ok(());
+                        }
+                        analyzer.yul_sstore(ctx, loc, &slot, &value)?;
+                        ctx.push_expr(ExprRet::Multi(vec![]), analyzer)
+                            .into_expr_err(loc)?;
+                        Ok(())
+                    })
+                })
             }
             "balance" => {
                 self.parse_ctx_yul_expr(&arguments[0], ctx)?;
@@ -514,6 +636,7 @@ pub trait YulFuncCaller:
                     let Some(_lhs_paths) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
                         return Err(ExprErr::NoRhs(loc, "Yul `codecopy` operation had no input".to_string()))
                     };
+                    ctx.forget_memory(analyzer).into_expr_err(loc)?;
                     ctx.push_expr(ExprRet::Multi(vec![]), analyzer)
                         .into_expr_err(loc)
                 })
@@ -535,6 +658,7 @@ pub trait YulFuncCaller:
                     let Some(_lhs_paths) = ctx.pop_expr_latest(loc, analyzer).into_expr_err(loc)? else {
                         return Err(ExprErr::NoRhs(loc, "Yul `extcodecopy` operation had no input".to_string()))
                     };
+                    ctx.forget_memory(analyzer).into_expr_err(loc)?;
                     ctx.push_expr(ExprRet::Multi(vec![]), analyzer)
                         .into_expr_err(loc)
                 })
@@ -597,6 +721,434 @@ pub trait YulFuncCaller:
         }
     }
 
+    /// Gets the value of a yul argument as a word, if it is constant
+    fn yul_const_word(&mut self, loc: Loc, arg: &ExprRet) -> Result<Option<U256>, ExprErr> {
+        let (ExprRet::Single(idx) | ExprRet::SingleLiteral(idx)) = arg else {
+            return Ok(None);
+        };
+        let Node::ContextVar(_) = self.node(*idx) else {
+            return Ok(None);
+        };
+        let var = ContextVarNode::from(*idx);
+        if !var.is_const(self).into_expr_err(loc)? {
+            return Ok(None);
+        }
+        Ok(var
+            .evaled_range_min(self)
+            .into_expr_err(loc)?
+            .and_then(|min| min.maybe_concrete())
+            .and_then(|min| min.val.into_u256()))
+    }
+
+    /// Hashes `size` bytes of memory at `offset`. The hash is known if the bytes are the concrete words stored at
+    /// constant offsets, or there are none. If the bytes are whole stored words, the words are recorded as the
+    /// hash's preimage, so a storage slot derived from them can be resolved, see `yul_storage_element`
+    fn yul_keccak256(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        offset: &ExprRet,
+        size: &ExprRet,
+    ) -> Result<ContextVarNode, ExprErr> {
+        let offset = self.yul_const_word(loc, offset)?;
+        let size = self.yul_const_word(loc, size)?;
+        let memory_words = ctx.memory_words(self).into_expr_err(loc)?;
+        let words = match (offset, size) {
+            // every word has to be known, so there can't be more of them than are stored
+            (Some(offset), Some(size))
+                if size % 32 == U256::zero() && size / 32 <= U256::from(memory_words.len()) =>
+            {
+                (0..size.as_usize() / 32)
+                    .map(|i| {
+                        offset
+                            .checked_add(U256::from(i * 32))
+                            .and_then(|word_offset| memory_words.get(&word_offset).copied())
+                    })
+                    .collect::<Option<Vec<_>>>()
+            }
+            _ => None,
+        };
+
+        let mut preimage = words.as_ref().map(|_| vec![]);
+        for word in words.iter().flatten() {
+            let value = if word.is_const(self).into_expr_err(loc)? {
+                word.evaled_range_min(self)
+                    .into_expr_err(loc)?
+                    .and_then(|min| min.maybe_concrete())
+                    .and_then(|min| min.val.into_u256())
+            } else {
+                None
+            };
+            match (value, preimage.as_mut()) {
+                (Some(value), Some(preimage)) => {
+                    let mut bytes = [0u8; 32];
+                    value.to_big_endian(&mut bytes);
+                    preimage.extend(bytes);
+                }
+                _ => preimage = None,
+            }
+        }
+
+        let hash = if size == Some(U256::zero()) {
+            Concrete::DynBytes(vec![]).keccak256()
+        } else {
+            preimage.and_then(|preimage| Concrete::DynBytes(preimage).keccak256())
+        };
+        let var = if let Some(hash) = hash {
+            let cnode = ConcreteNode::from(self.add_node(Node::Concrete(hash)));
+            ContextVar::new_from_concrete(loc, ctx, cnode, self).into_expr_err(loc)?
+        } else {
+            let b = Builtin::Bytes(32);
+            ContextVar::new_from_builtin(loc, self.builtin_or_add(b).into(), self)
+                .into_expr_err(loc)?
+        };
+        let hash = ContextVarNode::from(self.add_node(Node::ContextVar(var)));
+        if let Some(words) = words {
+            ctx.add_hash(hash, words, self).into_expr_err(loc)?;
+        }
+        Ok(hash)
+    }
+
+    /// Applies an `mstore`, or an `mstore8` if it doesn't store a whole word. A store at a constant offset
+    /// replaces the words it overlaps, so that a `keccak256` of them is known. A store at an offset that isn't
+    /// constant could overwrite any word. Solidity's memory variables are widened, as memory they point into may
+    /// have been written
+    fn yul_mstore(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        offset: &ExprRet,
+        value: &ExprRet,
+        whole_word: bool,
+    ) -> Result<(), ExprErr> {
+        if let Some(offset) = self.yul_const_word(loc, offset)? {
+            let len = U256::from(if whole_word { 32 } else { 1 });
+            let word = match value {
+                ExprRet::Single(idx) | ExprRet::SingleLiteral(idx)
+                    if whole_word && matches!(self.node(*idx), Node::ContextVar(_)) =>
+                {
+                    Some(ContextVarNode::from(*idx))
+                }
+                _ => None,
+            };
+            ctx.store_memory(offset, len, word, self)
+                .into_expr_err(loc)?;
+        } else {
+            ctx.forget_memory(self).into_expr_err(loc)?;
+        }
+        self.yul_widen_memory(ctx, loc)
+    }
+
+    /// Widens solidity's memory variables in scope to the full range of their types, as memory they point into
+    /// may have been written
+    fn yul_widen_memory(&mut self, ctx: ContextNode, loc: Loc) -> Result<(), ExprErr> {
+        // TODO: improve this. Right now we are extremely pessimistic and just say we know nothing about memory variables anymore.
+        // We should check if the location is a reference to an existing var and update based on that
+        // @MemoryModel
+        let vars = ctx.local_vars(self).clone();
+        vars.into_iter().try_for_each(|(_name, var)| {
+            // widen to any  max range
+            let latest_var = var.latest_version(self);
+            if matches!(
+                latest_var.underlying(self).into_expr_err(loc)?.storage,
+                Some(StorageLocation::Memory(_))
+            ) {
+                let res = latest_var.ty(self).into_expr_err(loc)?;
+                if let Some(r) = res.default_range(self).unwrap() {
+                    let new_var = self.advance_var_in_ctx(latest_var, loc, ctx).unwrap();
+                    let res = new_var.set_range_min(self, r.min).into_expr_err(loc);
+                    let _ = self.add_if_err(res);
+                    let res = new_var.set_range_max(self, r.max).into_expr_err(loc);
+                    let _ = self.add_if_err(res);
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Gets the value of a constant `sload`/`sstore` slot and the storage layout of the context's contract to
+    /// resolve it against. Returns `None` if the slot isn't constant or the context isn't in a contract
+    fn yul_storage_slots(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        slot: &ExprRet,
+    ) -> Result<Option<(U256, Vec<StorageSlot>)>, ExprErr> {
+        let (ExprRet::Single(idx) | ExprRet::SingleLiteral(idx)) = slot else {
+            return Ok(None);
+        };
+        let Node::ContextVar(_) = self.node(*idx) else {
+            return Ok(None);
+        };
+        let slot_var = ContextVarNode::from(*idx);
+        if !slot_var.is_const(self).into_expr_err(loc)? {
+            return Ok(None);
+        }
+        let Some(slot) = slot_var
+            .evaled_range_min(self)
+            .into_expr_err(loc)?
+            .and_then(|min| min.maybe_concrete())
+            .and_then(|min| min.val.into_u256())
+        else {
+            return Ok(None);
+        };
+        let Some(contract) = ctx.maybe_associated_contract(self).into_expr_err(loc)? else {
+            return Ok(None);
+        };
+        let layout = contract.storage_layout(self).into_expr_err(loc)?;
+        Ok(Some((slot, layout)))
+    }
+
+    /// Pushes a storage variable accessed by its slot, as if it were accessed by name. Returns false without
+    /// pushing anything if a local variable shadows the storage variable's name
+    fn yul_storage_var(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        var: VarNode,
+    ) -> Result<bool, ExprErr> {
+        let name = var.name(self).into_expr_err(loc)?;
+        if let Some(cvar) = ctx.var_by_name_or_recurse(self, &name).into_expr_err(loc)? {
+            if !cvar.is_storage(self).into_expr_err(loc)? {
+                return Ok(false);
+            }
+        }
+        self.variable(&Identifier { loc, name }, ctx, None)?;
+        Ok(true)
+    }
+
+    /// Applies an `sstore` to the storage variables it can write. A store to the slot of a single whole word
+    /// variable, or of a whole word element of a mapping or dynamic array, assigns it, and a store to a slot shared
+    /// by packed variables, or spanned by a struct or fixed size array, widens them. Any other constant slot can
+    /// only hold a value of a mapping or dynamic array, so it widens those. A store to a slot that isn't constant
+    /// widens every storage variable
+    fn yul_sstore(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        slot: &ExprRet,
+        value: &ExprRet,
+    ) -> Result<(), ExprErr> {
+        if let Some(elem) = self.yul_storage_element(ctx, loc, slot)? {
+            return self.match_assign_sides(ctx, loc, &ExprRet::Single(elem.into()), value);
+        }
+        let Some((slot, layout)) = self.yul_storage_slots(ctx, loc, slot)? else {
+            return self.yul_widen_storage(ctx, loc, None);
+        };
+        let at_slot = layout
+            .iter()
+            .filter(|storage_slot| storage_slot.contains(slot))
+            .collect::<Vec<_>>();
+        if let [storage_slot] = &at_slot[..] {
+            if storage_slot.is_full_word() && self.yul_storage_var(ctx, loc, storage_slot.var)? {
+                let Some(lhs) = ctx.pop_expr_latest(loc, self).into_expr_err(loc)? else {
+                    return Err(ExprErr::NoLhs(
+                        loc,
+                        "Yul `sstore` had no storage variable to assign".to_string(),
+                    ));
+                };
+                return self.match_assign_sides(ctx, loc, &lhs, value);
+            }
+        }
+
+        let widened = if at_slot.is_empty() {
+            layout
+                .iter()
+                .filter(|storage_slot| storage_slot.size.is_none())
+                .collect::<Vec<_>>()
+        } else {
+            at_slot
+        };
+        let names = widened
+            .iter()
+            .map(|storage_slot| storage_slot.var.name(self))
+            .collect::<Result<Vec<_>, _>>()
+            .into_expr_err(loc)?;
+        self.yul_widen_storage(ctx, loc, Some(&names))
+    }
+
+    /// Resolves a slot derived from the slot of a mapping or dynamic array of whole words to the element it holds,
+    /// as solidity lays them out: the value of a mapping at a key is at `keccak256(key . slot)` and the element of
+    /// a dynamic array at an index is at `keccak256(slot) + index`. Returns `None` if the slot isn't derived from a
+    /// hash of known memory like that
+    fn yul_storage_element(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        slot: &ExprRet,
+    ) -> Result<Option<ContextVarNode>, ExprErr> {
+        let (ExprRet::Single(idx) | ExprRet::SingleLiteral(idx)) = slot else {
+            return Ok(None);
+        };
+        let Node::ContextVar(_) = self.node(*idx) else {
+            return Ok(None);
+        };
+        let slot = ContextVarNode::from(*idx);
+
+        // the slot is either a hash, or a hash plus an index
+        let (words, index) = match self.yul_hash_preimage(ctx, loc, slot)? {
+            Some(words) => (words, None),
+            None => {
+                let mut found = None;
+                for var in self.yul_aliases(loc, slot)? {
+                    let Some(TmpConstruction {
+                        lhs,
+                        op: RangeOp::Add(_),
+                        rhs: Some(rhs),
+                    }) = var.tmp_of(self).into_expr_err(loc)?
+                    else {
+                        continue;
+                    };
+                    if let Some(words) = self.yul_hash_preimage(ctx, loc, lhs)? {
+                        found = Some((words, Some(rhs)));
+                    } else if let Some(words) = self.yul_hash_preimage(ctx, loc, rhs)? {
+                        found = Some((words, Some(lhs)));
+                    }
+                    break;
+                }
+                let Some(found) = found else {
+                    return Ok(None);
+                };
+                found
+            }
+        };
+        let (elements, base, key) = match (&words[..], index) {
+            ([key, base], None) => (StorageElements::Mapping, *base, *key),
+            ([base], Some(index)) => (StorageElements::DynamicArray, *base, index),
+            ([base], None) => {
+                let cnode =
+                    ConcreteNode::from(self.add_node(Node::Concrete(Concrete::from(U256::zero()))));
+                let zero =
+                    ContextVar::new_from_concrete(loc, ctx, cnode, self).into_expr_err(loc)?;
+                let zero = ContextVarNode::from(self.add_node(Node::ContextVar(zero)));
+                (StorageElements::DynamicArray, *base, zero)
+            }
+            _ => return Ok(None),
+        };
+
+        let Some(base) = self.yul_const_word(loc, &ExprRet::Single(base.into()))? else {
+            return Ok(None);
+        };
+        let Some(contract) = ctx.maybe_associated_contract(self).into_expr_err(loc)? else {
+            return Ok(None);
+        };
+        let layout = contract.storage_layout(self).into_expr_err(loc)?;
+        let Some(storage_slot) = layout
+            .into_iter()
+            .find(|storage_slot| storage_slot.slot == base && storage_slot.size.is_none())
+        else {
+            return Ok(None);
+        };
+        if storage_slot.full_word_elements(self).into_expr_err(loc)? != Some(elements)
+            || !self.yul_storage_var(ctx, loc, storage_slot.var)?
+        {
+            return Ok(None);
+        }
+        let Some(parent) = ctx.pop_expr_latest(loc, self).into_expr_err(loc)? else {
+            return Err(ExprErr::NoLhs(
+                loc,
+                "Yul storage access had no storage variable".to_string(),
+            ));
+        };
+        let parent = ContextVarNode::from(parent.expect_single().into_expr_err(loc)?);
+        // a raw storage access past the end of an array doesn't revert, so the index isn't required to be in bounds
+        let elem = self.index_var(ctx, loc, parent.latest_version(self), key)?;
+        Ok(Some(elem))
+    }
+
+    /// Gets the words of memory that were hashed into a value, following assignments of it back to the hash
+    fn yul_hash_preimage(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        var: ContextVarNode,
+    ) -> Result<Option<Vec<ContextVarNode>>, ExprErr> {
+        for var in self.yul_aliases(loc, var)? {
+            if let Some(words) = ctx.hash_preimage(var, self).into_expr_err(loc)? {
+                return Ok(Some(words));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Gets a variable and the variables it was assigned from, most recent first
+    fn yul_aliases(
+        &mut self,
+        loc: Loc,
+        var: ContextVarNode,
+    ) -> Result<Vec<ContextVarNode>, ExprErr> {
+        let mut aliases = vec![var];
+        let mut var = var;
+        loop {
+            match (
+                var.range_min(self).into_expr_err(loc)?,
+                var.range_max(self).into_expr_err(loc)?,
+            ) {
+                (Some(Elem::Dynamic(min)), Some(Elem::Dynamic(max))) if min.idx == max.idx => {
+                    var = ContextVarNode::from(min.idx);
+                    if aliases.contains(&var) {
+                        return Ok(aliases);
+                    }
+                    aliases.push(var);
+                }
+                _ => return Ok(aliases),
+            }
+        }
+    }
+
+    /// Widens the storage variables the context has accessed to the full range of their types, or only the ones
+    /// with the given names
+    fn yul_widen_storage(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        names: Option<&[String]>,
+    ) -> Result<(), ExprErr> {
+        let vars = ctx.local_vars(self).clone();
+        vars.iter().try_for_each(|(name, var)| {
+            if names.is_some_and(|names| !names.contains(name)) {
+                return Ok(());
+            }
+            // widen to any  max range
+            let latest_var = var.latest_version(self);
+            if matches!(
+                latest_var.underlying(self).into_expr_err(loc)?.storage,
+                Some(StorageLocation::Storage(_))
+            ) {
+                let res = latest_var.ty(self).into_expr_err(loc)?;
+                if let Some(r) = res.default_range(self).unwrap() {
+                    let new_var = self.advance_var_in_ctx(latest_var, loc, ctx).unwrap();
+                    let res = new_var.set_range_min(self, r.min).into_expr_err(loc);
+                    let _ = self.add_if_err(res);
+                    let res = new_var.set_range_max(self, r.max).into_expr_err(loc);
+                    let _ = self.add_if_err(res);
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Records the value a yul builtin passes into an operation a caller shouldn't control
+    fn record_yul_sink(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        kind: SinkKind,
+        input: &ExprRet,
+    ) -> Result<(), ExprErr> {
+        if let ExprRet::Single(idx) | ExprRet::SingleLiteral(idx) = input {
+            if let Node::ContextVar(_) = self.node(*idx) {
+                let sink = ContextSink {
+                    loc,
+                    kind,
+                    var: ContextVarNode::from(*idx),
+                };
+                ctx.add_sink(sink, self).into_expr_err(loc)?;
+            }
+        }
+        Ok(())
+    }
+
     // fn byte_index(&mut self, var: ExprRet, index: ExprRet) -> Result<ExprRet, ExprErr> {
     //     match (var, index) {
     //         (ExprRet::Single(var_idx)
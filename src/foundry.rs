@@ -0,0 +1,193 @@
+use crate::resolver::hardhat_sources;
+use crate::try_parse_remappings;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The subset of a `foundry.toml` profile that is relevant to analysis
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundryConfig {
+    /// The project root, i.e. the directory containing `foundry.toml`
+    pub root: PathBuf,
+    /// The contracts directory, relative to the root
+    pub src: PathBuf,
+    /// The test directory, relative to the root
+    pub test: PathBuf,
+    /// The script directory, relative to the root
+    pub script: PathBuf,
+    /// The library directories, relative to the root
+    pub libs: Vec<PathBuf>,
+    /// Remappings explicitly set in `foundry.toml`
+    pub remappings: Vec<(String, String)>,
+}
+
+impl Default for FoundryConfig {
+    fn default() -> Self {
+        Self {
+            root: Default::default(),
+            src: PathBuf::from("src"),
+            test: PathBuf::from("test"),
+            script: PathBuf::from("script"),
+            libs: vec![PathBuf::from("lib")],
+            remappings: vec![],
+        }
+    }
+}
+
+impl FoundryConfig {
+    /// Reads the `foundry.toml` in `root`, using the profile set by the `FOUNDRY_PROFILE`
+    /// environment variable (falling back to `default`). If there is no `foundry.toml` but there is a
+    /// hardhat config, hardhat's layout is used (dependencies are resolved from `node_modules`).
+    /// Otherwise foundry's default layout is assumed.
+    pub fn from_root(root: &Path) -> Result<Self, String> {
+        let profile = std::env::var("FOUNDRY_PROFILE").unwrap_or_else(|_| "default".to_string());
+        let mut config = FoundryConfig {
+            root: root.to_path_buf(),
+            ..Default::default()
+        };
+
+        let toml_path = root.join("foundry.toml");
+        if !toml_path.exists() {
+            if let Some(sources) = hardhat_sources(root) {
+                config.src = sources;
+                config.libs = vec![];
+            }
+            return Ok(config);
+        }
+
+        let contents = fs::read_to_string(&toml_path).map_err(|e| e.to_string())?;
+        let parsed: toml::Value = contents
+            .parse()
+            .map_err(|e: toml::de::Error| e.to_string())?;
+        let profiles = parsed.get("profile");
+        // non-default profiles inherit from the default profile
+        let mut layers = vec![profiles.and_then(|p| p.get("default"))];
+        if profile != "default" {
+            layers.push(profiles.and_then(|p| p.get(&profile)));
+        }
+
+        for layer in layers.into_iter().flatten() {
+            if let Some(src) = layer.get("src").and_then(|v| v.as_str()) {
+                config.src = PathBuf::from(src);
+            }
+            if let Some(test) = layer.get("test").and_then(|v| v.as_str()) {
+                config.test = PathBuf::from(test);
+            }
+            if let Some(script) = layer.get("script").and_then(|v| v.as_str()) {
+                config.script = PathBuf::from(script);
+            }
+            if let Some(libs) = layer.get("libs").and_then(|v| v.as_array()) {
+                config.libs = libs
+                    .iter()
+                    .filter_map(|lib| lib.as_str())
+                    .map(PathBuf::from)
+                    .collect();
+            }
+            if let Some(remappings) = layer.get("remappings").and_then(|v| v.as_array()) {
+                config.remappings = try_parse_remappings(
+                    &remappings
+                        .iter()
+                        .filter_map(|remapping| remapping.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                )?;
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// All remappings for the project, in priority order: those set in `foundry.toml`, then those in a
+    /// `remappings.txt` at the root, then those derived from the library directories. Errors if
+    /// `remappings.txt` contains an invalid remapping.
+    pub fn all_remappings(&self) -> Result<Vec<(String, String)>, String> {
+        let mut remappings = self.remappings.clone();
+        if let Ok(remappings_file) = fs::read_to_string(self.root.join("remappings.txt")) {
+            remappings.extend(try_parse_remappings(&remappings_file)?);
+        }
+        remappings.extend(self.derived_remappings());
+
+        let mut seen = vec![];
+        remappings.retain(|(name, _)| {
+            if seen.contains(name) {
+                false
+            } else {
+                seen.push(name.clone());
+                true
+            }
+        });
+        Ok(remappings)
+    }
+
+    /// Derives remappings the same way `forge remappings` does: every directory in a library
+    /// directory is remapped by name to its `src` directory if it has one, otherwise to itself.
+    /// Nested library directories of dependencies are searched as well.
+    pub fn derived_remappings(&self) -> Vec<(String, String)> {
+        let mut remappings = vec![];
+        self.libs
+            .iter()
+            .for_each(|lib| Self::derive_lib_remappings(&self.root, lib, &mut remappings));
+        remappings
+    }
+
+    fn derive_lib_remappings(root: &Path, lib: &Path, remappings: &mut Vec<(String, String)>) {
+        let Ok(entries) = fs::read_dir(root.join(lib)) else {
+            return;
+        };
+        let mut dirs = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect::<Vec<_>>();
+        dirs.sort();
+
+        dirs.into_iter().for_each(|dir| {
+            let Some(name) = dir.file_name().and_then(|name| name.to_str()) else {
+                return;
+            };
+            if name.starts_with('.') {
+                return;
+            }
+            let rel = lib.join(name);
+            let target = if dir.join("src").is_dir() {
+                rel.join("src")
+            } else {
+                rel.clone()
+            };
+            remappings.push((format!("{name}/"), format!("{}/", target.to_string_lossy())));
+
+            // dependencies of dependencies
+            Self::derive_lib_remappings(root, &rel.join("lib"), remappings);
+        });
+    }
+
+    /// The solidity files of the project. Files in the `test` and `script` directories are
+    /// only included if `include_tests` is set.
+    pub fn source_files(&self, include_tests: bool) -> Vec<PathBuf> {
+        let mut files = vec![];
+        Self::sol_files_in(&self.root.join(&self.src), &mut files);
+        if include_tests {
+            Self::sol_files_in(&self.root.join(&self.test), &mut files);
+            Self::sol_files_in(&self.root.join(&self.script), &mut files);
+        }
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    fn sol_files_in(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .for_each(|path| {
+                if path.is_dir() {
+                    Self::sol_files_in(&path, files);
+                } else if path.extension().map(|ext| ext == "sol").unwrap_or(false) {
+                    files.push(path);
+                }
+            });
+    }
+}
@@ -8,6 +8,8 @@ use shared::context::ContextNode;
 use shared::context::ExprRet;
 use shared::context::{Context, ContextEdge};
 use shared::nodes::*;
+use shared::range::SolcRange;
+use shared::smt::SmtSolver;
 use shared::{Edge, Node, NodeIdx};
 use solang_parser::diagnostics::Diagnostic;
 use solang_parser::helpers::CodeLocation;
@@ -19,11 +21,12 @@ use std::ffi::OsString;
 use std::path::Path;
 
 use solang_parser::pt::{
-    ContractDefinition, ContractPart, EnumDefinition, ErrorDefinition, Expression,
-    FunctionDefinition, FunctionTy, SourceUnit, SourceUnitPart, StructDefinition, TypeDefinition,
-    Using, UsingList, VariableDefinition,
+    ContractDefinition, ContractPart, ContractTy, EnumDefinition, ErrorDefinition, EventDefinition,
+    Expression, FunctionDefinition, FunctionTy, SourceUnit, SourceUnitPart, StructDefinition,
+    TypeDefinition, Using, UsingList, VariableDefinition,
 };
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::{collections::HashMap, fs};
 
 use ariadne::{Cache, Color, Config, Fmt, Label, Report, ReportKind, Span};
@@ -32,8 +35,13 @@ use petgraph::{graph::*, Directed};
 mod builtin_fns;
 
 pub mod context;
+pub mod foundry;
+pub mod resolver;
+pub mod standard_json;
 // pub mod range;
 use context::*;
+use foundry::FoundryConfig;
+use resolver::*;
 pub use shared;
 
 #[derive(Debug, Clone, Default)]
@@ -65,6 +73,12 @@ pub struct Analyzer {
     pub root: PathBuf,
     /// Solidity remappings - as would be passed into the solidity compiler
     pub remappings: Vec<(String, String)>,
+    /// Additional directories to resolve direct imports against - as would be passed into the solidity compiler via `--include-path`
+    pub include_paths: Vec<PathBuf>,
+    /// User provided import resolvers, tried after remappings and relative imports. See [Analyzer::import_resolvers]
+    pub extra_import_resolvers: Vec<Arc<dyn ImportResolver>>,
+    /// In-memory sources by source unit name (i.e. from a solc standard-JSON input). When set, the filesystem is not used
+    pub virtual_srcs: BTreeMap<PathBuf, String>,
     /// Imported sources - the canonicalized string to the entry source element index
     pub imported_srcs: BTreeMap<OsString, Option<NodeIdx>>,
     /// Since we use a staged approach to analysis, we analyze all user types first then go through and patch up any missing or unresolved
@@ -96,6 +110,27 @@ pub struct Analyzer {
     pub max_depth: usize,
     /// The maximum number of forks throughout the lifetime of the analysis.
     pub max_width: usize,
+    /// The maximum number of loop iterations to unroll. Loops that may run longer have the variables modified in
+    /// their body widened instead
+    pub max_loop_unroll: usize,
+    /// Whether to apply the joined return values, storage writes and required conditions of an already analyzed
+    /// internal function at its call sites instead of evaluating its body again. Only functions analyzed before the
+    /// call is reached are summarized, so which calls are depends on the order the functions are declared in
+    pub summarize_calls: bool,
+    /// Whether to analyze the constructors first and start every other function from the storage they and the
+    /// variables' initializers leave behind, rather than from the full range of each variable's type. Writes by
+    /// other functions aren't accounted for, so this models the first call to a freshly deployed contract
+    pub storage_from_constructor: bool,
+    /// The range each storage variable has when a function is called on a deployed contract. Only populated
+    /// with [Analyzer::storage_from_constructor]
+    pub storage_entry: BTreeMap<VarNode, SolcRange>,
+    /// An SMT solver to check the dependencies of a context after each `require`, killing the context as
+    /// unreachable if they are unsatisfiable. Without one, only the interval reasoning of the ranges kills contexts
+    pub smt_solver: Option<SmtSolver>,
+    /// Whether every contract is assumed to be known, so that an external call through a contract or interface
+    /// type is resolved to the implementations of every deployable contract inheriting it. Each implementation is
+    /// called in its own fork of the calling context instead of returning unconstrained values
+    pub closed_world: bool,
     /// Dummy function used during parsing to attach contexts to for more complex first-pass parsing (i.e. before `final_pass`)
     pub parse_fn: FunctionNode,
 }
@@ -105,6 +140,9 @@ impl Default for Analyzer {
         let mut a = Self {
             root: Default::default(),
             remappings: Default::default(),
+            include_paths: Default::default(),
+            extra_import_resolvers: Default::default(),
+            virtual_srcs: Default::default(),
             imported_srcs: Default::default(),
             final_pass_items: Default::default(),
             file_no: 0,
@@ -120,6 +158,12 @@ impl Default for Analyzer {
             expr_errs: Default::default(),
             max_depth: 1024,
             max_width: 2_i32.pow(14) as usize,
+            max_loop_unroll: 10,
+            summarize_calls: false,
+            storage_from_constructor: false,
+            storage_entry: Default::default(),
+            smt_solver: None,
+            closed_world: false,
             parse_fn: NodeIdx::from(0).into(),
         };
         a.builtin_fn_inputs = builtin_fns::builtin_fns_inputs(&mut a);
@@ -175,6 +219,30 @@ impl AnalyzerLike for Analyzer {
         self.max_width
     }
 
+    fn max_loop_unroll(&self) -> usize {
+        self.max_loop_unroll
+    }
+
+    fn summarize_calls(&self) -> bool {
+        self.summarize_calls
+    }
+
+    fn storage_from_constructor(&self) -> bool {
+        self.storage_from_constructor
+    }
+
+    fn storage_entry(&self) -> &BTreeMap<VarNode, SolcRange> {
+        &self.storage_entry
+    }
+
+    fn smt_solver(&self) -> Option<&SmtSolver> {
+        self.smt_solver.as_ref()
+    }
+
+    fn closed_world(&self) -> bool {
+        self.closed_world
+    }
+
     fn add_expr_err(&mut self, err: ExprErr) {
         if !self.expr_errs.contains(&err) {
             self.expr_errs.push(err);
@@ -380,13 +448,54 @@ impl Analyzer {
             .map_err(|err| err.to_string())
             .expect("Remappings file not found");
 
-        self.remappings = remappings_file
-            .lines()
-            .map(|x| x.trim())
-            .filter(|x| !x.is_empty())
-            .map(|x| x.split_once('=').expect("Invalid remapping"))
-            .map(|(name, path)| (name.to_owned(), path.to_owned()))
-            .collect();
+        self.remappings = parse_remappings(&remappings_file);
+    }
+
+    /// Sets the root and remappings based on a foundry project
+    pub fn set_foundry_project(&mut self, config: &FoundryConfig) -> Result<(), String> {
+        self.root = config.root.clone();
+        self.remappings = config.all_remappings()?;
+        Ok(())
+    }
+
+    /// Parses multiple files into this analyzer, sharing imports between them. Returns the source unit
+    /// of each file that was passed in (files that were already imported by a previous file are not
+    /// reparsed) and all sources, including imports, or an error if one of the files could not be read.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn parse_project(
+        &mut self,
+        paths: &[PathBuf],
+    ) -> Result<(Vec<NodeIdx>, Vec<(Option<NodeIdx>, String, String, usize)>), String> {
+        let mut entries = vec![];
+        let mut all_sources = vec![];
+        for path in paths.iter() {
+            let canonical = self
+                .canonical_src_path(path)
+                .ok_or_else(|| format!("Could not find file: {path:?}"))?;
+            let canonical_str_path = canonical.as_os_str();
+            if let Some(other_entry) = self.imported_srcs.get(canonical_str_path) {
+                entries.extend(other_entry);
+                continue;
+            }
+
+            let sol = self
+                .read_src(&canonical)
+                .ok_or_else(|| format!("Could not read file: {canonical:?}"))?;
+            if !all_sources.is_empty() {
+                self.file_no += 1;
+            }
+            let file_no = self.file_no;
+            // breaks recursion issues
+            self.imported_srcs.insert(canonical_str_path.into(), None);
+            let (maybe_entry, inner_sources) = self.parse(&sol, path, false);
+            self.imported_srcs
+                .insert(canonical_str_path.into(), maybe_entry);
+            entries.extend(maybe_entry);
+            all_sources.extend(inner_sources);
+            all_sources.push((maybe_entry, path.to_str().unwrap().to_owned(), sol, file_no));
+        }
+        self.final_pass();
+        Ok((entries, all_sources))
     }
 
     pub fn print_errors(
@@ -481,13 +590,69 @@ impl Analyzer {
             });
         });
 
-        elems.into_iter().for_each(|final_pass_item| {
-            final_pass_item.funcs.into_iter().for_each(|func| {
-                if let Some(body) = &func.underlying(self).unwrap().body.clone() {
-                    self.parse_ctx_statement(body, false, Some(func));
-                }
-            });
+        let mut funcs = elems
+            .into_iter()
+            .flat_map(|final_pass_item| final_pass_item.funcs)
+            .collect::<Vec<_>>();
+        if self.storage_from_constructor {
+            // the other functions start from the storage the constructors leave behind
+            let (constructors, rest): (Vec<_>, Vec<_>) = funcs
+                .into_iter()
+                .partition(|func| func.underlying(self).unwrap().ty == FunctionTy::Constructor);
+            constructors
+                .into_iter()
+                .for_each(|func| self.analyze_func_body(func));
+            self.set_storage_entry();
+            funcs = rest;
+        }
+        funcs
+            .into_iter()
+            .for_each(|func| self.analyze_func_body(func));
+    }
+
+    /// Analyzes the body of a function, if it has one
+    fn analyze_func_body(&mut self, func: FunctionNode) {
+        if let Some(body) = &func.underlying(self).unwrap().body.clone() {
+            self.parse_ctx_statement(body, false, Some(func));
+        }
+    }
+
+    /// Sets the range each storage variable starts with to the union of its ranges in every deployable contract
+    /// that has it. A variable is left with the full range of its type if any of them doesn't know its range
+    fn set_storage_entry(&mut self) {
+        let contracts = self
+            .graph
+            .node_indices()
+            .filter(|idx| {
+                matches!(
+                    self.node(*idx),
+                    Node::Contract(Contract {
+                        ty: ContractTy::Contract(_),
+                        ..
+                    })
+                )
+            })
+            .map(ContractNode::from)
+            .collect::<Vec<_>>();
+
+        let mut deployed: BTreeMap<VarNode, Vec<Option<SolcRange>>> = BTreeMap::default();
+        contracts.into_iter().for_each(|contract| {
+            let loc = contract.loc(self).unwrap();
+            let res = contract.deployed_storage(self).into_expr_err(loc);
+            if let Some(storage) = self.add_if_err(res) {
+                storage.into_iter().for_each(|(var, range)| {
+                    deployed.entry(var).or_default().push(range);
+                });
+            }
         });
+
+        self.storage_entry = deployed
+            .into_iter()
+            .filter_map(|(var, ranges)| {
+                let range = join_ranges(ranges, self).ok()??;
+                Some((var, range))
+            })
+            .collect();
     }
 
     #[tracing::instrument(level = "trace", skip_all)]
@@ -593,7 +758,10 @@ impl Analyzer {
                 let node = self.parse_ty_def(def);
                 self.add_edge(node, sup_node, Edge::Ty);
             }
-            EventDefinition(_def) => todo!(),
+            EventDefinition(def) => {
+                let node = self.parse_event_def(def);
+                self.add_edge(node, sup_node, Edge::Event);
+            }
             Annotation(_anno) => todo!(),
             Using(using) => usings.push((*using.clone(), parent)),
             StraySemicolon(_loc) => todo!(),
@@ -605,6 +773,57 @@ impl Analyzer {
         (sup_node, func_nodes, usings, inherits, vars)
     }
 
+    /// The import resolvers, in the order they are tried: remappings, relative imports, user provided resolvers,
+    /// the root and include paths, and finally `node_modules`. If there are in-memory sources, imports are only
+    /// resolved against them.
+    pub fn import_resolvers(&self) -> Vec<Arc<dyn ImportResolver>> {
+        if !self.virtual_srcs.is_empty() {
+            return vec![Arc::new(VirtualResolver::new(
+                self.virtual_srcs.keys().cloned().collect(),
+                self.remappings.clone(),
+            ))];
+        }
+
+        let mut resolvers: Vec<Arc<dyn ImportResolver>> = vec![
+            Arc::new(RemappingResolver::new(
+                self.root.clone(),
+                self.remappings.clone(),
+            )),
+            Arc::new(RelativeResolver),
+        ];
+        resolvers.extend(self.extra_import_resolvers.iter().cloned());
+        let mut include_paths = vec![self.root.clone()];
+        include_paths.extend(self.include_paths.iter().cloned());
+        resolvers.push(Arc::new(IncludePathResolver::new(include_paths)));
+        resolvers.push(Arc::new(NodeModulesResolver::new(self.root.clone())));
+        resolvers
+    }
+
+    /// Canonicalizes the path of a source. In-memory sources are already canonical
+    pub fn canonical_src_path(&self, path: &Path) -> Option<PathBuf> {
+        if self.virtual_srcs.contains_key(path) {
+            Some(path.to_path_buf())
+        } else {
+            fs::canonicalize(path).ok()
+        }
+    }
+
+    /// Reads a source, preferring in-memory sources over the filesystem
+    pub fn read_src(&self, path: &Path) -> Option<String> {
+        if let Some(sol) = self.virtual_srcs.get(path) {
+            Some(sol.clone())
+        } else {
+            fs::read_to_string(path).ok()
+        }
+    }
+
+    /// Resolves an import path, imported from the file at `current_path`, to a file on disk
+    pub fn resolve_import(&self, import_path: &str, current_path: &Path) -> Option<PathBuf> {
+        self.import_resolvers()
+            .iter()
+            .find_map(|resolver| resolver.resolve(import_path, current_path))
+    }
+
     #[tracing::instrument(level = "trace", skip_all)]
     pub fn parse_import(
         &mut self,
@@ -612,137 +831,64 @@ impl Analyzer {
         current_path: &Path,
         parent: NodeIdx,
     ) -> Vec<(Option<NodeIdx>, String, String, usize)> {
-        match import {
+        let import_path = match import {
             Import::Plain(import_path, _) => {
                 tracing::trace!("parse_import, path: {:?}", import_path);
-                let remapping = self
-                    .remappings
-                    .iter()
-                    .find(|x| import_path.string.starts_with(&x.0));
-
-                let remapped = if let Some((name, path)) = remapping {
-                    self.root.join(path).join(
-                        import_path
-                            .string
-                            .replacen(name, "", 1)
-                            .trim_start_matches('/'),
-                    )
-                } else {
-                    current_path
-                        .parent()
-                        .unwrap()
-                        .join(import_path.string.clone())
-                };
-
-                let canonical = fs::canonicalize(&remapped)
-                    .unwrap_or_else(|_| panic!(
-                            "Could not find file: {remapped:?}{}",
-                            if self.remappings.is_empty() {
-                                ". It looks like you didn't pass in any remappings. Try adding the `--remappings ./path/to/remappings.txt` to the command line input"
-                            } else { "" }
-                        )
-                    );
-                let canonical_str_path = canonical.as_os_str();
-                if let Some(other_entry) = self.imported_srcs.get(canonical_str_path) {
-                    if let Some(o_e) = other_entry {
-                        self.add_edge(*o_e, parent, Edge::Import);
-                    }
-                    return vec![];
-                }
-
-                let sol = fs::read_to_string(&canonical).unwrap_or_else(|_| {
-                    panic!(
-                        "Could not find file for dependency: {canonical:?}{}",
-                        if self.remappings.is_empty() {
-                            ". It looks like you didn't pass in any remappings. Try adding the `--remappings ./path/to/remappings.txt` to the command line input (where `remappings.txt` is the output of `forge remappings > remappings.txt`)"
-                        } else { "" }
-                    )
-                });
-                self.file_no += 1;
-                let file_no = self.file_no;
-                // breaks recursion issues
-                self.imported_srcs.insert(canonical_str_path.into(), None);
-                let (maybe_entry, mut inner_sources) = self.parse(&sol, &remapped, false);
-                self.imported_srcs
-                    .insert(canonical_str_path.into(), maybe_entry);
-                if let Some(other_entry) = maybe_entry {
-                    self.add_edge(other_entry, parent, Edge::Import);
-                }
-
-                inner_sources.push((
-                    maybe_entry,
-                    remapped.to_str().unwrap().to_owned(),
-                    sol.to_string(),
-                    file_no,
-                ));
-                inner_sources
+                import_path
+            }
+            Import::GlobalSymbol(import_path, _, _) => {
+                tracing::trace!("parse_import, path: {:?}, GlobalSymbol", import_path);
+                import_path
             }
             Import::Rename(import_path, _elems, _) => {
                 tracing::trace!("parse_import, path: {:?}, Rename", import_path);
-                let remapping = self
-                    .remappings
-                    .iter()
-                    .find(|x| import_path.string.starts_with(&x.0));
-
-                let remapped = if let Some((name, path)) = remapping {
-                    self.root.join(path).join(
-                        import_path
-                            .string
-                            .replacen(name, "", 1)
-                            .trim_start_matches('/'),
-                    )
-                } else {
-                    current_path
-                        .parent()
-                        .unwrap()
-                        .join(import_path.string.clone())
-                };
-
-                let canonical = fs::canonicalize(&remapped).unwrap_or_else(|_| panic!(
-                        "Could not find file: {remapped:?}{}",
-                        if self.remappings.is_empty() {
-                            ". It looks like you didn't pass in any remappings. Try adding the `--remappings ./path/to/remappings.txt` to the command line input"
-                        } else { "" }
-                    )
-                );
-                let canonical_str_path = canonical.as_os_str();
-                if let Some(other_entry) = self.imported_srcs.get(canonical_str_path) {
-                    if let Some(o_e) = other_entry {
-                        self.add_edge(*o_e, parent, Edge::Import);
-                    }
-                    return vec![];
-                }
+                import_path
+            }
+        };
 
-                let sol = fs::read_to_string(&canonical).unwrap_or_else(|_| {
-                    panic!(
-                        "Could not find file for dependency: {canonical:?}{}",
-                        if self.remappings.is_empty() {
-                            ". It looks like you didn't pass in any remappings. Try adding the `--remappings ./path/to/remappings.txt` to the command line input"
-                        } else { "" }
-                    )
-                });
-                self.file_no += 1;
-                let file_no = self.file_no;
-
-                // breaks recursion issues
-                self.imported_srcs.insert(canonical_str_path.into(), None);
-                let (maybe_entry, mut inner_sources) = self.parse(&sol, &remapped, false);
-                self.imported_srcs
-                    .insert(canonical_str_path.into(), maybe_entry);
-                if let Some(other_entry) = maybe_entry {
-                    self.add_edge(other_entry, parent, Edge::Import);
-                }
+        let remapped = self
+            .resolve_import(&import_path.string, current_path)
+            .unwrap_or_else(|| panic!(
+                    "Could not find file: {:?}{}",
+                    import_path.string,
+                    if self.remappings.is_empty() {
+                        ". It looks like you didn't pass in any remappings. Try adding the `--remappings ./path/to/remappings.txt` to the command line input"
+                    } else { "" }
+                )
+            );
 
-                inner_sources.push((
-                    maybe_entry,
-                    remapped.to_str().unwrap().to_owned(),
-                    sol.to_string(),
-                    file_no,
-                ));
-                inner_sources
+        let canonical = self
+            .canonical_src_path(&remapped)
+            .unwrap_or_else(|| panic!("Could not find file: {remapped:?}"));
+        let canonical_str_path = canonical.as_os_str();
+        if let Some(other_entry) = self.imported_srcs.get(canonical_str_path) {
+            if let Some(o_e) = other_entry {
+                self.add_edge(*o_e, parent, Edge::Import);
             }
-            e => todo!("import {:?}", e),
+            return vec![];
+        }
+
+        let sol = self
+            .read_src(&canonical)
+            .unwrap_or_else(|| panic!("Could not find file for dependency: {canonical:?}"));
+        self.file_no += 1;
+        let file_no = self.file_no;
+        // breaks recursion issues
+        self.imported_srcs.insert(canonical_str_path.into(), None);
+        let (maybe_entry, mut inner_sources) = self.parse(&sol, &remapped, false);
+        self.imported_srcs
+            .insert(canonical_str_path.into(), maybe_entry);
+        if let Some(other_entry) = maybe_entry {
+            self.add_edge(other_entry, parent, Edge::Import);
         }
+
+        inner_sources.push((
+            maybe_entry,
+            remapped.to_str().unwrap().to_owned(),
+            sol.to_string(),
+            file_no,
+        ));
+        inner_sources
     }
 
     // #[tracing::instrument(name = "parse_contract_def", skip_all, fields(name = format!("{:?}", contract_def.name)))]
@@ -824,7 +970,10 @@ impl Analyzer {
                 let node = self.parse_ty_def(def);
                 self.add_edge(node, con_node, Edge::Ty);
             }
-            EventDefinition(_def) => {}
+            EventDefinition(def) => {
+                let node = self.parse_event_def(def);
+                self.add_edge(node, con_node, Edge::Event);
+            }
             Annotation(_anno) => todo!(),
             Using(using) => usings.push((*using.clone(), con_node.0.into())),
             StraySemicolon(_loc) => todo!(),
@@ -998,14 +1147,26 @@ impl Analyzer {
     pub fn parse_err_def(&mut self, err_def: &ErrorDefinition) -> ErrorNode {
         tracing::trace!("Parsing error {:?}", err_def);
         let err_node = ErrorNode(self.add_node(Error::from(err_def.clone())).index());
-        err_def.fields.iter().for_each(|field| {
-            let param = ErrorParam::new(self, field.clone());
+        err_def.fields.iter().enumerate().for_each(|(i, field)| {
+            let param = ErrorParam::new(self, field.clone(), i);
             let field_node = self.add_node(param);
             self.add_edge(field_node, err_node, Edge::ErrorParam);
         });
         err_node
     }
 
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn parse_event_def(&mut self, event_def: &EventDefinition) -> EventNode {
+        tracing::trace!("Parsing event {:?}", event_def.name);
+        let event_node = EventNode(self.add_node(Event::from(event_def.clone())).index());
+        event_def.fields.iter().enumerate().for_each(|(i, field)| {
+            let param = EventParam::new(self, field.clone(), i);
+            let field_node = self.add_node(param);
+            self.add_edge(field_node, event_node, Edge::EventParam);
+        });
+        event_node
+    }
+
     #[tracing::instrument(level = "trace", skip_all)]
     pub fn parse_func_def(
         &mut self,
@@ -1107,6 +1268,25 @@ impl Analyzer {
     }
 }
 
+/// Parses remappings in the format output by `forge remappings`, i.e. `name=path` per line
+pub fn parse_remappings(remappings: &str) -> Vec<(String, String)> {
+    try_parse_remappings(remappings).expect("Invalid remapping")
+}
+
+/// Parses remappings in the format output by `forge remappings`, returning an error for a line without a `=`
+pub fn try_parse_remappings(remappings: &str) -> Result<Vec<(String, String)>, String> {
+    remappings
+        .lines()
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty())
+        .map(|x| {
+            x.split_once('=')
+                .map(|(name, path)| (name.to_owned(), path.to_owned()))
+                .ok_or_else(|| format!("Invalid remapping: \"{x}\""))
+        })
+        .collect()
+}
+
 /// Print the report of parser's diagnostics
 pub fn print_diagnostics_report(
     content: &str,
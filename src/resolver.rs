@@ -0,0 +1,193 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Resolves the path string of an `import` statement to a file on disk
+///
+/// The [Analyzer](crate::Analyzer) tries each of its resolvers in order, using the first path that is returned.
+/// Resolvers should only return paths that exist.
+pub trait ImportResolver: std::fmt::Debug {
+    fn resolve(&self, import_path: &str, current_path: &Path) -> Option<PathBuf>;
+}
+
+/// Resolves imports via solidity remappings, i.e. `@openzeppelin/=lib/openzeppelin-contracts/`
+#[derive(Debug, Clone, Default)]
+pub struct RemappingResolver {
+    pub root: PathBuf,
+    pub remappings: Vec<(String, String)>,
+}
+
+impl RemappingResolver {
+    pub fn new(root: PathBuf, remappings: Vec<(String, String)>) -> Self {
+        Self { root, remappings }
+    }
+}
+
+impl ImportResolver for RemappingResolver {
+    fn resolve(&self, import_path: &str, _current_path: &Path) -> Option<PathBuf> {
+        let (name, path) = self
+            .remappings
+            .iter()
+            .filter(|(name, _)| import_path.starts_with(name))
+            .max_by_key(|(name, _)| name.len())?;
+        let remapped = self
+            .root
+            .join(path)
+            .join(import_path.replacen(name, "", 1).trim_start_matches('/'));
+        remapped.exists().then_some(remapped)
+    }
+}
+
+/// Resolves imports relative to the directory of the importing file
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RelativeResolver;
+
+impl ImportResolver for RelativeResolver {
+    fn resolve(&self, import_path: &str, current_path: &Path) -> Option<PathBuf> {
+        let relative = current_path.parent()?.join(import_path);
+        relative.exists().then_some(relative)
+    }
+}
+
+/// Resolves direct (non-relative) imports against a list of directories, like solc's
+/// `--base-path` and `--include-path`
+#[derive(Debug, Clone, Default)]
+pub struct IncludePathResolver {
+    pub paths: Vec<PathBuf>,
+}
+
+impl IncludePathResolver {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self { paths }
+    }
+}
+
+impl ImportResolver for IncludePathResolver {
+    fn resolve(&self, import_path: &str, _current_path: &Path) -> Option<PathBuf> {
+        if import_path.starts_with("./") || import_path.starts_with("../") {
+            return None;
+        }
+        self.paths
+            .iter()
+            .map(|path| path.join(import_path))
+            .find(|path| path.exists())
+    }
+}
+
+/// Resolves imports from `node_modules` directories, as hardhat and truffle projects do. Starting at
+/// the importing file's directory and at the root, each ancestor directory is searched for a `node_modules`
+/// directory containing the import.
+#[derive(Debug, Clone, Default)]
+pub struct NodeModulesResolver {
+    pub root: PathBuf,
+}
+
+impl NodeModulesResolver {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn search_up(start: &Path, import_path: &str) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join("node_modules").join(import_path))
+            .find(|path| path.exists())
+    }
+}
+
+impl ImportResolver for NodeModulesResolver {
+    fn resolve(&self, import_path: &str, current_path: &Path) -> Option<PathBuf> {
+        if import_path.starts_with("./") || import_path.starts_with("../") {
+            return None;
+        }
+        let current_dir = current_path
+            .parent()
+            .and_then(|dir| fs::canonicalize(dir).ok());
+        let root = fs::canonicalize(&self.root).ok();
+        current_dir
+            .iter()
+            .chain(root.iter())
+            .find_map(|start| Self::search_up(start, import_path))
+    }
+}
+
+/// Reads the `paths.sources` setting out of a hardhat config in `root`, if there is a hardhat config.
+/// Defaults to `contracts` like hardhat does.
+///
+/// Hardhat configs are javascript/typescript, so this is a best effort textual search rather than
+/// an evaluation of the config.
+pub fn hardhat_sources(root: &Path) -> Option<PathBuf> {
+    let config = [
+        "hardhat.config.ts",
+        "hardhat.config.js",
+        "hardhat.config.cjs",
+    ]
+    .iter()
+    .find_map(|name| fs::read_to_string(root.join(name)).ok())?;
+
+    let sources = config
+        .find("paths")
+        .map(|paths_start| &config[paths_start..])
+        .and_then(|paths| {
+            let after_key = &paths[paths.find("sources")? + "sources".len()..];
+            let after_colon = after_key.trim_start().strip_prefix(':')?.trim_start();
+            let quote = after_colon
+                .chars()
+                .next()
+                .filter(|c| ['"', '\'', '`'].contains(c))?;
+            let value = &after_colon[1..];
+            Some(value[..value.find(quote)?].to_string())
+        })
+        .unwrap_or_else(|| "contracts".to_string());
+
+    Some(PathBuf::from(sources.trim_start_matches("./")))
+}
+
+/// Resolves imports against in-memory sources, i.e. the `sources` of a solc standard-JSON input. Like solc,
+/// relative imports are resolved against the importing source unit's name and direct imports are
+/// remapped and then used as is.
+#[derive(Debug, Clone, Default)]
+pub struct VirtualResolver {
+    pub sources: BTreeSet<PathBuf>,
+    pub remappings: Vec<(String, String)>,
+}
+
+impl VirtualResolver {
+    pub fn new(sources: BTreeSet<PathBuf>, remappings: Vec<(String, String)>) -> Self {
+        Self {
+            sources,
+            remappings,
+        }
+    }
+}
+
+impl ImportResolver for VirtualResolver {
+    fn resolve(&self, import_path: &str, current_path: &Path) -> Option<PathBuf> {
+        let unit_name = if import_path.starts_with("./") || import_path.starts_with("../") {
+            normalize_path(&current_path.parent()?.join(import_path))
+        } else if let Some((name, path)) = self
+            .remappings
+            .iter()
+            .filter(|(name, _)| import_path.starts_with(name))
+            .max_by_key(|(name, _)| name.len())
+        {
+            normalize_path(Path::new(&import_path.replacen(name, path, 1)))
+        } else {
+            normalize_path(Path::new(import_path))
+        };
+        self.sources.contains(&unit_name).then_some(unit_name)
+    }
+}
+
+/// Lexically removes `.` and `..` components from a path, without touching the filesystem
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    path.components().for_each(|component| match component {
+        Component::CurDir => {}
+        Component::ParentDir => {
+            normalized.pop();
+        }
+        c => normalized.push(c),
+    });
+    normalized
+}
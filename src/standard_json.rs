@@ -0,0 +1,90 @@
+use crate::{try_parse_remappings, Analyzer};
+
+use serde::Deserialize;
+use shared::NodeIdx;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// The parts of a solc standard-JSON input that are relevant to analysis
+#[derive(Debug, Clone, Deserialize)]
+pub struct StandardJsonInput {
+    pub language: Option<String>,
+    pub sources: BTreeMap<String, StandardJsonSource>,
+    #[serde(default)]
+    pub settings: StandardJsonSettings,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StandardJsonSource {
+    pub content: Option<String>,
+    #[serde(default)]
+    pub urls: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StandardJsonSettings {
+    #[serde(default)]
+    pub remappings: Vec<String>,
+}
+
+impl StandardJsonInput {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let input: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if let Some(language) = &input.language {
+            if language != "Solidity" {
+                return Err(format!("Unsupported standard-JSON language: {language}"));
+            }
+        }
+        Ok(input)
+    }
+
+    /// The remappings in the settings. Context-scoped remappings (i.e. `context:prefix=target`) are rejected, as
+    /// remappings are applied to every import regardless of the importing file
+    pub fn remappings(&self) -> Result<Vec<(String, String)>, String> {
+        try_parse_remappings(&self.settings.remappings.join("\n"))?
+            .into_iter()
+            .map(|(name, path)| {
+                if name.contains(':') {
+                    Err(format!(
+                        "Context-scoped remappings are not supported: \"{name}={path}\""
+                    ))
+                } else {
+                    Ok((name, path))
+                }
+            })
+            .collect()
+    }
+}
+
+impl Analyzer {
+    /// Parses a solc standard-JSON input. Every source in the `sources` map is registered as an in-memory source
+    /// (only inline `content` is supported, as `urls` would require reading the filesystem), the remappings in the
+    /// settings are applied, and then every source is parsed and analyzed without touching the filesystem.
+    ///
+    /// Returns the source unit of each source and all sources, the same as [Analyzer::parse_project]
+    pub fn parse_standard_json(
+        &mut self,
+        json: &str,
+    ) -> Result<(Vec<NodeIdx>, Vec<(Option<NodeIdx>, String, String, usize)>), String> {
+        let input = StandardJsonInput::from_json(json)?;
+        let remappings = input.remappings()?;
+        let mut unit_names = vec![];
+        input
+            .sources
+            .into_iter()
+            .try_for_each(|(unit_name, source)| {
+                let Some(content) = source.content else {
+                    return Err(format!(
+                        "Source \"{unit_name}\" has no inline content. Only inline `content` is supported, got urls: {:?}",
+                        source.urls
+                    ));
+                };
+                let unit_name = PathBuf::from(unit_name);
+                self.virtual_srcs.insert(unit_name.clone(), content);
+                unit_names.push(unit_name);
+                Ok(())
+            })?;
+        self.remappings = remappings;
+        self.parse_project(&unit_names)
+    }
+}
@@ -0,0 +1,436 @@
+use ethers_core::types::U256;
+use pyrometer::context::analyzers::{
+    InvariantAnalyzer, PanicAnalyzer, ReentrancyAnalyzer, ReportConfig, UncheckedMathAnalyzer,
+};
+use pyrometer::context::queries::storage_write::{AccessStorageWriteQuery, StorageRangeQuery};
+use pyrometer::context::queries::taint::TaintQuery;
+use pyrometer::Analyzer;
+use shared::analyzer::Search;
+use shared::nodes::{Concrete, ContractNode};
+use shared::range::{Range, SolcRange};
+use shared::{Edge, NodeIdx};
+use std::collections::BTreeMap;
+use std::env;
+mod helpers;
+use helpers::*;
+
+#[test]
+fn test_invariants() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/invariants.sol");
+    let sol = include_str!("./test_data/invariants.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Counter");
+    let (invariants, _rounds) = analyzer.storage_invariants(contract).unwrap();
+    let expected = [
+        ("count", 0, 10),
+        ("score", 0, 5),
+        ("level", 0, 255),
+        ("cap", 100, 100),
+        ("limit", 50, 50),
+    ];
+    for (name, min, max) in expected {
+        let (_, range) = invariants
+            .iter()
+            .find(|(var, _)| var.name(&analyzer).unwrap() == name)
+            .unwrap_or_else(|| panic!("No invariant for storage variable: {name}"));
+        let range = range
+            .as_ref()
+            .unwrap_or_else(|| panic!("Unknown invariant for storage variable: {name}"));
+        let min_val = range
+            .evaled_range_min(&analyzer)
+            .unwrap()
+            .maybe_concrete()
+            .and_then(|min| min.val.into_u256());
+        let max_val = range
+            .evaled_range_max(&analyzer)
+            .unwrap()
+            .maybe_concrete()
+            .and_then(|max| max.val.into_u256());
+        assert_eq!(
+            (min_val, max_val),
+            (Some(U256::from(min)), Some(U256::from(max))),
+            "Unexpected invariant for storage variable: {name}"
+        );
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+#[test]
+fn test_reentrancy() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/reentrancy.sol");
+    let sol = include_str!("./test_data/reentrancy.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Bank");
+    let file_mapping = file_mapping(&all_sources);
+    let violations = analyzer.reentrancy_for(&file_mapping, contract).unwrap();
+    let mut found = violations
+        .iter()
+        .map(|violation| {
+            let mut cross_fns = violation
+                .cross_fns
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>();
+            cross_fns.sort();
+            cross_fns.dedup();
+            (
+                violation.func_name.as_str(),
+                violation.var_name.as_str(),
+                cross_fns,
+            )
+        })
+        .collect::<Vec<_>>();
+    found.sort();
+    // each function that writes a storage variable after an external call, the variable and the other functions that
+    // access it
+    assert_eq!(
+        found,
+        vec![
+            ("claim(uint256)", "totalDeposits", vec!["deposit(uint256)"]),
+            (
+                "relay(address, address, uint256)",
+                "balances",
+                vec![
+                    "deposit(uint256)",
+                    "withdraw(uint256)",
+                    "withdrawSafe(uint256)",
+                ],
+            ),
+            (
+                "withdraw(uint256)",
+                "balances",
+                vec![
+                    "deposit(uint256)",
+                    "relay(address, address, uint256)",
+                    "withdrawSafe(uint256)",
+                ],
+            ),
+        ],
+        "Unexpected reentrancy violations"
+    );
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+#[test]
+fn test_taint() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/taint.sol");
+    let sol = include_str!("./test_data/taint.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, all_sources) = parse_file(&mut analyzer, &path_str, sol);
+    assert!(
+        analyzer.expr_errs.is_empty(),
+        "Analyzer encountered parse errors"
+    );
+    let file_mapping = file_mapping(&all_sources);
+    let sinks = analyzer
+        .taint_query(&[entry], &file_mapping, "Vault".to_string())
+        .unwrap()
+        .sinks;
+    let mut found = sinks
+        .iter()
+        .map(|sink| {
+            let mut sources = sink
+                .sources
+                .iter()
+                .map(|source| source.as_str())
+                .collect::<Vec<_>>();
+            sources.sort();
+            sources.dedup();
+            (sink.func_name.as_str(), sink.kind.as_str(), sources)
+        })
+        .collect::<Vec<_>>();
+    found.sort();
+    // each function caller controlled data reaches a sensitive operation in, the kind of operation and where the data
+    // comes from
+    assert_eq!(
+        found,
+        vec![
+            (
+                "direct(address, bytes)",
+                "delegatecall target",
+                vec!["input target of direct(address, bytes)"],
+            ),
+            (
+                "forward(bytes)",
+                "delegatecall target",
+                vec!["input newImpl of setImpl(address)"],
+            ),
+            (
+                "get(uint256)",
+                "array index",
+                vec!["input i of get(uint256)"]
+            ),
+            (
+                "killTo(address)",
+                "selfdestruct recipient",
+                vec!["input to of killTo(address)"],
+            ),
+            (
+                "pay(uint256)",
+                "transfer amount",
+                vec!["input amount of pay(uint256)"],
+            ),
+            ("payBack()", "transfer amount", vec!["msg.value"]),
+        ],
+        "Unexpected tainted sinks"
+    );
+}
+
+#[test]
+fn test_access_query() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/access.sol");
+    let sol = include_str!("./test_data/access.sol");
+    // the functions that write each storage variable or a field of it, and whether the write is guarded by a condition
+    let expected: &[(&str, &[(&str, &str, bool)])] = &[
+        (
+            "owner",
+            &[
+                ("claimOwnership()", "owner", false),
+                ("setOwner(address)", "owner", true),
+            ],
+        ),
+        (
+            "config",
+            &[
+                ("setFee(uint256)", "config.fee", true),
+                ("setTreasury(address)", "config.treasury", false),
+            ],
+        ),
+    ];
+    for (storage_var_name, writes) in expected {
+        let mut analyzer = Analyzer::default();
+        let (entry, all_sources) = parse_file(&mut analyzer, &path_str, sol);
+        assert!(
+            analyzer.expr_errs.is_empty(),
+            "Analyzer encountered parse errors"
+        );
+        let file_mapping = file_mapping(&all_sources);
+        let access = analyzer
+            .access_query(
+                &[entry],
+                &file_mapping,
+                "Owned".to_string(),
+                storage_var_name.to_string(),
+            )
+            .unwrap();
+        let mut found = access
+            .writes
+            .iter()
+            .map(|write| {
+                (
+                    write.func_name.as_str(),
+                    write.var_name.as_str(),
+                    !write.is_unconditional(),
+                )
+            })
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(
+            found, *writes,
+            "Unexpected writes of storage variable: {storage_var_name}"
+        );
+    }
+}
+
+#[test]
+fn test_write_query() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/write_query.sol");
+    let sol = include_str!("./test_data/write_query.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, all_sources) = parse_file(&mut analyzer, &path_str, sol);
+    assert!(
+        analyzer.expr_errs.is_empty(),
+        "Analyzer encountered parse errors"
+    );
+    let file_mapping = file_mapping(&all_sources);
+    // the function, the target value of `count` and whether a path through the function can leave it with the value
+    let expected = [
+        ("set", 5, true),
+        ("set", 50, false),
+        ("reset", 100, true),
+        ("reset", 5, false),
+    ];
+    for (func_name, value, reachable) in expected {
+        let target = Concrete::Uint(256, U256::from(value));
+        let reports = analyzer
+            .func_query(
+                &[entry],
+                &file_mapping,
+                ReportConfig::default(),
+                "Counter".to_string(),
+                func_name.to_string(),
+                "count".to_string(),
+                SolcRange::new(target.clone().into(), target.into(), vec![]),
+            )
+            .unwrap();
+        assert_eq!(
+            !reports.is_empty(),
+            reachable,
+            "Unexpected paths through {func_name} to count == {value}"
+        );
+        assert!(
+            reports.iter().all(|report| report.write_loc.is_some()),
+            "A path through {func_name} to count == {value} without its write"
+        );
+    }
+
+    let res = analyzer.func_query(
+        &[entry],
+        &file_mapping,
+        ReportConfig::default(),
+        "Counter".to_string(),
+        "missing".to_string(),
+        "count".to_string(),
+        SolcRange::new(
+            Concrete::Bool(true).into(),
+            Concrete::Bool(true).into(),
+            vec![],
+        ),
+    );
+    assert!(
+        res.is_err(),
+        "Expected a query of a missing function to fail"
+    );
+}
+
+#[test]
+fn test_unchecked_math() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/unchecked.sol");
+    let sol = include_str!("./test_data/unchecked.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Math");
+    let file_mapping = file_mapping(&all_sources);
+    let analyses = analyzer
+        .unchecked_math_for(&file_mapping, contract)
+        .unwrap();
+    let mut found = analyses
+        .iter()
+        .map(|analysis| {
+            (
+                analysis.func_name.as_str(),
+                analysis.op.as_str(),
+                analysis.kind.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    found.sort();
+    // the arithmetic whose overflow check doesn't match its operands' ranges
+    assert_eq!(
+        found,
+        vec![
+            ("boundedAdd(uint256)", "+", "can be unchecked"),
+            ("boundedMul(uint256, uint256)", "*", "can be unchecked"),
+            ("boundedNegate(int256)", "-", "can be unchecked"),
+            ("boundedPow(uint256)", "**", "can be unchecked"),
+            ("counter()", "++", "can be unchecked"),
+            ("uncheckedDec(uint256)", "-", "unchecked overflow"),
+            ("uncheckedNegate(int256)", "-", "unchecked overflow"),
+        ],
+        "Unexpected unchecked math findings"
+    );
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+#[test]
+fn test_panics() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/panics.sol");
+    let sol = include_str!("./test_data/panics.sol");
+    let expected = [
+        ("add(uint256, uint256)", "+", 0x11, "may revert"),
+        ("divZero(uint256)", "/", 0x12, "always reverts"),
+        ("divide(uint256, uint256)", "/", 0x12, "may revert"),
+        ("negate(int8)", "-", 0x11, "may revert"),
+        ("power(uint256, uint256)", "**", 0x11, "may revert"),
+        ("powerOverflow()", "**", 0x11, "always reverts"),
+        ("underflow()", "-", 0x11, "always reverts"),
+    ]
+    .map(|(func, op, code, likelihood)| (func.to_string(), op.to_string(), code, likelihood));
+    assert_eq!(
+        panics_of(&path_str, sol, "Panics"),
+        expected,
+        "Unexpected panics"
+    );
+}
+
+#[test]
+fn test_array_bounds() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/bounds.sol");
+    let sol = include_str!("./test_data/bounds.sol");
+    let expected = [
+        ("bytesAny(bytes32, uint256)", "[]", 0x32, "may revert"),
+        ("fixedAny(uint256)", "[]", 0x32, "may revert"),
+        ("fixedOut()", "[]", 0x32, "always reverts"),
+        ("popAny()", "pop()", 0x31, "may revert"),
+    ]
+    .map(|(func, op, code, likelihood)| (func.to_string(), op.to_string(), code, likelihood));
+    assert_eq!(
+        panics_of(&path_str, sol, "Bounds"),
+        expected,
+        "Unexpected panics"
+    );
+}
+
+/// The arithmetic and array accesses of a contract that can panic, as the function, the operator, the panic code and
+/// how likely the panic is, in order
+fn panics_of(
+    path_str: &str,
+    sol: &str,
+    contract_name: &str,
+) -> Vec<(String, String, u8, &'static str)> {
+    let mut analyzer = Analyzer::default();
+    let (_, contract, all_sources) = parse_contract(&mut analyzer, path_str, sol, contract_name);
+    // contexts are killed by the panics, so there is no check that none are
+    assert!(
+        analyzer.expr_errs.is_empty(),
+        "Analyzer encountered parse errors"
+    );
+    let file_mapping = file_mapping(&all_sources);
+    let mut panics = analyzer
+        .panics_for(&file_mapping, contract)
+        .unwrap()
+        .into_iter()
+        .map(|panic| {
+            (
+                panic.func_name,
+                panic.op,
+                panic.kind.code(),
+                panic.likelihood.as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    panics.sort();
+    panics
+}
+
+/// Parses a source file with the given analyzer and finds the contract with the given name in it
+fn parse_contract(
+    analyzer: &mut Analyzer,
+    path_str: &str,
+    sol: &str,
+    contract_name: &str,
+) -> (NodeIdx, ContractNode, Sources) {
+    let (entry, all_sources) = parse_file(analyzer, path_str, sol);
+    let contract = analyzer
+        .search_children(entry, &Edge::Contract)
+        .into_iter()
+        .map(ContractNode::from)
+        .find(|contract| contract.name(analyzer).unwrap() == contract_name)
+        .unwrap_or_else(|| panic!("No contract named: {contract_name}"));
+    (entry, contract, all_sources)
+}
+
+/// Maps the file number of each parsed source to its path
+fn file_mapping(all_sources: &Sources) -> BTreeMap<usize, String> {
+    all_sources
+        .iter()
+        .map(|(_entry, name, _src, num)| (*num, name.clone()))
+        .collect()
+}
@@ -0,0 +1,223 @@
+use ethers_core::types::U256;
+use pyrometer::Analyzer;
+use shared::analyzer::Search;
+use shared::context::{ContextNode, ContextVarNode};
+use shared::nodes::ContractNode;
+use shared::range::{elem_ty::Elem, Range};
+use shared::{Edge, NodeIdx};
+use std::collections::BTreeSet;
+use std::env;
+mod helpers;
+use helpers::*;
+
+#[test]
+fn test_summaries() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/summaries.sol");
+    let sol = include_str!("./test_data/summaries.sol");
+    let mut analyzer = Analyzer {
+        summarize_calls: true,
+        ..Default::default()
+    };
+    let (_, contract, _) = parse_contract(&mut analyzer, &path_str, sol, "Summaries");
+
+    // the calls of already analyzed functions each function summarized
+    let summarized: &[(&str, &[&str])] = &[
+        ("useSummaries", &["setLast", "first", "double"]),
+        ("useBounded", &["bounded"]),
+        ("useScaled", &["scaled"]),
+        ("useScaledOutOfRange", &["scaled"]),
+    ];
+    for (func_name, callees) in summarized {
+        let body_ctx = body_ctx_of(&mut analyzer, contract, func_name);
+        let mut ctxs = vec![body_ctx];
+        ctxs.extend(body_ctx.descendants(&analyzer).unwrap());
+        let mut calls = BTreeSet::default();
+        for ctx in ctxs {
+            for (_, callee) in ctx.summarized_calls(&analyzer).unwrap() {
+                calls.insert(callee.name(&analyzer).unwrap());
+            }
+        }
+        let callees = callees
+            .iter()
+            .map(|callee| callee.to_string())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(calls, callees, "Unexpected summarized calls in {func_name}");
+    }
+
+    // the summaries carry the requirements of the callees
+    for func_name in ["useBounded", "useScaledOutOfRange"] {
+        let body_ctx = body_ctx_of(&mut analyzer, contract, func_name);
+        assert!(
+            body_ctx.successful_leaves(&analyzer).unwrap().is_empty(),
+            "{func_name} has a successful path"
+        );
+    }
+
+    assert_storage_bounds(
+        &mut analyzer,
+        contract,
+        &[
+            ("useSummaries", "last", 5, 5),
+            ("useSummaries", "total", 14, 14),
+            ("useScaled", "total", 1, 298),
+            ("useScaled", "last", 0, 99),
+        ],
+    );
+}
+
+#[test]
+fn test_closed_world() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/closed_world.sol");
+    let sol = include_str!("./test_data/closed_world.sol");
+    let mut analyzer = Analyzer {
+        closed_world: true,
+        ..Default::default()
+    };
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Router");
+    // each implementation is called on a path of its own, with the call's arguments
+    let expected: &[(&str, &str, &[u64])] = &[
+        ("route", "lastFee", &[5, 10]),
+        ("routeScaled", "lastScaled", &[60]),
+    ];
+    for (func_name, name, values) in expected {
+        let mut found = leaves_of(&mut analyzer, contract, func_name)
+            .into_iter()
+            .map(|leaf| storage_bounds(&analyzer, leaf, func_name, name))
+            .collect::<Vec<_>>();
+        found.sort();
+        let values = values
+            .iter()
+            .map(|value| (Some(U256::from(*value)), Some(U256::from(*value))))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found, values,
+            "Unexpected ranges for {name} after {func_name}"
+        );
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+#[test]
+fn test_call_frame() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/call_frame.sol");
+    let sol = include_str!("./test_data/call_frame.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Sender");
+    assert_storage_bounds(
+        &mut analyzer,
+        contract,
+        &[("pay", "paid", 5, 5), ("payNothing", "unpaid", 0, 0)],
+    );
+
+    // the callee sees the calling contract as msg.sender, so the address it returns is bounded by the caller's `this`
+    for func_name in ["whoAmI", "whoAmIThrough"] {
+        for leaf in leaves_of(&mut analyzer, contract, func_name) {
+            let var = leaf
+                .storage_var_by_name(&analyzer, "seenAs")
+                .unwrap()
+                .unwrap_or_else(|| panic!("{func_name} doesn't access seenAs"));
+            for bound in [var.range_min(&analyzer), var.range_max(&analyzer)] {
+                let mut bound = bound.unwrap();
+                loop {
+                    let Some(Elem::Dynamic(dy)) = bound else {
+                        panic!("seenAs isn't bounded by this after {func_name}");
+                    };
+                    let bounded_by = ContextVarNode::from(dy.idx);
+                    if bounded_by.name(&analyzer).unwrap() == "this" {
+                        break;
+                    }
+                    bound = bounded_by.range_min(&analyzer).unwrap();
+                }
+            }
+        }
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+/// Asserts that after each given function of a contract the given storage variables have the given bounds on every
+/// successful path
+fn assert_storage_bounds(
+    analyzer: &mut Analyzer,
+    contract: ContractNode,
+    after: &[(&str, &str, u64, u64)],
+) {
+    for (func_name, name, min, max) in after {
+        for leaf in leaves_of(analyzer, contract, func_name) {
+            assert_eq!(
+                storage_bounds(analyzer, leaf, func_name, name),
+                (Some(U256::from(*min)), Some(U256::from(*max))),
+                "Unexpected range for {name} after {func_name}"
+            );
+        }
+    }
+}
+
+/// Gets the concrete bounds of a storage variable at the end of a path through a function
+fn storage_bounds(
+    analyzer: &Analyzer,
+    leaf: ContextNode,
+    func_name: &str,
+    name: &str,
+) -> (Option<U256>, Option<U256>) {
+    let range = leaf
+        .storage_var_by_name(analyzer, name)
+        .unwrap()
+        .unwrap_or_else(|| panic!("{func_name} doesn't access storage variable: {name}"))
+        .range(analyzer)
+        .unwrap()
+        .unwrap();
+    let min = range
+        .evaled_range_min(analyzer)
+        .unwrap()
+        .maybe_concrete()
+        .and_then(|min| min.val.into_u256());
+    let max = range
+        .evaled_range_max(analyzer)
+        .unwrap()
+        .maybe_concrete()
+        .and_then(|max| max.val.into_u256());
+    (min, max)
+}
+
+/// Parses a source file with the given analyzer and finds the contract with the given name in it
+fn parse_contract(
+    analyzer: &mut Analyzer,
+    path_str: &str,
+    sol: &str,
+    contract_name: &str,
+) -> (NodeIdx, ContractNode, Sources) {
+    let (entry, all_sources) = parse_file(analyzer, path_str, sol);
+    let contract = analyzer
+        .search_children(entry, &Edge::Contract)
+        .into_iter()
+        .map(ContractNode::from)
+        .find(|contract| contract.name(analyzer).unwrap() == contract_name)
+        .unwrap_or_else(|| panic!("No contract named: {contract_name}"));
+    (entry, contract, all_sources)
+}
+
+/// Gets the context of the body of the function of a contract with the given name, which may leave out the parameter
+/// list of its signature
+fn body_ctx_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> ContextNode {
+    let func = contract
+        .funcs(analyzer)
+        .into_iter()
+        .find(|func| {
+            let name = func.name(analyzer).unwrap();
+            name == func_name || name.split('(').next() == Some(func_name)
+        })
+        .unwrap_or_else(|| panic!("No function named: {func_name}"));
+    func.body_ctx(analyzer)
+}
+
+/// Gets the successful paths through the function of a contract with the given name, asserting that there is one
+fn leaves_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> Vec<ContextNode> {
+    let leaves = body_ctx_of(analyzer, contract, func_name)
+        .successful_leaves(analyzer)
+        .unwrap();
+    assert!(!leaves.is_empty(), "No successful path through {func_name}");
+    leaves
+}
@@ -0,0 +1,160 @@
+use ethers_core::types::U256;
+use pyrometer::Analyzer;
+use shared::analyzer::Search;
+use shared::context::ContextNode;
+use shared::nodes::ContractNode;
+use shared::{Edge, NodeIdx};
+use std::env;
+mod helpers;
+use helpers::*;
+
+#[test]
+fn test_hashing() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/hashing.sol");
+    let sol = include_str!("./test_data/hashing.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Hashing");
+
+    // the hashes each function returns, as the 32 byte word each is stored in. Fixed size bytes are left aligned in the
+    // word
+    let expected: &[(&str, &[&str])] = &[
+        (
+            "keccak",
+            &["0x9f2df0fed2c77648de5860a4cc508cd0818c85b8b8a1ab4ceeef8d981c8956a6"],
+        ),
+        (
+            "sha",
+            &["0xba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"],
+        ),
+        (
+            "ripemd",
+            &["0x8eb208f7e05d987a9b044a8e98c6b087f15a0bfc000000000000000000000000"],
+        ),
+        (
+            "selector",
+            &["0xa9059cbb00000000000000000000000000000000000000000000000000000000"],
+        ),
+        (
+            "encode",
+            &[
+                "0xb10e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf6",
+                "0x5fe7f977e71dba2ea1a68e21057beebb9be2ac30c6410aa38d4f3fbe41dcffd2",
+                "0x92c0c5db84060b0512a02a5fbd80aeec7b4074b724abcfb509a13c96b6564336",
+            ],
+        ),
+        (
+            "yulEmpty",
+            &["0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"],
+        ),
+    ];
+    for (func_name, values) in expected {
+        let values = values
+            .iter()
+            .map(|value| Some(U256::from(*value)))
+            .collect::<Vec<_>>();
+        for leaf in leaves_of(&mut analyzer, contract, func_name) {
+            let rets = leaf
+                .underlying(&analyzer)
+                .unwrap()
+                .ret
+                .iter()
+                .map(|(_, ret)| {
+                    let min = ret
+                        .evaled_range_min(&analyzer)
+                        .unwrap()
+                        .and_then(|min| min.maybe_concrete())
+                        .and_then(|min| min.val.into_u256());
+                    let max = ret
+                        .evaled_range_max(&analyzer)
+                        .unwrap()
+                        .and_then(|max| max.maybe_concrete())
+                        .and_then(|max| max.val.into_u256());
+                    if min == max {
+                        min
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(rets, values, "Unexpected return values of {func_name}");
+        }
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+#[test]
+fn test_abi_decode() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/abi_decode.sol");
+    let sol = include_str!("./test_data/abi_decode.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Decode");
+
+    // the bounds of the decoded value each function stores
+    let expected = [("roundTrip", 11, 19), ("concreteRoundTrip", 7, 7)];
+    for (func_name, min, max) in expected {
+        for leaf in leaves_of(&mut analyzer, contract, func_name) {
+            let stored = leaf
+                .storage_var_by_name(&analyzer, "stored")
+                .unwrap()
+                .unwrap_or_else(|| panic!("{func_name} doesn't access stored"));
+            let min_val = stored
+                .evaled_range_min(&analyzer)
+                .unwrap()
+                .and_then(|min| min.maybe_concrete())
+                .and_then(|min| min.val.into_u256());
+            let max_val = stored
+                .evaled_range_max(&analyzer)
+                .unwrap()
+                .and_then(|max| max.maybe_concrete())
+                .and_then(|max| max.val.into_u256());
+            assert_eq!(
+                (min_val, max_val),
+                (Some(U256::from(min)), Some(U256::from(max))),
+                "Unexpected range for stored after {func_name}"
+            );
+        }
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+/// Parses a source file with the given analyzer and finds the contract with the given name in it
+fn parse_contract(
+    analyzer: &mut Analyzer,
+    path_str: &str,
+    sol: &str,
+    contract_name: &str,
+) -> (NodeIdx, ContractNode, Sources) {
+    let (entry, all_sources) = parse_file(analyzer, path_str, sol);
+    let contract = analyzer
+        .search_children(entry, &Edge::Contract)
+        .into_iter()
+        .map(ContractNode::from)
+        .find(|contract| contract.name(analyzer).unwrap() == contract_name)
+        .unwrap_or_else(|| panic!("No contract named: {contract_name}"));
+    (entry, contract, all_sources)
+}
+
+/// Gets the context of the body of the function of a contract with the given name, which may leave out the parameter
+/// list of its signature
+fn body_ctx_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> ContextNode {
+    let func = contract
+        .funcs(analyzer)
+        .into_iter()
+        .find(|func| {
+            let name = func.name(analyzer).unwrap();
+            name == func_name || name.split('(').next() == Some(func_name)
+        })
+        .unwrap_or_else(|| panic!("No function named: {func_name}"));
+    func.body_ctx(analyzer)
+}
+
+/// Gets the successful paths through the function of a contract with the given name, asserting that there is one
+fn leaves_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> Vec<ContextNode> {
+    let leaves = body_ctx_of(analyzer, contract, func_name)
+        .successful_leaves(analyzer)
+        .unwrap();
+    assert!(!leaves.is_empty(), "No successful path through {func_name}");
+    leaves
+}
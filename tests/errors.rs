@@ -0,0 +1,230 @@
+use ethers_core::types::U256;
+use pyrometer::Analyzer;
+use shared::analyzer::Search;
+use shared::context::{ContextNode, ContextVarNode, KilledKind};
+use shared::nodes::{ContractNode, FunctionNode};
+use shared::range::Range;
+use shared::{Edge, NodeIdx};
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+mod helpers;
+use helpers::*;
+
+#[test]
+fn test_try_catch() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/try_catch.sol");
+    let sol = include_str!("./test_data/try_catch.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "TryCatch");
+
+    // each function forks into a context for each `try` branch, in which the variables are bound with the given
+    // bounds. A variable expected to have `None` bounds only has to be bound
+    let expected: &[(&str, &str, &[(&str, Option<(u64, u64)>)])] = &[
+        ("tryPrice", "try", &[("p", None)]),
+        ("tryPrice", "catch Error", &[("reason", None)]),
+        ("tryPrice", "catch Panic", &[("code", Some((0, 0x51)))]),
+        ("tryPrice", "catch", &[]),
+        ("tryMulti", "try", &[("pa", None)]),
+        ("tryMulti", "catch", &[("data", None)]),
+        ("tryNoReturns", "try", &[]),
+        ("tryNoReturns", "catch", &[]),
+        // the argument is evaluated before forking, so the catch clause sees its increment
+        ("tryCounter", "try", &[("i", Some((1, 1)))]),
+        ("tryCounter", "catch", &[("i", Some((1, 1)))]),
+    ];
+    for (func_name, fork, vars) in expected {
+        let body_ctx = body_ctx_of(&mut analyzer, contract, func_name);
+        let fork_suffix = format!("fork{{ {fork} }}");
+        let branch_ctxs: Vec<_> = body_ctx
+            .descendants(&analyzer)
+            .unwrap()
+            .into_iter()
+            .filter(|ctx| ctx.path(&analyzer).ends_with(&fork_suffix))
+            .collect();
+        assert!(
+            !branch_ctxs.is_empty(),
+            "{func_name} has no `{fork}` branch"
+        );
+        for (name, bounds) in vars.iter() {
+            let bounds = bounds.map(|(min, max)| (U256::from(min), U256::from(max)));
+            let bound = branch_ctxs.iter().any(|branch_ctx| {
+                let mut ctxs = vec![*branch_ctx];
+                ctxs.extend(branch_ctx.descendants(&analyzer).unwrap());
+                ctxs.into_iter().any(|ctx| {
+                    ctx.var_by_name_or_recurse(&analyzer, name)
+                        .unwrap()
+                        .is_some_and(|var| {
+                            bounds.is_none()
+                                || arg_bounds(&analyzer, &[var.latest_version(&analyzer)])
+                                    == [bounds]
+                        })
+                })
+            });
+            assert!(
+                bound,
+                "{name} isn't bound with bounds {bounds:?} in the `{fork}` branch of {func_name}"
+            );
+        }
+    }
+
+    for leaf in leaves_of(&mut analyzer, contract, "tryCounter") {
+        let range = leaf
+            .storage_var_by_name(&analyzer, "lastPrice")
+            .unwrap()
+            .expect("tryCounter doesn't access lastPrice")
+            .range(&analyzer)
+            .unwrap()
+            .unwrap();
+        let min = range.evaled_range_min(&analyzer).unwrap();
+        let max = range.evaled_range_max(&analyzer).unwrap();
+        assert_eq!(
+            (
+                min.maybe_concrete().and_then(|min| min.val.into_u256()),
+                max.maybe_concrete().and_then(|max| max.val.into_u256())
+            ),
+            (Some(U256::one()), Some(U256::one())),
+            "Unexpected range for lastPrice after tryCounter"
+        );
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+#[test]
+fn test_custom_errors() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/custom_errors.sol");
+    let sol = include_str!("./test_data/custom_errors.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, _) = parse_file(&mut analyzer, &path_str, sol);
+    assert!(
+        analyzer.expr_errs.is_empty(),
+        "Analyzer encountered parse errors: {:?}",
+        analyzer.expr_errs
+    );
+
+    // every reverting path records the error it reverted with
+    let mut reverts: BTreeMap<FunctionNode, BTreeSet<_>> = BTreeMap::default();
+    let funcs = analyzer.search_children(entry, &Edge::Func);
+    for func in funcs.into_iter() {
+        let func = FunctionNode::from(func);
+        if let Some(ctx) = func.maybe_body_ctx(&mut analyzer) {
+            let mut edges = ctx.all_edges(&analyzer).unwrap();
+            if edges.is_empty() {
+                edges.push(ctx);
+            }
+            edges.iter().for_each(|subctx| {
+                if let Some((_, KilledKind::Revert)) = subctx.killed_loc(&analyzer).unwrap() {
+                    let revert = subctx.reverts_with(&analyzer).unwrap().unwrap_or_else(|| {
+                        panic!("Revert without a reason in: {}", subctx.path(&analyzer))
+                    });
+                    let error = revert
+                        .error
+                        .map(|error| error.name(&analyzer).unwrap())
+                        .unwrap_or_else(|| "Error".to_string());
+                    reverts
+                        .entry(func)
+                        .or_default()
+                        .insert((error, arg_bounds(&analyzer, &revert.args)));
+                }
+            });
+        }
+    }
+    assert!(!reverts.is_empty(), "No reverting paths");
+
+    // the errors the reverting paths through each function revert with, with the bounds of their arguments. A `revert`
+    // or `require` with a reason string reverts with `Error`, and an argument expected to be `None` isn't checked
+    let expected: &[(&str, &str, &[Option<(u64, u64)>])] = &[
+        ("withdraw", "InsufficientBalance", &[None, None]),
+        (
+            "withdrawNamed",
+            "InsufficientBalance",
+            &[Some((0, 99)), Some((100, 100))],
+        ),
+        ("onlyOwner", "Unauthorized", &[]),
+        ("locked", "Locked", &[None]),
+        ("reason", "Error", &[None]),
+    ];
+    for (func_name, error, args) in expected {
+        let func_reverts = reverts
+            .iter()
+            .find(|(func, _)| func.name(&analyzer).unwrap() == *func_name)
+            .map(|(_, func_reverts)| func_reverts.clone())
+            .unwrap_or_default();
+        let args = args
+            .iter()
+            .map(|arg| arg.map(|(min, max)| (U256::from(min), U256::from(max))))
+            .collect::<Vec<_>>();
+        let args_match = |bounds: &Vec<Option<(U256, U256)>>| {
+            bounds.len() == args.len()
+                && bounds
+                    .iter()
+                    .zip(&args)
+                    .all(|(bounds, arg)| arg.is_none() || bounds == arg)
+        };
+        assert_eq!(
+            func_reverts.len(),
+            1,
+            "Unexpected reverts in {func_name}: {func_reverts:?}"
+        );
+        assert!(
+            func_reverts
+                .iter()
+                .any(|(name, bounds)| name == error && args_match(bounds)),
+            "{func_name} doesn't revert with {error} with arguments in {args:?}, reverts with: {func_reverts:?}"
+        );
+    }
+}
+
+/// The evaluated bounds of each of a list of values, if concrete
+fn arg_bounds(analyzer: &Analyzer, args: &[ContextVarNode]) -> Vec<Option<(U256, U256)>> {
+    args.iter()
+        .map(|arg| {
+            let min = arg.evaled_range_min(analyzer).unwrap()?;
+            let max = arg.evaled_range_max(analyzer).unwrap()?;
+            min.maybe_concrete()
+                .and_then(|min| min.val.into_u256())
+                .zip(max.maybe_concrete().and_then(|max| max.val.into_u256()))
+        })
+        .collect()
+}
+
+/// Parses a source file with the given analyzer and finds the contract with the given name in it
+fn parse_contract(
+    analyzer: &mut Analyzer,
+    path_str: &str,
+    sol: &str,
+    contract_name: &str,
+) -> (NodeIdx, ContractNode, Sources) {
+    let (entry, all_sources) = parse_file(analyzer, path_str, sol);
+    let contract = analyzer
+        .search_children(entry, &Edge::Contract)
+        .into_iter()
+        .map(ContractNode::from)
+        .find(|contract| contract.name(analyzer).unwrap() == contract_name)
+        .unwrap_or_else(|| panic!("No contract named: {contract_name}"));
+    (entry, contract, all_sources)
+}
+
+/// Gets the context of the body of the function of a contract with the given name, which may leave out the parameter
+/// list of its signature
+fn body_ctx_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> ContextNode {
+    let func = contract
+        .funcs(analyzer)
+        .into_iter()
+        .find(|func| {
+            let name = func.name(analyzer).unwrap();
+            name == func_name || name.split('(').next() == Some(func_name)
+        })
+        .unwrap_or_else(|| panic!("No function named: {func_name}"));
+    func.body_ctx(analyzer)
+}
+
+/// Gets the successful paths through the function of a contract with the given name, asserting that there is one
+fn leaves_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> Vec<ContextNode> {
+    let leaves = body_ctx_of(analyzer, contract, func_name)
+        .successful_leaves(analyzer)
+        .unwrap();
+    assert!(!leaves.is_empty(), "No successful path through {func_name}");
+    leaves
+}
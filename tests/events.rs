@@ -0,0 +1,129 @@
+use ethers_core::types::U256;
+use pyrometer::Analyzer;
+use shared::analyzer::Search;
+use shared::context::{ContextNode, ContextVarNode};
+use shared::nodes::ContractNode;
+use shared::{Edge, NodeIdx};
+use std::collections::BTreeSet;
+use std::env;
+mod helpers;
+use helpers::*;
+
+#[test]
+fn test_events() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/events.sol");
+    let sol = include_str!("./test_data/events.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Events");
+
+    // the events each function emits on its successful paths, with the bounds of their arguments. An argument expected
+    // to be `None` isn't checked
+    let expected: &[(&str, &str, &[Option<(u64, u64)>])] = &[
+        ("emitFree", "FreeEvent", &[Some((0, 99))]),
+        ("emitNoArgs", "Ping", &[]),
+        ("emitNoArgs", "Anon", &[Some((1, 1))]),
+        ("emitInherited", "Inherited", &[None, Some((10, 10))]),
+        (
+            "emitInherited",
+            "Inherited",
+            &[Some((0, 10)), Some((0, 20))],
+        ),
+        ("emitNamed", "Inherited", &[Some((0, 4)), Some((5, 9))]),
+        ("emitMember", "Transfer", &[None, None, None]),
+    ];
+    let func_names: BTreeSet<&str> = expected.iter().map(|(func_name, ..)| *func_name).collect();
+    for func_name in func_names {
+        let mut emits = BTreeSet::default();
+        for leaf in leaves_of(&mut analyzer, contract, func_name) {
+            for emit in leaf.path_emits(&analyzer).unwrap() {
+                emits.insert((
+                    emit.event.name(&analyzer).unwrap(),
+                    arg_bounds(&analyzer, &emit.args),
+                ));
+            }
+        }
+        let expected: Vec<_> = expected
+            .iter()
+            .filter(|(expected_func, ..)| *expected_func == func_name)
+            .collect();
+        assert_eq!(
+            emits.len(),
+            expected.len(),
+            "Unexpected events emitted by {func_name}: {emits:?}"
+        );
+        for (_, event, args) in expected {
+            let args = args
+                .iter()
+                .map(|arg| arg.map(|(min, max)| (U256::from(min), U256::from(max))))
+                .collect::<Vec<_>>();
+            let args_match = |bounds: &Vec<Option<(U256, U256)>>| {
+                bounds.len() == args.len()
+                    && bounds
+                        .iter()
+                        .zip(&args)
+                        .all(|(bounds, arg)| arg.is_none() || bounds == arg)
+            };
+            assert!(
+                emits
+                    .iter()
+                    .any(|(name, bounds)| name == event && args_match(bounds)),
+                "{func_name} doesn't emit {event} with arguments in {args:?}, emitted: {emits:?}"
+            );
+        }
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+/// The evaluated bounds of each argument of an event, if concrete
+fn arg_bounds(analyzer: &Analyzer, args: &[ContextVarNode]) -> Vec<Option<(U256, U256)>> {
+    args.iter()
+        .map(|arg| {
+            let min = arg.evaled_range_min(analyzer).unwrap()?;
+            let max = arg.evaled_range_max(analyzer).unwrap()?;
+            min.maybe_concrete()
+                .and_then(|min| min.val.into_u256())
+                .zip(max.maybe_concrete().and_then(|max| max.val.into_u256()))
+        })
+        .collect()
+}
+
+/// Parses a source file with the given analyzer and finds the contract with the given name in it
+fn parse_contract(
+    analyzer: &mut Analyzer,
+    path_str: &str,
+    sol: &str,
+    contract_name: &str,
+) -> (NodeIdx, ContractNode, Sources) {
+    let (entry, all_sources) = parse_file(analyzer, path_str, sol);
+    let contract = analyzer
+        .search_children(entry, &Edge::Contract)
+        .into_iter()
+        .map(ContractNode::from)
+        .find(|contract| contract.name(analyzer).unwrap() == contract_name)
+        .unwrap_or_else(|| panic!("No contract named: {contract_name}"));
+    (entry, contract, all_sources)
+}
+
+/// Gets the context of the body of the function of a contract with the given name, which may leave out the parameter
+/// list of its signature
+fn body_ctx_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> ContextNode {
+    let func = contract
+        .funcs(analyzer)
+        .into_iter()
+        .find(|func| {
+            let name = func.name(analyzer).unwrap();
+            name == func_name || name.split('(').next() == Some(func_name)
+        })
+        .unwrap_or_else(|| panic!("No function named: {func_name}"));
+    func.body_ctx(analyzer)
+}
+
+/// Gets the successful paths through the function of a contract with the given name, asserting that there is one
+fn leaves_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> Vec<ContextNode> {
+    let leaves = body_ctx_of(analyzer, contract, func_name)
+        .successful_leaves(analyzer)
+        .unwrap();
+    assert!(!leaves.is_empty(), "No successful path through {func_name}");
+    leaves
+}
@@ -9,30 +9,22 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-pub fn assert_no_ctx_killed(path_str: String, sol: &str) {
-    let mut analyzer = Analyzer::default();
-    let (maybe_entry, mut all_sources) =
-        analyzer.parse(sol, &PathBuf::from(path_str.clone()), true);
-    all_sources.push((maybe_entry, path_str.clone(), sol.to_string(), 0));
-    let entry = maybe_entry.unwrap();
-    no_ctx_killed(analyzer, entry, path_str, all_sources);
-}
+/// The sources an analyzer parsed, as the entry of each, its path, its contents and its file number
+pub type Sources = Vec<(Option<NodeIdx>, String, String, usize)>;
 
-pub fn remapping_assert_no_ctx_killed(path_str: String, remapping_file: String, sol: &str) {
-    let mut analyzer = Analyzer::default();
-    analyzer.set_remappings_and_root(remapping_file);
-    let (maybe_entry, mut all_sources) =
-        analyzer.parse(sol, &PathBuf::from(path_str.clone()), true);
-    all_sources.push((maybe_entry, path_str.clone(), sol.to_string(), 0));
-    let entry = maybe_entry.unwrap();
-    no_ctx_killed(analyzer, entry, path_str, all_sources);
+/// Parses a source file with the given analyzer. Returns the entry of the file and every parsed source, including the
+/// file itself
+pub fn parse_file(analyzer: &mut Analyzer, path_str: &str, sol: &str) -> (NodeIdx, Sources) {
+    let (maybe_entry, mut all_sources) = analyzer.parse(sol, &PathBuf::from(path_str), true);
+    all_sources.push((maybe_entry, path_str.to_string(), sol.to_string(), 0));
+    (maybe_entry.unwrap(), all_sources)
 }
 
 pub fn no_ctx_killed(
     mut analyzer: Analyzer,
     entry: NodeIdx,
     path_str: String,
-    all_sources: Vec<(Option<NodeIdx>, String, String, usize)>,
+    all_sources: Sources,
 ) {
     assert!(
         analyzer.expr_errs.is_empty(),
@@ -51,7 +43,12 @@ pub fn no_ctx_killed(
         show_unreachables: true,
         show_nonreverts: true,
     };
-    let file_mapping: BTreeMap<_, _> = vec![(0usize, path_str)].into_iter().collect();
+    let mut file_mapping: BTreeMap<_, _> = vec![(0usize, path_str)].into_iter().collect();
+    file_mapping.extend(
+        all_sources
+            .iter()
+            .map(|(_entry, name, _src, num)| (*num, name.clone())),
+    );
 
     let mut source_map = sources(
         all_sources
@@ -78,6 +75,11 @@ pub fn no_ctx_killed(
                         .print_reports(&mut source_map, &analyzer);
                     panic!("Killed context in test");
                 }
+                assert!(
+                    subctx.loop_control(&analyzer).unwrap().is_none(),
+                    "Path left inside of a loop: {}",
+                    subctx.path(&analyzer)
+                );
             });
         }
     }
@@ -0,0 +1,148 @@
+use ethers_core::types::U256;
+use pyrometer::Analyzer;
+use shared::analyzer::Search;
+use shared::context::ContextNode;
+use shared::nodes::{join_ranges, ContractNode};
+use shared::range::Range;
+use shared::{Edge, NodeIdx};
+use std::env;
+mod helpers;
+use helpers::*;
+
+#[test]
+fn test_unrolling() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/unrolling.sol");
+    let sol = include_str!("./test_data/unrolling.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Unroll");
+
+    // every path through each function returns the constant
+    let expected = [
+        ("forLoop", 6),
+        ("constLoop", 11),
+        ("stepInBody", 5),
+        ("whileLoop", 10),
+        ("doWhileLoop", 3),
+        ("loopBreak", 10),
+        ("loopContinue", 5),
+        ("nestedLoops", 6),
+        ("counterAfterLoop", 4),
+    ];
+    for (func_name, value) in expected {
+        for leaf in leaves_of(&mut analyzer, contract, func_name) {
+            let rets = leaf
+                .underlying(&analyzer)
+                .unwrap()
+                .ret
+                .iter()
+                .map(|(_, ret)| {
+                    let min = ret
+                        .evaled_range_min(&analyzer)
+                        .unwrap()
+                        .and_then(|min| min.maybe_concrete())
+                        .and_then(|min| min.val.into_u256());
+                    let max = ret
+                        .evaled_range_max(&analyzer)
+                        .unwrap()
+                        .and_then(|max| max.maybe_concrete())
+                        .and_then(|max| max.val.into_u256());
+                    (min, max)
+                })
+                .collect::<Vec<_>>();
+            let value = Some(U256::from(value));
+            assert_eq!(
+                rets,
+                vec![(value, value)],
+                "Unexpected return values of {func_name}"
+            );
+        }
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+#[test]
+fn test_loop_storage() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/loop_storage.sol");
+    let sol = include_str!("./test_data/loop_storage.sol");
+    let mut analyzer = Analyzer {
+        storage_from_constructor: true,
+        ..Default::default()
+    };
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Counter");
+
+    // the bounds of each storage variable after each function, joined over its successful paths
+    let expected = [("bump", "count", 0, 255), ("mark", "last", 1, 255)];
+    for (func_name, name, min, max) in expected {
+        let ranges = leaves_of(&mut analyzer, contract, func_name)
+            .into_iter()
+            .map(|leaf| {
+                leaf.storage_var_by_name(&analyzer, name)
+                    .unwrap()
+                    .unwrap_or_else(|| panic!("A path through {func_name} doesn't access {name}"))
+                    .range(&analyzer)
+                    .unwrap()
+            })
+            .collect();
+        let range = join_ranges(ranges, &analyzer)
+            .unwrap()
+            .unwrap_or_else(|| panic!("Unknown range for storage variable: {name}"));
+        let min_val = range
+            .evaled_range_min(&analyzer)
+            .unwrap()
+            .maybe_concrete()
+            .and_then(|min| min.val.into_u256());
+        let max_val = range
+            .evaled_range_max(&analyzer)
+            .unwrap()
+            .maybe_concrete()
+            .and_then(|max| max.val.into_u256());
+        assert_eq!(
+            (min_val, max_val),
+            (Some(U256::from(min)), Some(U256::from(max))),
+            "Unexpected range for {name} after {func_name}"
+        );
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+/// Parses a source file with the given analyzer and finds the contract with the given name in it
+fn parse_contract(
+    analyzer: &mut Analyzer,
+    path_str: &str,
+    sol: &str,
+    contract_name: &str,
+) -> (NodeIdx, ContractNode, Sources) {
+    let (entry, all_sources) = parse_file(analyzer, path_str, sol);
+    let contract = analyzer
+        .search_children(entry, &Edge::Contract)
+        .into_iter()
+        .map(ContractNode::from)
+        .find(|contract| contract.name(analyzer).unwrap() == contract_name)
+        .unwrap_or_else(|| panic!("No contract named: {contract_name}"));
+    (entry, contract, all_sources)
+}
+
+/// Gets the context of the body of the function of a contract with the given name, which may leave out the parameter
+/// list of its signature
+fn body_ctx_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> ContextNode {
+    let func = contract
+        .funcs(analyzer)
+        .into_iter()
+        .find(|func| {
+            let name = func.name(analyzer).unwrap();
+            name == func_name || name.split('(').next() == Some(func_name)
+        })
+        .unwrap_or_else(|| panic!("No function named: {func_name}"));
+    func.body_ctx(analyzer)
+}
+
+/// Gets the successful paths through the function of a contract with the given name, asserting that there is one
+fn leaves_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> Vec<ContextNode> {
+    let leaves = body_ctx_of(analyzer, contract, func_name)
+        .successful_leaves(analyzer)
+        .unwrap();
+    assert!(!leaves.is_empty(), "No successful path through {func_name}");
+    leaves
+}
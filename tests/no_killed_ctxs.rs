@@ -1,4 +1,7 @@
+use pyrometer::foundry::FoundryConfig;
+use pyrometer::Analyzer;
 use std::env;
+use std::path::PathBuf;
 mod helpers;
 use helpers::*;
 
@@ -173,3 +176,105 @@ fn test_remapping_import() {
         sol,
     );
 }
+
+#[test]
+fn test_foundry_project() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    project_assert_no_ctx_killed(format!("{manifest_dir}/tests/test_data/foundry_project"));
+}
+
+#[test]
+fn test_hardhat_project() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    project_assert_no_ctx_killed(format!("{manifest_dir}/tests/test_data/hardhat_project"));
+}
+
+#[test]
+fn test_standard_json() {
+    let json = include_str!("./test_data/standard_json.json");
+    standard_json_assert_no_ctx_killed(json);
+}
+
+#[test]
+fn test_standard_json_invalid_remapping() {
+    let json = r#"{
+        "language": "Solidity",
+        "sources": { "A.sol": { "content": "contract A {}" } },
+        "settings": { "remappings": ["@lib/"] }
+    }"#;
+    let mut analyzer = Analyzer::default();
+    let Err(err) = analyzer.parse_standard_json(json) else {
+        panic!("Expected standard-JSON input with an invalid remapping to be rejected");
+    };
+    assert!(
+        err.contains("Invalid remapping: \"@lib/\""),
+        "Unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_standard_json_context_remapping() {
+    let json = r#"{
+        "language": "Solidity",
+        "sources": { "A.sol": { "content": "contract A {}" } },
+        "settings": { "remappings": ["A.sol:@lib/=lib/"] }
+    }"#;
+    let mut analyzer = Analyzer::default();
+    let Err(err) = analyzer.parse_standard_json(json) else {
+        panic!("Expected standard-JSON input with a context-scoped remapping to be rejected");
+    };
+    assert!(
+        err.contains("Context-scoped remappings are not supported: \"A.sol:@lib/=lib/\""),
+        "Unexpected error: {err}"
+    );
+}
+
+fn assert_no_ctx_killed(path_str: String, sol: &str) {
+    let mut analyzer = Analyzer::default();
+    let (entry, all_sources) = parse_file(&mut analyzer, &path_str, sol);
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+fn remapping_assert_no_ctx_killed(path_str: String, remapping_file: String, sol: &str) {
+    let mut analyzer = Analyzer::default();
+    analyzer.set_remappings_and_root(remapping_file);
+    let (entry, all_sources) = parse_file(&mut analyzer, &path_str, sol);
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+fn project_assert_no_ctx_killed(root: String) {
+    let mut analyzer = Analyzer::default();
+    let config = FoundryConfig::from_root(&PathBuf::from(root)).unwrap();
+    analyzer.set_foundry_project(&config).unwrap();
+    let (entries, all_sources) = analyzer.parse_project(&config.source_files(false)).unwrap();
+    assert_eq!(entries.len(), config.source_files(false).len());
+    entries.into_iter().for_each(|entry| {
+        let (_, path_str, _, _) = all_sources
+            .iter()
+            .find(|(maybe_entry, ..)| *maybe_entry == Some(entry))
+            .unwrap();
+        no_ctx_killed(
+            analyzer.clone(),
+            entry,
+            path_str.clone(),
+            all_sources.clone(),
+        );
+    });
+}
+
+fn standard_json_assert_no_ctx_killed(json: &str) {
+    let mut analyzer = Analyzer::default();
+    let (entries, all_sources) = analyzer.parse_standard_json(json).unwrap();
+    entries.into_iter().for_each(|entry| {
+        let (_, path_str, _, _) = all_sources
+            .iter()
+            .find(|(maybe_entry, ..)| *maybe_entry == Some(entry))
+            .unwrap();
+        no_ctx_killed(
+            analyzer.clone(),
+            entry,
+            path_str.clone(),
+            all_sources.clone(),
+        );
+    });
+}
@@ -0,0 +1,327 @@
+use jsonschema::JSONSchema;
+use pyrometer::context::analyzers::{
+    FunctionVarsBoundAnalyzer, JsonError, JsonReport, ReportConfig, SarifDisplay, SarifLog,
+    JSON_SCHEMA_VERSION,
+};
+use pyrometer::context::exprs::ExprErr;
+use pyrometer::Analyzer;
+use shared::analyzer::Search;
+use shared::nodes::ContractNode;
+use shared::{Edge, NodeIdx};
+use solang_parser::pt::Loc;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+
+/// A path expected in a JSON report: the path, the kind of how it was killed, the error it reverted with, the events it
+/// emitted and the bounds of its returns
+type ExpectedPath<'a> = (
+    &'a str,
+    Option<&'a str>,
+    Option<&'a str>,
+    &'a [&'a str],
+    &'a [(&'a str, &'a str)],
+);
+
+#[test]
+fn test_json_output() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/output.sol");
+    let sol = include_str!("./test_data/output.sol");
+    let mut analyzer = Analyzer::default();
+    let (contract, all_sources) = parse_output(&mut analyzer, &path_str, sol);
+    let file_mapping: BTreeMap<_, _> = all_sources
+        .iter()
+        .map(|(_entry, name, _src, num)| (*num, name.clone()))
+        .collect();
+    let ctxs = contract
+        .funcs(&analyzer)
+        .into_iter()
+        .filter_map(|func| func.maybe_body_ctx(&mut analyzer))
+        .collect::<Vec<_>>();
+    let functions = ctxs
+        .into_iter()
+        .map(|ctx| {
+            analyzer
+                .bounds_for_all(&file_mapping, ctx, all_paths_config())
+                .as_json(&file_mapping, &analyzer)
+                .unwrap()
+        })
+        .collect();
+    let errors = analyzer
+        .expr_errs
+        .iter()
+        .map(|err| JsonError::new(&file_mapping, err))
+        .collect();
+    // check the report as `--format json` emits it
+    let json = serde_json::to_string_pretty(&JsonReport::new(functions, errors)).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(report["version"], JSON_SCHEMA_VERSION, "Unexpected version");
+    assert_eq!(report["errors"], serde_json::json!([]), "Unexpected errors");
+    assert_json_spans(&report, &path_str);
+
+    let expected: &[(&str, &[ExpectedPath])] = &[
+        (
+            "check(uint256)",
+            &[
+                (
+                    "check(uint256).fork{ true }",
+                    Some("revert"),
+                    Some("TooLarge"),
+                    &[],
+                    &[],
+                ),
+                (
+                    "check(uint256).fork{ false }",
+                    None,
+                    None,
+                    &["Checked"],
+                    &[("1", "11")],
+                ),
+            ],
+        ),
+        (
+            "reason()",
+            &[("reason()", Some("revert"), Some("Error"), &[], &[])],
+        ),
+    ];
+    let functions = report["functions"].as_array().unwrap();
+    for (func_name, paths) in expected {
+        let function = functions
+            .iter()
+            .find(|function| function["function"] == *func_name)
+            .unwrap_or_else(|| panic!("No report for function: {func_name}"));
+        let found = function["contexts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|ctx| {
+                let events = ctx["emits"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|emit| emit["event"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>();
+                let returns = ctx["returns"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|ret| {
+                        let part = &ret["parts"][0];
+                        (
+                            part["min"].as_str().unwrap().to_string(),
+                            part["max"].as_str().unwrap().to_string(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                (
+                    ctx["path"].as_str().unwrap().to_string(),
+                    ctx["killed"]["kind"].as_str().map(str::to_string),
+                    ctx["reverts_with"]["error"].as_str().map(str::to_string),
+                    events,
+                    returns,
+                )
+            })
+            .collect::<Vec<_>>();
+        let paths = paths
+            .iter()
+            .map(|(path, killed, reverts_with, emits, returns)| {
+                (
+                    path.to_string(),
+                    killed.map(str::to_string),
+                    reverts_with.map(str::to_string),
+                    emits
+                        .iter()
+                        .map(|emit| emit.to_string())
+                        .collect::<Vec<_>>(),
+                    returns
+                        .iter()
+                        .map(|(min, max)| (min.to_string(), max.to_string()))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(found, paths, "Unexpected paths through {func_name}");
+    }
+}
+
+#[test]
+fn test_sarif_output() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/output.sol");
+    let sol = include_str!("./test_data/output.sol");
+    let mut analyzer = Analyzer::default();
+    let (contract, all_sources) = parse_output(&mut analyzer, &path_str, sol);
+    let file_mapping: BTreeMap<_, _> = all_sources
+        .iter()
+        .map(|(_entry, name, _src, num)| (*num, name.clone()))
+        .collect();
+    let ctxs = contract
+        .funcs(&analyzer)
+        .into_iter()
+        .filter_map(|func| func.maybe_body_ctx(&mut analyzer))
+        .collect::<Vec<_>>();
+    let mut results = ctxs
+        .into_iter()
+        .flat_map(|ctx| {
+            analyzer
+                .bounds_for_all(&file_mapping, ctx, all_paths_config())
+                .as_cli_compat(&file_mapping)
+                .sarif_results(&analyzer)
+        })
+        .collect::<Vec<_>>();
+    results.extend(
+        analyzer
+            .expr_errs
+            .iter()
+            .map(|err| err.sarif_result(&file_mapping)),
+    );
+    let srcs = all_sources
+        .iter()
+        .map(|(_entry, name, src, _num)| (name.clone(), src.clone()))
+        .collect();
+    // check the log as `--format sarif` emits it
+    let json = serde_json::to_string_pretty(&SarifLog::new(results, &srcs)).unwrap();
+    let log: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_sarif_valid(&log);
+
+    // the results other than bounds, with the lines and columns their regions start and end at
+    let found = log["runs"][0]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|result| result["ruleId"] != "pyrometer/bounds")
+        .map(|result| {
+            let region = &result["locations"][0]["physicalLocation"]["region"];
+            (
+                result["ruleId"].as_str().unwrap(),
+                (
+                    region["startLine"].as_u64().unwrap(),
+                    region["startColumn"].as_u64().unwrap(),
+                ),
+                (
+                    region["endLine"].as_u64().unwrap(),
+                    region["endColumn"].as_u64().unwrap(),
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            ("pyrometer/revert", (7, 13), (7, 31)),
+            ("pyrometer/revert", (14, 9), (14, 30)),
+        ],
+        "Unexpected SARIF results"
+    );
+}
+
+#[test]
+fn test_builtin_loc_error() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/output.sol");
+    let sol = include_str!("./test_data/output.sol");
+    // an error at a loc outside of any file, i.e. of a builtin, is reported at the start of the entry file
+    let file_mapping = vec![(0usize, path_str.clone())].into_iter().collect();
+    let err = ExprErr::Todo(Loc::Builtin, "builtin".to_string());
+    let json = serde_json::to_string(&JsonError::new(&file_mapping, &err)).unwrap();
+    let error: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        error["span"],
+        serde_json::json!({ "file": path_str, "start": 0, "end": 0 }),
+        "Unexpected JSON span of an error outside of a file"
+    );
+
+    let srcs = vec![(path_str.clone(), sol.to_string())]
+        .into_iter()
+        .collect();
+    let sarif = serde_json::to_string(&SarifLog::new(vec![err.sarif_result(&file_mapping)], &srcs))
+        .unwrap();
+    let log: serde_json::Value = serde_json::from_str(&sarif).unwrap();
+    assert_sarif_valid(&log);
+    assert_eq!(
+        log["runs"][0]["results"][0]["locations"][0]["physicalLocation"],
+        serde_json::json!({
+            "artifactLocation": { "uri": path_str },
+            "region": {
+                "startLine": 1,
+                "startColumn": 1,
+                "endLine": 1,
+                "endColumn": 1,
+                "byteOffset": 0,
+                "byteLength": 0,
+            },
+        }),
+        "Unexpected SARIF location of an error outside of a file"
+    );
+}
+
+/// Asserts that every span in a JSON value is into the given file and has its start before its end
+fn assert_json_spans(value: &serde_json::Value, path_str: &str) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            if let Some(span) = fields.get("span") {
+                assert_eq!(span["file"], path_str, "Span into another file");
+                assert!(
+                    span["start"].as_u64().unwrap() <= span["end"].as_u64().unwrap(),
+                    "Span ends before it starts: {span}"
+                );
+            }
+            fields
+                .values()
+                .for_each(|value| assert_json_spans(value, path_str));
+        }
+        serde_json::Value::Array(values) => values
+            .iter()
+            .for_each(|value| assert_json_spans(value, path_str)),
+        _ => {}
+    }
+}
+
+/// Asserts that a SARIF log is valid against the definitions of the SARIF 2.1.0 schema for the objects pyrometer emits
+fn assert_sarif_valid(log: &serde_json::Value) {
+    let schema =
+        serde_json::from_str(include_str!("./test_data/sarif-schema-2.1.0-subset.json")).unwrap();
+    let schema = JSONSchema::compile(&schema).unwrap();
+    let errors = match schema.validate(log) {
+        Ok(()) => return,
+        Err(errors) => errors
+            .map(|err| format!("{}: {err}", err.instance_path))
+            .collect::<Vec<_>>(),
+    };
+    panic!("Invalid SARIF log:\n{}", errors.join("\n"));
+}
+
+/// Parses the output fixture and finds its `Output` contract. Returns the contract and every parsed source, including
+/// the fixture itself
+fn parse_output(
+    analyzer: &mut Analyzer,
+    path_str: &str,
+    sol: &str,
+) -> (ContractNode, Vec<(Option<NodeIdx>, String, String, usize)>) {
+    let (maybe_entry, mut all_sources) = analyzer.parse(sol, &PathBuf::from(path_str), true);
+    all_sources.push((maybe_entry, path_str.to_string(), sol.to_string(), 0));
+    let contract = analyzer
+        .search_children(maybe_entry.unwrap(), &Edge::Contract)
+        .into_iter()
+        .map(ContractNode::from)
+        .find(|contract| contract.name(analyzer).unwrap() == "Output")
+        .unwrap();
+    (contract, all_sources)
+}
+
+/// A report config that shows every variable of every path
+fn all_paths_config() -> ReportConfig {
+    ReportConfig {
+        eval_bounds: true,
+        simplify_bounds: false,
+        show_tmps: true,
+        show_consts: true,
+        show_symbolics: true,
+        show_initial_bounds: true,
+        show_all_lines: true,
+        show_reverts: true,
+        show_unreachables: true,
+        show_nonreverts: true,
+    }
+}
@@ -0,0 +1,118 @@
+use pyrometer::Analyzer;
+use shared::analyzer::Search;
+use shared::context::{ContextNode, KilledKind};
+use shared::nodes::ContractNode;
+use shared::smt::{SmtEncoder, SmtSolver, DEFAULT_SMT_TIMEOUT_MS};
+use shared::{Edge, NodeIdx};
+use std::env;
+mod helpers;
+use helpers::*;
+
+#[test]
+fn test_smt_encoding() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/smt.sol");
+    let sol = include_str!("./test_data/smt.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Smt");
+
+    // fragments of the SMT-LIB2 script encoding the dependencies of every successful path through each function
+    let expected: &[(&str, &[&str])] = &[
+        (
+            "ordered",
+            &["(set-logic QF_BV)", "(assert (bvult ", "(check-sat)"],
+        ),
+        ("signed", &["(assert (bvsgt (bvadd "]),
+        ("nonZero", &["(assert (not (= "]),
+    ];
+    for (func_name, fragments) in expected {
+        for leaf in leaves_of(&mut analyzer, contract, func_name) {
+            let script = SmtEncoder::encode_ctx(leaf, &analyzer)
+                .unwrap()
+                .unwrap_or_else(|| panic!("Nothing to encode for {func_name}"));
+            for fragment in fragments.iter() {
+                assert!(
+                    script.contains(fragment),
+                    "Expected {fragment} in the encoding of {func_name}:\n{script}"
+                );
+            }
+        }
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+#[test]
+#[ignore = "requires z3 in PATH"]
+fn test_smt_solver() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/smt_unsat.sol");
+    let sol = include_str!("./test_data/smt_unsat.sol");
+    let mut analyzer = Analyzer {
+        smt_solver: Some(SmtSolver::z3("z3", DEFAULT_SMT_TIMEOUT_MS)),
+        ..Default::default()
+    };
+    let (_, contract, _) = parse_contract(&mut analyzer, &path_str, sol, "SmtUnsat");
+    assert!(
+        analyzer.expr_errs.is_empty(),
+        "Analyzer encountered errors: {:?}",
+        analyzer.expr_errs
+    );
+
+    // the solver kills the only path through a function with contradicting requirements as unreachable
+    let body_ctx = body_ctx_of(&mut analyzer, contract, "contradiction");
+    assert!(
+        body_ctx.successful_leaves(&analyzer).unwrap().is_empty(),
+        "contradiction has a successful path"
+    );
+    let mut ctxs = vec![body_ctx];
+    ctxs.extend(body_ctx.descendants(&analyzer).unwrap());
+    assert!(
+        ctxs.iter().any(|ctx| matches!(
+            ctx.killed_loc(&analyzer).unwrap(),
+            Some((_, KilledKind::Unreachable))
+        )),
+        "No path through contradiction was killed as unreachable"
+    );
+    // but not one through a function with consistent requirements
+    leaves_of(&mut analyzer, contract, "consistent");
+}
+
+/// Parses a source file with the given analyzer and finds the contract with the given name in it
+fn parse_contract(
+    analyzer: &mut Analyzer,
+    path_str: &str,
+    sol: &str,
+    contract_name: &str,
+) -> (NodeIdx, ContractNode, Sources) {
+    let (entry, all_sources) = parse_file(analyzer, path_str, sol);
+    let contract = analyzer
+        .search_children(entry, &Edge::Contract)
+        .into_iter()
+        .map(ContractNode::from)
+        .find(|contract| contract.name(analyzer).unwrap() == contract_name)
+        .unwrap_or_else(|| panic!("No contract named: {contract_name}"));
+    (entry, contract, all_sources)
+}
+
+/// Gets the context of the body of the function of a contract with the given name, which may leave out the parameter
+/// list of its signature
+fn body_ctx_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> ContextNode {
+    let func = contract
+        .funcs(analyzer)
+        .into_iter()
+        .find(|func| {
+            let name = func.name(analyzer).unwrap();
+            name == func_name || name.split('(').next() == Some(func_name)
+        })
+        .unwrap_or_else(|| panic!("No function named: {func_name}"));
+    func.body_ctx(analyzer)
+}
+
+/// Gets the successful paths through the function of a contract with the given name, asserting that there is one
+fn leaves_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> Vec<ContextNode> {
+    let leaves = body_ctx_of(analyzer, contract, func_name)
+        .successful_leaves(analyzer)
+        .unwrap();
+    assert!(!leaves.is_empty(), "No successful path through {func_name}");
+    leaves
+}
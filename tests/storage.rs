@@ -0,0 +1,226 @@
+use ethers_core::types::{H256, U256};
+use pyrometer::Analyzer;
+use shared::analyzer::Search;
+use shared::context::ContextNode;
+use shared::nodes::{dynamic_array_slot, erc7201_slot, mapping_slot, ContractNode};
+use shared::range::{Range, SolcRange};
+use shared::{Edge, NodeIdx};
+use std::env;
+mod helpers;
+use helpers::*;
+
+#[test]
+fn test_storage_from_constructor() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/storage_from_constructor.sol");
+    let sol = include_str!("./test_data/storage_from_constructor.sol");
+    let mut analyzer = Analyzer {
+        storage_from_constructor: true,
+        ..Default::default()
+    };
+    let (entry, all_sources) = parse_file(&mut analyzer, &path_str, sol);
+
+    // the bounds each storage variable starts functions with
+    let expected = [
+        ("cap", 1, 1000),
+        ("fee", 30, 30),
+        ("total", 0, 0),
+        ("rate", 5, 10),
+        ("limit", 100, 100),
+    ];
+    for (name, min, max) in expected {
+        let (_, range) = analyzer
+            .storage_entry
+            .iter()
+            .find(|(var, _)| var.name(&analyzer).unwrap() == name)
+            .unwrap_or_else(|| panic!("No entry range for storage variable: {name}"));
+        assert_eq!(
+            concrete_bounds(&analyzer, range),
+            (Some(U256::from(min)), Some(U256::from(max))),
+            "Unexpected entry range for storage variable: {name}"
+        );
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+#[test]
+fn test_storage_slots() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/storage_slots.sol");
+    let sol = include_str!("./test_data/storage_slots.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Slots");
+    let layout = contract
+        .storage_layout(&analyzer)
+        .unwrap()
+        .into_iter()
+        .map(|slot| {
+            (
+                slot.var.name(&analyzer).unwrap(),
+                slot.slot.as_u64(),
+                slot.offset,
+            )
+        })
+        .collect::<Vec<_>>();
+    let expected = [
+        ("a", 0, 0),
+        ("b", 1, 0),
+        ("c", 1, 16),
+        ("m", 2, 0),
+        ("small", 3, 0),
+        ("d", 5, 0),
+    ]
+    .map(|(name, slot, offset)| (name.to_string(), slot, offset));
+    assert_eq!(layout, expected, "Unexpected storage layout");
+
+    // the bounds of storage variables after functions that access them through their slots
+    let after = [
+        ("storeD", "d", 0, 9),
+        ("copyD", "a", 7, 7),
+        ("slots", "a", 5, 5),
+        ("slots", "d", 16, 16),
+    ];
+    for (func_name, name, min, max) in after {
+        for leaf in leaves_of(&mut analyzer, contract, func_name) {
+            let range = leaf
+                .storage_var_by_name(&analyzer, name)
+                .unwrap()
+                .unwrap_or_else(|| panic!("{func_name} doesn't access storage variable: {name}"))
+                .range(&analyzer)
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                concrete_bounds(&analyzer, &range),
+                (Some(U256::from(min)), Some(U256::from(max))),
+                "Unexpected range for {name} after {func_name}"
+            );
+        }
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+#[test]
+fn test_slot_derivation() {
+    assert_eq!(
+        dynamic_array_slot(U256::zero()),
+        U256::from("0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563")
+    );
+    assert_eq!(
+        mapping_slot(H256::zero(), U256::zero()),
+        U256::from("0xad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5")
+    );
+    // the example namespace of the ERC-7201 spec
+    assert_eq!(
+        erc7201_slot("example.main"),
+        U256::from("0x183a6125c38840424c4a85fa12bab2ab606c4b6d0e7cc73c0c06ba5300eab500")
+    );
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path_str = format!("{manifest_dir}/tests/test_data/storage_elements.sol");
+    let sol = include_str!("./test_data/storage_elements.sol");
+    let mut analyzer = Analyzer::default();
+    let (entry, contract, all_sources) = parse_contract(&mut analyzer, &path_str, sol, "Elements");
+
+    // the elements and slots each function returns
+    let expected = [
+        ("mappingLoad", U256::from(7)),
+        ("mappingStore", U256::from(9)),
+        ("arrayLoad", U256::from(5)),
+        ("arrayStore", U256::from(3)),
+        (
+            "mappingSlot",
+            mapping_slot(H256::from_low_u64_be(3), U256::from(2)),
+        ),
+        ("arraySlot", dynamic_array_slot(U256::one())),
+        ("namespace", erc7201_slot("example.main")),
+    ];
+    for (func_name, value) in expected {
+        for rets in const_returns(&mut analyzer, contract, func_name) {
+            assert_eq!(
+                rets,
+                [Some(value)],
+                "Unexpected return values of {func_name}"
+            );
+        }
+    }
+    no_ctx_killed(analyzer, entry, path_str, all_sources);
+}
+
+/// The values each successful path through a function of a contract returns, if constant
+fn const_returns(
+    analyzer: &mut Analyzer,
+    contract: ContractNode,
+    func_name: &str,
+) -> Vec<Vec<Option<U256>>> {
+    let leaves = leaves_of(analyzer, contract, func_name);
+    leaves
+        .into_iter()
+        .map(|leaf| {
+            let rets = leaf.underlying(analyzer).unwrap().ret.clone();
+            rets.into_iter()
+                .map(|(_, ret)| {
+                    let range = ret.range(analyzer).unwrap()?;
+                    match concrete_bounds(analyzer, &range) {
+                        (Some(min), Some(max)) if min == max => Some(min),
+                        _ => None,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The evaluated bounds of a range, if concrete
+fn concrete_bounds(analyzer: &Analyzer, range: &SolcRange) -> (Option<U256>, Option<U256>) {
+    let min = range
+        .evaled_range_min(analyzer)
+        .unwrap()
+        .maybe_concrete()
+        .and_then(|min| min.val.into_u256());
+    let max = range
+        .evaled_range_max(analyzer)
+        .unwrap()
+        .maybe_concrete()
+        .and_then(|max| max.val.into_u256());
+    (min, max)
+}
+
+/// Parses a source file with the given analyzer and finds the contract with the given name in it
+fn parse_contract(
+    analyzer: &mut Analyzer,
+    path_str: &str,
+    sol: &str,
+    contract_name: &str,
+) -> (NodeIdx, ContractNode, Sources) {
+    let (entry, all_sources) = parse_file(analyzer, path_str, sol);
+    let contract = analyzer
+        .search_children(entry, &Edge::Contract)
+        .into_iter()
+        .map(ContractNode::from)
+        .find(|contract| contract.name(analyzer).unwrap() == contract_name)
+        .unwrap_or_else(|| panic!("No contract named: {contract_name}"));
+    (entry, contract, all_sources)
+}
+
+/// Gets the context of the body of the function of a contract with the given name, which may leave out the parameter
+/// list of its signature
+fn body_ctx_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> ContextNode {
+    let func = contract
+        .funcs(analyzer)
+        .into_iter()
+        .find(|func| {
+            let name = func.name(analyzer).unwrap();
+            name == func_name || name.split('(').next() == Some(func_name)
+        })
+        .unwrap_or_else(|| panic!("No function named: {func_name}"));
+    func.body_ctx(analyzer)
+}
+
+/// Gets the successful paths through the function of a contract with the given name, asserting that there is one
+fn leaves_of(analyzer: &mut Analyzer, contract: ContractNode, func_name: &str) -> Vec<ContextNode> {
+    let leaves = body_ctx_of(analyzer, contract, func_name)
+        .successful_leaves(analyzer)
+        .unwrap();
+    assert!(!leaves.is_empty(), "No successful path through {func_name}");
+    leaves
+}
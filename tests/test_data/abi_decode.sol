@@ -0,0 +1,35 @@
+contract Decode {
+    struct Pair {
+        uint256 a;
+        address b;
+    }
+
+    uint256 stored;
+    uint256 other;
+
+    function roundTrip(uint256 x) public {
+        require(x > 10 && x < 20);
+        bytes memory data = abi.encode(x, msg.sender);
+        (uint256 y, address z) = abi.decode(data, (uint256, address));
+        stored = y;
+    }
+
+    function concreteRoundTrip() public {
+        stored = abi.decode(abi.encode(uint256(7)), (uint256));
+    }
+
+    function calldataArgs(uint256 x, address y) public {
+        (uint256 a, address b) = abi.decode(msg.data[4:], (uint256, address));
+        stored = a;
+    }
+
+    function decodeStruct(bytes memory data) public {
+        Pair memory p = abi.decode(data, (Pair));
+        other = p.a;
+    }
+
+    function decodeArray(bytes memory data) public {
+        uint256[] memory arr = abi.decode(data, (uint256[]));
+        other = arr.length;
+    }
+}
@@ -0,0 +1,40 @@
+contract Owned {
+    struct Config {
+        uint256 fee;
+        address treasury;
+    }
+
+    address owner;
+    Config config;
+    mapping(address => uint256) balances;
+
+    constructor() {
+        owner = msg.sender;
+    }
+
+    modifier onlyOwner() {
+        require(msg.sender == owner);
+        _;
+    }
+
+    function setOwner(address newOwner) public onlyOwner {
+        owner = newOwner;
+    }
+
+    function claimOwnership() public {
+        owner = msg.sender;
+    }
+
+    function setFee(uint256 fee) public onlyOwner {
+        require(fee < 100);
+        config.fee = fee;
+    }
+
+    function setTreasury(address treasury) public {
+        config.treasury = treasury;
+    }
+
+    function deposit() public payable {
+        balances[msg.sender] += msg.value;
+    }
+}
@@ -0,0 +1,30 @@
+contract Bounds {
+    uint256[] values;
+    uint256[3] fixedValues;
+
+    function fixedIn() public view returns (uint256) {
+        return fixedValues[2];
+    }
+
+    function fixedOut() public view returns (uint256) {
+        uint256 i = 3;
+        return fixedValues[i];
+    }
+
+    function fixedAny(uint256 i) public view returns (uint256) {
+        return fixedValues[i];
+    }
+
+    function fixedBounded(uint256 i) public view returns (uint256) {
+        require(i < 3);
+        return fixedValues[i];
+    }
+
+    function bytesAny(bytes32 b, uint256 i) public pure returns (bytes1) {
+        return b[i];
+    }
+
+    function popAny() public {
+        values.pop();
+    }
+}
@@ -0,0 +1,46 @@
+contract Wallet {
+    address lastCaller;
+
+    function deposit() external payable returns (uint256) {
+        return msg.value;
+    }
+
+    function caller() external view returns (address) {
+        return msg.sender;
+    }
+
+    modifier recordCaller() {
+        lastCaller = msg.sender;
+        _;
+    }
+
+    function callerThrough() external recordCaller returns (address) {
+        return sender();
+    }
+
+    function sender() internal view returns (address) {
+        return msg.sender;
+    }
+}
+
+contract Sender {
+    uint256 paid;
+    uint256 unpaid;
+    address seenAs;
+
+    function pay(Wallet wallet) public payable {
+        paid = wallet.deposit{value: 5}();
+    }
+
+    function payNothing(Wallet wallet) public {
+        unpaid = wallet.deposit();
+    }
+
+    function whoAmI(Wallet wallet) public {
+        seenAs = wallet.caller();
+    }
+
+    function whoAmIThrough(Wallet wallet) public {
+        seenAs = wallet.callerThrough();
+    }
+}
@@ -0,0 +1,39 @@
+interface IFee {
+    function fee(uint256 amount) external view returns (uint256);
+}
+
+interface IScale {
+    function scale(uint256) external view returns (uint256);
+}
+
+contract Router {
+    uint256 lastFee;
+    uint256 lastScaled;
+
+    function route(IFee feeModel) public {
+        lastFee = feeModel.fee(50);
+    }
+
+    function routeScaled(IScale scaler) public {
+        lastScaled = scaler.scale(30);
+    }
+}
+
+contract FlatFee is IFee {
+    function fee(uint256) external pure returns (uint256) {
+        return 10;
+    }
+}
+
+contract ScaledFee is IFee {
+    function fee(uint256 amount) external view returns (uint256) {
+        require(msg.sender != address(0));
+        return amount / 10;
+    }
+}
+
+contract DoubleScale is IScale {
+    function scale(uint256 amount) external pure returns (uint256) {
+        return amount * 2;
+    }
+}
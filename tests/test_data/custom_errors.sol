@@ -0,0 +1,41 @@
+error Unauthorized();
+
+interface IVault {
+    error Locked(uint256 until);
+}
+
+contract ErrorsBase {
+    error InsufficientBalance(uint256 available, uint256 required);
+}
+
+contract CustomErrors is ErrorsBase {
+    mapping(address => uint256) balances;
+    address owner;
+
+    function withdraw(uint256 amount) public {
+        uint256 available = balances[msg.sender];
+        if (available < amount) {
+            revert InsufficientBalance(available, amount);
+        }
+        balances[msg.sender] = available - amount;
+    }
+
+    function withdrawNamed(uint256 available) public {
+        require(available < 100);
+        revert InsufficientBalance({required: 100, available: available});
+    }
+
+    function onlyOwner() public {
+        if (msg.sender != owner) {
+            revert Unauthorized();
+        }
+    }
+
+    function locked(uint256 until) public {
+        revert IVault.Locked(until);
+    }
+
+    function reason() public {
+        revert("not allowed");
+    }
+}
@@ -0,0 +1,48 @@
+event FreeEvent(uint256 indexed x);
+
+interface IEvents {
+    event Transfer(address indexed from, address indexed to, uint256 amount);
+}
+
+contract EventsBase {
+    event Inherited(uint256 a, uint256 b);
+}
+
+contract Events is EventsBase {
+    event Ping();
+    event Anon(uint256 x) anonymous;
+
+    mapping(address => uint256) balances;
+
+    function emitFree(uint256 x) public {
+        require(x < 100);
+        emit FreeEvent(x);
+    }
+
+    function emitNoArgs() public {
+        emit Ping();
+        emit Anon(1);
+    }
+
+    function emitInherited(uint256 a) public {
+        if (a > 10) {
+            emit Inherited(a, 10);
+        } else {
+            emit Inherited(a, a * 2);
+        }
+    }
+
+    function emitNamed(uint256 a, uint256 b) public {
+        require(a < 5);
+        require(b >= 5);
+        require(b < 10);
+        emit Inherited({b: b, a: a});
+    }
+
+    function emitMember(address to, uint256 amount) public {
+        require(amount <= balances[msg.sender]);
+        balances[msg.sender] -= amount;
+        balances[to] += amount;
+        emit IEvents.Transfer(msg.sender, to, amount);
+    }
+}
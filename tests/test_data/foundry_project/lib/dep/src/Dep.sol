@@ -0,0 +1,5 @@
+contract Dep {
+	function val() public pure returns (uint256) {
+		return 100;
+	}
+}
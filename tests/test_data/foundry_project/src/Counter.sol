@@ -0,0 +1,10 @@
+import "dep/Dep.sol";
+import "./nested/Math.sol";
+
+contract Counter is Dep {
+	uint256 public count;
+
+	function increment() public {
+		count = Math.add(val(), 1);
+	}
+}
@@ -0,0 +1,7 @@
+import "@dep/Dep.sol";
+
+library Math {
+	function add(uint256 a, uint256 b) internal pure returns (uint256) {
+		return a + b;
+	}
+}
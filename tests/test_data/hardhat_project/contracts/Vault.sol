@@ -0,0 +1,10 @@
+import "@oz/contracts/Ownable.sol";
+
+contract Vault is Ownable {
+	uint256 public total;
+
+	function deposit(uint256 amount) public {
+		require(amount > 0);
+		total = amount;
+	}
+}
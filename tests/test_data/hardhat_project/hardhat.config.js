@@ -0,0 +1,6 @@
+module.exports = {
+  solidity: "0.8.17",
+  paths: {
+    sources: "./contracts",
+  },
+};
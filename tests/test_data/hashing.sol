@@ -0,0 +1,33 @@
+contract Hashing {
+    function keccak() public pure returns (bytes32) {
+        return keccak256("MINTER_ROLE");
+    }
+
+    function sha() public pure returns (bytes32) {
+        return sha256("abc");
+    }
+
+    function ripemd() public pure returns (bytes20) {
+        return ripemd160("abc");
+    }
+
+    function selector() public pure returns (bytes4) {
+        return bytes4(keccak256("transfer(address,uint256)"));
+    }
+
+    function encode() public pure returns (bytes32, bytes32, bytes32) {
+        return (
+            keccak256(abi.encode(uint8(1))),
+            keccak256(abi.encodePacked(uint8(1))),
+            keccak256(abi.encodeWithSignature("f()"))
+        );
+    }
+
+    function yulEmpty() public pure returns (bytes32) {
+        bytes32 h;
+        assembly {
+            h := keccak256(0, 0)
+        }
+        return h;
+    }
+}
@@ -0,0 +1,38 @@
+contract Counter {
+    uint256 count;
+    uint8 score;
+    uint8 level;
+    uint256 immutable cap;
+    uint256 limit = 50;
+
+    constructor() {
+        cap = 100;
+    }
+
+    function increment() public {
+        require(count < 10);
+        count += 1;
+    }
+
+    function reset() public {
+        count = 0;
+    }
+
+    function bump(uint8 x) public {
+        require(x <= 3);
+        uint8 half = score / 2;
+        score = half + x;
+    }
+
+    function levelUp() public {
+        level += 1;
+    }
+
+    function getCap() public view returns (uint256) {
+        return cap;
+    }
+
+    function getLimit() public view returns (uint256) {
+        return limit;
+    }
+}
@@ -0,0 +1,21 @@
+contract Counter {
+    uint8 count;
+    uint8 last;
+    uint8 step = 3;
+
+    constructor() {
+        last = 1;
+    }
+
+    function bump(uint256 n) public {
+        for (uint256 i = 0; i < n; i++) {
+            count += 1;
+        }
+    }
+
+    function mark(uint256 n) public {
+        for (uint256 i = 0; i < n; i++) {
+            last = step;
+        }
+    }
+}
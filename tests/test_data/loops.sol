@@ -28,4 +28,97 @@ contract For {
         require(x == 10);
         return x;
     }
+
+    function counted_loop_def_iter() public returns (uint256) {
+        uint256 x = 0;
+        for (uint256 i = 1; i < 10; i++) {
+            i += 1;
+            x += 1;
+        }
+
+        require(x == 5);
+        return x;
+    }
+
+    function const_while_loop() public returns (uint256) {
+        uint256 x = 20;
+        while (x > 10) {
+            x -= 1;
+        }
+
+        require(x == 10);
+        return x;
+    }
+
+    function do_while_loop() public returns (uint256) {
+        uint256 x = 0;
+        do {
+            x += 1;
+        } while (x < 3);
+
+        require(x == 3);
+        return x;
+    }
+
+    function loop_break() public returns (uint256) {
+        uint256 x = 0;
+        for (uint256 i = 0; i < 10; i++) {
+            if (i == 5) {
+                break;
+            }
+            x += 2;
+        }
+
+        require(x == 10);
+        return x;
+    }
+
+    function loop_continue() public returns (uint256) {
+        uint256 x = 0;
+        for (uint256 i = 0; i < 10; i++) {
+            if (i < 5) {
+                continue;
+            }
+            x += 1;
+        }
+
+        require(x == 5);
+        return x;
+    }
+
+    function unbounded_loop(uint256 n) public returns (uint256) {
+        uint256 x = 0;
+        for (uint256 i = 0; i < n; i++) {
+            if (x > 100) {
+                break;
+            }
+            x += 1;
+        }
+        return x;
+    }
+
+    function widened_loop() public returns (uint256) {
+        uint256 i = 0;
+        uint256 x = 5;
+        while (i < 100) {
+            i += 1;
+        }
+
+        // only `i` is widened, and it is narrowed by the exit condition
+        if (i < 100) {
+            revert();
+        }
+        if (x != 5) {
+            revert();
+        }
+        return i;
+    }
+
+    function widened_for_loop(uint256 n) public returns (uint256) {
+        uint256 x = 0;
+        for (uint256 i = 0; i < n; i++) {
+            x += 2;
+        }
+        return x;
+    }
 }
@@ -0,0 +1,16 @@
+contract Output {
+    error TooLarge(uint256 x);
+    event Checked(uint256 x);
+
+    function check(uint256 x) public returns (uint256) {
+        if (x > 10) {
+            revert TooLarge(x);
+        }
+        emit Checked(x);
+        return x + 1;
+    }
+
+    function reason() public {
+        revert("not allowed");
+    }
+}
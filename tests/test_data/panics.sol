@@ -0,0 +1,52 @@
+contract Panics {
+    function divide(uint256 a, uint256 b) public pure returns (uint256) {
+        return a / b;
+    }
+
+    function safeDivide(uint256 a, uint256 b) public pure returns (uint256) {
+        require(b > 0);
+        return a / b;
+    }
+
+    function divZero(uint256 a) public pure returns (uint256) {
+        uint256 b = 0;
+        return a / b;
+    }
+
+    function add(uint256 a, uint256 b) public pure returns (uint256) {
+        return a + b;
+    }
+
+    function bounded(uint8 a) public pure returns (uint8) {
+        require(a < 10);
+        return a + 1;
+    }
+
+    function underflow() public pure returns (uint256) {
+        uint256 a = 1;
+        return a - 2;
+    }
+
+    function power(uint256 a, uint256 b) public pure returns (uint256) {
+        return a ** b;
+    }
+
+    function powerOverflow() public pure returns (uint256) {
+        uint256 a = 2;
+        return a ** 256;
+    }
+
+    function boundedPower(uint256 a) public pure returns (uint256) {
+        require(a < 10);
+        return a ** 3;
+    }
+
+    function negate(int8 a) public pure returns (int8) {
+        return -a;
+    }
+
+    function boundedNegate(int8 a) public pure returns (int8) {
+        require(a > -100);
+        return -a;
+    }
+}
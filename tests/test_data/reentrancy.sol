@@ -0,0 +1,47 @@
+interface IToken {
+    function transfer(address to, uint256 amount) external returns (bool);
+}
+
+contract Bank {
+    IToken token;
+    mapping(address => uint256) balances;
+    uint256 totalDeposits;
+    bool locked;
+
+    function deposit(uint256 amount) public {
+        balances[msg.sender] += amount;
+        totalDeposits += amount;
+    }
+
+    function withdraw(uint256 amount) public {
+        require(balances[msg.sender] >= amount);
+        msg.sender.call("");
+        balances[msg.sender] -= amount;
+    }
+
+    function withdrawSafe(uint256 amount) public {
+        require(balances[msg.sender] >= amount);
+        balances[msg.sender] -= amount;
+        msg.sender.call("");
+    }
+
+    function claim(uint256 amount) public {
+        require(totalDeposits >= amount);
+        token.transfer(msg.sender, amount);
+        totalDeposits -= amount;
+    }
+
+    function claimGuarded(uint256 amount) public {
+        require(!locked);
+        locked = true;
+        token.transfer(msg.sender, amount);
+        locked = false;
+    }
+
+    function relay(address from, address to, uint256 amount) public {
+        require(balances[from] >= amount);
+        balances[from] -= amount;
+        token.transfer(to, amount);
+        balances[to] += amount;
+    }
+}
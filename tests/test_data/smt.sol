@@ -0,0 +1,17 @@
+contract Smt {
+    function ordered(uint256 a, uint256 b, uint256 c) public pure returns (uint256) {
+        require(a < b);
+        require(b < c);
+        return c - a;
+    }
+
+    function signed(int256 a, int256 b) public pure returns (int256) {
+        require(a + b > -1);
+        return a;
+    }
+
+    function nonZero(uint256 b) public pure returns (uint256) {
+        require(b != 0);
+        return b;
+    }
+}
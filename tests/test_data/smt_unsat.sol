@@ -0,0 +1,12 @@
+contract SmtUnsat {
+    function contradiction(uint256 x) public pure returns (uint256) {
+        // twice any uint256 is even, also when it wraps around, but its range contains 7
+        require(x * 2 == 7);
+        return x;
+    }
+
+    function consistent(uint256 x) public pure returns (uint256) {
+        require(x * 2 == 8);
+        return x;
+    }
+}
@@ -0,0 +1,73 @@
+contract Elements {
+    mapping(uint256 => uint256) m;
+    uint256[] arr;
+
+    function mappingLoad(uint256 k) public returns (uint256) {
+        m[k] = 7;
+        uint256 v;
+        assembly {
+            mstore(0x00, k)
+            mstore(0x20, m.slot)
+            v := sload(keccak256(0x00, 0x40))
+        }
+        return v;
+    }
+
+    function mappingStore(uint256 k) public returns (uint256) {
+        assembly {
+            mstore(0x00, k)
+            mstore(0x20, m.slot)
+            let s := keccak256(0x00, 0x40)
+            sstore(s, 9)
+        }
+        return m[k];
+    }
+
+    function arrayLoad(uint256 i) public returns (uint256) {
+        arr[i] = 5;
+        uint256 v;
+        assembly {
+            mstore(0x00, arr.slot)
+            v := sload(add(keccak256(0x00, 0x20), i))
+        }
+        return v;
+    }
+
+    function arrayStore(uint256 i) public returns (uint256) {
+        require(i < arr.length);
+        assembly {
+            mstore(0x00, arr.slot)
+            sstore(add(keccak256(0x00, 0x20), i), 3)
+        }
+        return arr[i];
+    }
+
+    function mappingSlot() public pure returns (bytes32) {
+        bytes32 s;
+        assembly {
+            mstore(0x00, 3)
+            mstore(0x20, 2)
+            s := keccak256(0x00, 0x40)
+        }
+        return s;
+    }
+
+    function arraySlot() public pure returns (bytes32) {
+        bytes32 s;
+        assembly {
+            mstore(0x00, 1)
+            s := keccak256(0x00, 0x20)
+        }
+        return s;
+    }
+
+    function namespace() public pure returns (bytes32) {
+        uint256 id = uint256(keccak256("example.main"));
+        bytes32 s;
+        assembly {
+            mstore(0x00, sub(id, 1))
+            s := and(keccak256(0x00, 0x20), not(0xff))
+        }
+        return s;
+    }
+}
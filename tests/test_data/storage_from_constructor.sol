@@ -0,0 +1,52 @@
+contract Vault {
+    uint256 immutable cap;
+    uint256 public fee = 30;
+    uint256 total;
+    bool paused;
+    address owner;
+
+    constructor(uint256 _cap) {
+        require(_cap > 0);
+        require(_cap <= 1000);
+        cap = _cap;
+        owner = msg.sender;
+        require(total == 0);
+        require(fee == 30);
+        require(!paused);
+    }
+
+    function remaining() public view returns (uint256) {
+        return cap - total;
+    }
+
+    function feeOf(uint256 amount) public view returns (uint256) {
+        require(amount <= 1 ether);
+        return amount * fee / 10000;
+    }
+
+    function deposit(uint256 amount) public {
+        require(!paused);
+        require(total + amount <= cap);
+        total += amount;
+    }
+}
+
+contract Base {
+    uint256 rate = 5;
+    uint256 limit;
+
+    constructor() {
+        limit = 100;
+    }
+
+    function scaled(uint256 x) public view returns (uint256) {
+        require(x <= limit);
+        return x * rate;
+    }
+}
+
+contract Derived is Base {
+    constructor() {
+        rate = 10;
+    }
+}
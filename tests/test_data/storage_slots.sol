@@ -0,0 +1,43 @@
+contract Base {
+    uint256 a;
+}
+
+contract Slots is Base {
+    uint128 b;
+    uint64 c;
+    mapping(uint256 => uint256) m;
+    uint8[40] small;
+    uint256 d;
+    uint256 constant K = 1;
+    uint256 immutable I;
+
+    constructor() {
+        I = 2;
+    }
+
+    function storeD(uint256 v) public {
+        require(v < 10);
+        assembly {
+            sstore(5, v)
+        }
+    }
+
+    function copyD() public {
+        d = 7;
+        assembly {
+            let x := sload(5)
+            sstore(0, x)
+        }
+    }
+
+    function slots() public {
+        uint256 s;
+        uint256 o;
+        assembly {
+            s := d.slot
+            o := c.offset
+        }
+        a = s;
+        d = o;
+    }
+}
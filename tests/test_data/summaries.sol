@@ -0,0 +1,48 @@
+contract Summaries {
+    uint256 total;
+    uint256 last;
+
+    function setLast(uint256 x) internal {
+        last = x;
+    }
+
+    function first(uint256 x, uint256 y) internal pure returns (uint256) {
+        return x;
+    }
+
+    function double(uint256 x) internal pure returns (uint256) {
+        return x * 2;
+    }
+
+    function bounded(uint256 x) internal pure returns (uint256) {
+        require(x < 10);
+        return x;
+    }
+
+    function scaled(uint256 x) internal pure returns (uint256) {
+        require(x < 100);
+        return x * 3 + 1;
+    }
+
+    function useSummaries() public {
+        setLast(5);
+        require(last == 5);
+        uint256 y = first(7, 8);
+        require(y == 7);
+        total = double(y);
+        require(total == 14);
+    }
+
+    function useBounded() public {
+        total = bounded(50);
+    }
+
+    function useScaled(uint256 x) public {
+        total = scaled(x);
+        last = x;
+    }
+
+    function useScaledOutOfRange() public {
+        total = scaled(100);
+    }
+}
@@ -0,0 +1,45 @@
+contract Vault {
+    address payable owner;
+    address impl;
+    uint256[] values;
+
+    constructor() {
+        owner = payable(msg.sender);
+    }
+
+    function setImpl(address newImpl) public {
+        impl = newImpl;
+    }
+
+    function forward(bytes memory data) public {
+        impl.delegatecall(data);
+    }
+
+    function direct(address target, bytes memory data) public {
+        target.delegatecall(data);
+    }
+
+    function pay(uint256 amount) public {
+        payable(msg.sender).transfer(amount);
+    }
+
+    function payBack() public payable {
+        payable(msg.sender).transfer(msg.value);
+    }
+
+    function get(uint256 i) public view returns (uint256) {
+        return values[i];
+    }
+
+    function getFirst() public view returns (uint256) {
+        return values[0];
+    }
+
+    function kill() public {
+        selfdestruct(owner);
+    }
+
+    function killTo(address payable to) public {
+        selfdestruct(to);
+    }
+}
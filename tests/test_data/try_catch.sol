@@ -0,0 +1,54 @@
+interface IOracle {
+    function price(uint256 asset) external returns (uint256);
+    function prices(uint256 a, uint256 b) external returns (uint256, uint256);
+    function poke() external;
+}
+
+contract TryCatch {
+    IOracle oracle;
+    uint256 lastPrice;
+
+    function tryPrice(uint256 asset) public returns (uint256) {
+        try oracle.price(asset) returns (uint256 p) {
+            lastPrice = p;
+            return p;
+        } catch Error(string memory reason) {
+            return 0;
+        } catch Panic(uint256 code) {
+            require(code <= 0x51);
+            return 1;
+        } catch {
+            return 2;
+        }
+    }
+
+    function tryMulti(uint256 a, uint256 b) public returns (uint256) {
+        uint256 x;
+        try oracle.prices(a, b) returns (uint256 pa, uint256) {
+            x = pa;
+        } catch (bytes memory data) {
+            x = 1;
+        }
+        return x;
+    }
+
+    function tryNoReturns() public returns (bool) {
+        bool ok;
+        try oracle.poke() {
+            ok = true;
+        } catch {
+            ok = false;
+        }
+        return ok;
+    }
+
+    function tryCounter() public returns (uint256) {
+        uint256 i = 0;
+        try oracle.price(i++) returns (uint256) {
+            lastPrice = i;
+        } catch {
+            lastPrice = i;
+        }
+        return i;
+    }
+}
@@ -0,0 +1,52 @@
+contract Math {
+    function boundedAdd(uint256 a) public pure returns (uint256) {
+        require(a < 100);
+        return a + 1;
+    }
+
+    function anyAdd(uint256 a, uint256 b) public pure returns (uint256) {
+        return a + b;
+    }
+
+    function counter() public pure returns (uint256) {
+        uint256 i = 5;
+        i++;
+        return i;
+    }
+
+    function boundedMul(uint256 a, uint256 b) public pure returns (uint256) {
+        require(a < 1000);
+        require(b < 1000);
+        return a * b;
+    }
+
+    function uncheckedDec(uint256 a) public pure returns (uint256) {
+        unchecked {
+            return a - 1;
+        }
+    }
+
+    function uncheckedInc(uint256 a) public pure returns (uint256) {
+        require(a < 10);
+        unchecked {
+            a++;
+        }
+        return a;
+    }
+
+    function boundedPow(uint256 a) public pure returns (uint256) {
+        require(a < 10);
+        return a ** 2;
+    }
+
+    function boundedNegate(int256 a) public pure returns (int256) {
+        require(a > -100);
+        return -a;
+    }
+
+    function uncheckedNegate(int256 a) public pure returns (int256) {
+        unchecked {
+            return -a;
+        }
+    }
+}
@@ -0,0 +1,84 @@
+contract Unroll {
+    function forLoop() public returns (uint256) {
+        uint256 x = 0;
+        for (uint256 i = 0; i < 3; i++) {
+            x += 2;
+        }
+        return x;
+    }
+
+    function constLoop() public returns (uint256) {
+        uint256 x = 0;
+        for (uint256 i = 0; i < 10; i++) {
+            x += 1;
+        }
+
+        x += 1;
+        return x;
+    }
+
+    function stepInBody() public returns (uint256) {
+        uint256 x = 0;
+        for (uint256 i = 1; i < 10; i++) {
+            i += 1;
+            x += 1;
+        }
+        return x;
+    }
+
+    function whileLoop() public returns (uint256) {
+        uint256 x = 20;
+        while (x > 10) {
+            x -= 1;
+        }
+        return x;
+    }
+
+    function doWhileLoop() public returns (uint256) {
+        uint256 x = 0;
+        do {
+            x += 1;
+        } while (x < 3);
+        return x;
+    }
+
+    function loopBreak() public returns (uint256) {
+        uint256 x = 0;
+        for (uint256 i = 0; i < 10; i++) {
+            if (i == 5) {
+                break;
+            }
+            x += 2;
+        }
+        return x;
+    }
+
+    function loopContinue() public returns (uint256) {
+        uint256 x = 0;
+        for (uint256 i = 0; i < 10; i++) {
+            if (i < 5) {
+                continue;
+            }
+            x += 1;
+        }
+        return x;
+    }
+
+    function nestedLoops() public returns (uint256) {
+        uint256 x = 0;
+        for (uint256 i = 0; i < 3; i++) {
+            for (uint256 j = 0; j < 2; j++) {
+                x += 1;
+            }
+        }
+        return x;
+    }
+
+    function counterAfterLoop() public returns (uint256) {
+        uint256 i = 0;
+        while (i < 4) {
+            i++;
+        }
+        return i;
+    }
+}
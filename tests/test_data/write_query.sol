@@ -0,0 +1,12 @@
+contract Counter {
+    uint256 count;
+
+    function set(uint256 x) public {
+        require(x < 10);
+        count = x;
+    }
+
+    function reset() public {
+        count = 100;
+    }
+}